@@ -0,0 +1,48 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use oai_pmh::{ListRecordsArgs, Verb};
+use serde::{Deserialize, Serialize};
+
+/// Progress of a list harvest, saved after each page so it can be resumed
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    pub endpoint: String,
+    pub verb: Verb,
+    pub args: ListRecordsArgs,
+    /// Token for the next page
+    pub resumption_token: String,
+    /// Records or headers written so far
+    pub harvested: usize,
+}
+
+impl Checkpoint {
+    /// The checkpoint at `path`, or `None` if there is none
+    pub fn load(path: &Path) -> io::Result<Option<Self>> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map(Some)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Replace the checkpoint at `path`, so an interruption never leaves half a file
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&temporary, path)
+    }
+
+    /// Remove the checkpoint at `path` once the harvest is complete
+    pub fn remove(path: &Path) -> io::Result<()> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,413 @@
+//! `oai-pmh`: harvest an OAI-PMH repository from the command line.
+//!
+//! Built with the `cli` feature: `cargo install oai-pmh --features cli`.
+mod checkpoint;
+mod output;
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand};
+use oai_pmh::client::response::{ErrorCode, ResponseError, ResumptionToken};
+use oai_pmh::provider::datestamp::Datestamp;
+use oai_pmh::provider::writer::ResponseWriter;
+use oai_pmh::{Client, Error, GetRecordArgs, ListMetadataFormatsArgs, ListRecordsArgs, Verb};
+
+use checkpoint::Checkpoint;
+use output::{Format, Output};
+
+const EXIT_CODES: &str = "\
+Exit codes:
+  0  success, including a list with no matching records
+  1  writing output or the checkpoint failed
+  2  invalid arguments, endpoint or checkpoint
+  3  the repository answered with an OAI-PMH error
+  4  the request failed (connection, timeout)
+  5  the response was not an OAI-PMH document, e.g. an HTTP error page";
+
+#[derive(Parser)]
+#[command(version, about = "Harvest metadata from an OAI-PMH repository", after_help = EXIT_CODES)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Xml, global = true)]
+    output: Format,
+
+    /// Folder for `--output files`
+    #[arg(long, default_value = ".", global = true)]
+    dir: PathBuf,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Describe the repository (Identify)
+    Identify { endpoint: String },
+    /// List the metadata formats of the repository or one item (ListMetadataFormats)
+    Formats {
+        endpoint: String,
+        /// Only the formats available for this item
+        #[arg(long)]
+        identifier: Option<String>,
+    },
+    /// List the sets (ListSets)
+    Sets { endpoint: String },
+    /// Fetch one record (GetRecord)
+    Get {
+        endpoint: String,
+        identifier: String,
+        /// Metadata format of the record
+        #[arg(long, default_value = "oai_dc")]
+        prefix: String,
+    },
+    /// List record headers (ListIdentifiers)
+    Identifiers(Harvest),
+    /// Harvest records (ListRecords)
+    Records(Harvest),
+}
+
+#[derive(Args)]
+struct Harvest {
+    /// Base URL of the repository
+    endpoint: String,
+    /// Metadata format to harvest
+    #[arg(long, default_value = "oai_dc")]
+    prefix: String,
+    /// Only records changed on or after this datestamp
+    #[arg(long)]
+    from: Option<String>,
+    /// Only records changed on or before this datestamp
+    #[arg(long)]
+    until: Option<String>,
+    /// Only records in this set or its subsets
+    #[arg(long)]
+    set: Option<String>,
+    /// Save progress to FILE after each page, and resume from it if it exists
+    #[arg(long, value_name = "FILE")]
+    checkpoint: Option<PathBuf>,
+}
+
+impl Harvest {
+    fn args(&self) -> ListRecordsArgs {
+        let mut args = ListRecordsArgs::new(&self.prefix);
+        if let Some(from) = &self.from {
+            args = args.from(from);
+        }
+        if let Some(until) = &self.until {
+            args = args.until(until);
+        }
+        if let Some(set) = &self.set {
+            args = args.set(set);
+        }
+        args
+    }
+}
+
+enum Failure {
+    Usage(String),
+    Write(io::Error),
+    Harvest(Error),
+}
+
+impl Failure {
+    fn exit_code(&self) -> u8 {
+        match self {
+            Failure::Write(_) => 1,
+            Failure::Usage(_) => 2,
+            Failure::Harvest(error) => match error {
+                Error::InvalidEndpoint(_) | Error::UrlParse(_) => 2,
+                Error::Oai(_) => 3,
+                Error::Http(_) | Error::Transport(_) => 4,
+                Error::XmlParse(_) | Error::Xml(_) | Error::UnexpectedResponse { .. } => 5,
+                _ => 1,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Usage(msg) => write!(f, "{msg}"),
+            Failure::Write(e) => write!(f, "writing output failed: {e}"),
+            Failure::Harvest(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<Error> for Failure {
+    fn from(error: Error) -> Self {
+        Failure::Harvest(error)
+    }
+}
+
+impl From<io::Error> for Failure {
+    fn from(error: io::Error) -> Self {
+        Failure::Write(error)
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to start the async runtime");
+
+    match runtime.block_on(run(cli)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            eprintln!("oai-pmh: {failure}");
+            ExitCode::from(failure.exit_code())
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Failure> {
+    let mut output = Output::new(cli.output, cli.dir);
+    match cli.command {
+        Command::Identify { endpoint } => {
+            let response = Client::new(&endpoint)?.identify().await?;
+            let writer = writer(
+                &response.request,
+                &response.response_date,
+                [("verb", "Identify")],
+            );
+            let identify = payload(response.error, response.payload)?;
+            output.identify(&writer, &identify)?;
+        }
+        Command::Formats {
+            endpoint,
+            identifier,
+        } => {
+            let args = identifier.as_deref().map(ListMetadataFormatsArgs::new);
+            let response = Client::new(&endpoint)?.list_metadata_formats(args).await?;
+            let mut request = vec![("verb", "ListMetadataFormats")];
+            request.extend(identifier.as_deref().map(|id| ("identifier", id)));
+            let writer = writer(&response.request, &response.response_date, request);
+            let formats = payload(response.error, response.payload)?;
+            output.formats(&writer, &formats.metadata_format)?;
+        }
+        Command::Sets { endpoint } => {
+            let client = Client::new(&endpoint)?;
+            let mut pages = client.list_sets().await?;
+            let mut request = vec![("verb".to_string(), "ListSets".to_string())];
+            while let Some(page) = pages.next().await {
+                let page = page?;
+                let writer = writer(&page.request, &page.response_date, request);
+                let sets = payload(page.error, page.payload)?;
+                let token = sets.resumption_token.as_ref();
+                output.sets(&writer, &sets.set, token)?;
+                request = resumption_request(Verb::ListSets, token);
+            }
+        }
+        Command::Get {
+            endpoint,
+            identifier,
+            prefix,
+        } => {
+            let args = GetRecordArgs::new(&identifier, &prefix);
+            let response = Client::new(&endpoint)?.get_record(args).await?;
+            let writer = writer(
+                &response.request,
+                &response.response_date,
+                [
+                    ("verb", "GetRecord"),
+                    ("identifier", &identifier),
+                    ("metadataPrefix", &prefix),
+                ],
+            );
+            let record = response.into_record()?;
+            output.record(&writer, &record)?;
+        }
+        Command::Identifiers(harvest) => {
+            let mut progress = Progress::start(Verb::ListIdentifiers, &harvest)?;
+            let client = Client::new(&harvest.endpoint)?;
+            let mut pages = match progress.resumption_token() {
+                Some(token) => client.resume_list_identifiers(token).await?,
+                None => {
+                    client
+                        .list_identifiers(progress.args.clone().into())
+                        .await?
+                }
+            };
+            while let Some(page) = pages.next().await {
+                let page = page?;
+                let writer = progress.writer(&page.request, &page.response_date);
+                let Some(list) = progress.list(page.error, page.payload)? else {
+                    break;
+                };
+                let token = list.resumption_token.as_ref();
+                output.headers(&writer, &list.header, token)?;
+                output.flush()?;
+                progress.page(list.header.len(), token)?;
+            }
+            progress.finish()?;
+        }
+        Command::Records(harvest) => {
+            let mut progress = Progress::start(Verb::ListRecords, &harvest)?;
+            let client = Client::new(&harvest.endpoint)?;
+            let mut pages = match progress.resumption_token() {
+                Some(token) => client.resume_list_records(token).await?,
+                None => client.list_records(progress.args.clone()).await?,
+            };
+            while let Some(page) = pages.next().await {
+                let page = page?;
+                let writer = progress.writer(&page.request, &page.response_date);
+                let Some(list) = progress.list(page.error, page.payload)? else {
+                    break;
+                };
+                let token = list.resumption_token.as_ref();
+                output.records(&writer, &list.record, token)?;
+                output.flush()?;
+                progress.page(list.record.len(), token)?;
+            }
+            progress.finish()?;
+        }
+    }
+    output.flush()?;
+    Ok(())
+}
+
+/// A writer that reproduces a response's `request` element and date
+fn writer<K: Into<String>, V: Into<String>>(
+    base_url: &str,
+    response_date: &str,
+    request: impl IntoIterator<Item = (K, V)>,
+) -> ResponseWriter {
+    let writer = ResponseWriter::new(base_url).request(request);
+    match Datestamp::parse(response_date) {
+        Some((date, _)) => writer.response_date(date),
+        None => writer,
+    }
+}
+
+/// The payload of a response, or its OAI-PMH error
+fn payload<T>(error: Option<ResponseError>, payload: Option<T>) -> Result<T, Error> {
+    if let Some(error) = error {
+        return Err(Error::Oai(error));
+    }
+    payload.ok_or_else(|| Error::Metadata("the response has no payload".to_string()))
+}
+
+// The request arguments for the page after one ending in `token`
+fn resumption_request(verb: Verb, token: Option<&ResumptionToken>) -> Vec<(String, String)> {
+    let mut request = vec![("verb".to_string(), verb.to_string())];
+    if let Some(token) = token {
+        request.push(("resumptionToken".to_string(), token.token.clone()));
+    }
+    request
+}
+
+/// Position in a `ListIdentifiers` or `ListRecords` harvest, kept in the checkpoint file
+struct Progress {
+    checkpoint: Checkpoint,
+    path: Option<PathBuf>,
+    args: ListRecordsArgs,
+    request: Vec<(String, String)>,
+}
+
+impl Progress {
+    /// Start the harvest, or continue it from an existing checkpoint
+    fn start(verb: Verb, harvest: &Harvest) -> Result<Self, Failure> {
+        let args = harvest.args();
+        let mut checkpoint = Checkpoint {
+            endpoint: harvest.endpoint.clone(),
+            verb,
+            args: args.clone(),
+            resumption_token: String::new(),
+            harvested: 0,
+        };
+        let mut request = vec![("verb".to_string(), verb.to_string())];
+        request.extend(request_args(&args));
+
+        if let Some(path) = &harvest.checkpoint
+            && let Some(saved) = Checkpoint::load(path).map_err(|e| {
+                Failure::Usage(format!("checkpoint {} is unreadable: {e}", path.display()))
+            })?
+        {
+            if (&saved.endpoint, saved.verb, &saved.args) != (&checkpoint.endpoint, verb, &args) {
+                return Err(Failure::Usage(format!(
+                    "checkpoint {} is for a different harvest; remove it to start over",
+                    path.display()
+                )));
+            }
+            request = resumption_request(verb, None);
+            request.push((
+                "resumptionToken".to_string(),
+                saved.resumption_token.clone(),
+            ));
+            checkpoint = saved;
+        }
+
+        Ok(Self {
+            checkpoint,
+            path: harvest.checkpoint.clone(),
+            args,
+            request,
+        })
+    }
+
+    fn resumption_token(&self) -> Option<&str> {
+        Some(self.checkpoint.resumption_token.as_str()).filter(|token| !token.is_empty())
+    }
+
+    fn writer(&self, base_url: &str, response_date: &str) -> ResponseWriter {
+        writer(base_url, response_date, self.request.clone())
+    }
+
+    /// The list in a page, `None` for an empty selective harvest
+    fn list<T>(&self, error: Option<ResponseError>, list: Option<T>) -> Result<Option<T>, Error> {
+        match error {
+            Some(error) if error.code == ErrorCode::NoRecordsMatch => {
+                eprintln!("oai-pmh: no records match");
+                Ok(None)
+            }
+            error => payload(error, list).map(Some),
+        }
+    }
+
+    /// Record a written page ending in `token`
+    fn page(&mut self, count: usize, token: Option<&ResumptionToken>) -> io::Result<()> {
+        self.checkpoint.harvested += count;
+        self.request = resumption_request(self.checkpoint.verb, token);
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        match token.filter(|token| !token.token.is_empty()) {
+            Some(token) => {
+                self.checkpoint.resumption_token = token.token.clone();
+                self.checkpoint.save(path)
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn finish(&self) -> io::Result<()> {
+        match &self.path {
+            Some(path) => Checkpoint::remove(path),
+            None => Ok(()),
+        }
+    }
+}
+
+fn request_args(args: &ListRecordsArgs) -> Vec<(String, String)> {
+    let mut request = vec![(
+        "metadataPrefix".to_string(),
+        args.metadata_prefix().to_string(),
+    )];
+    let optional = [
+        ("from", args.from_datestamp()),
+        ("until", args.until_datestamp()),
+        ("set", args.set_spec()),
+    ];
+    for (name, value) in optional {
+        if let Some(value) = value {
+            request.push((name.to_string(), value.to_string()));
+        }
+    }
+    request
+}
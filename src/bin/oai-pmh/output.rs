@@ -0,0 +1,187 @@
+use std::fs;
+use std::io::{self, BufWriter, Stdout, Write};
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+use oai_pmh::client::response::{Header, Identify, MetadataFormat, Record, ResumptionToken, Set};
+use oai_pmh::provider::writer::ResponseWriter;
+use serde_json::{Map, Value, json};
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    /// OAI-PMH response documents, one per response page
+    Xml,
+    /// One JSON object per line for each record, header, set or format
+    Jsonl,
+    /// One metadata file per record in --dir; other output as XML
+    Files,
+}
+
+/// Where harvested responses are written
+pub struct Output {
+    format: Format,
+    dir: PathBuf,
+    stdout: BufWriter<Stdout>,
+}
+
+impl Output {
+    pub fn new(format: Format, dir: PathBuf) -> Self {
+        Self {
+            format,
+            dir,
+            stdout: BufWriter::new(io::stdout()),
+        }
+    }
+
+    pub fn identify(&mut self, writer: &ResponseWriter, identify: &Identify) -> io::Result<()> {
+        match self.format {
+            Format::Jsonl => self.json(&json!({
+                "repositoryName": identify.repository_name,
+                "baseURL": identify.base_url,
+                "protocolVersion": identify.protocol_version,
+                "adminEmail": identify.admin_email,
+                "earliestDatestamp": identify.earliest_datestamp,
+                "deletedRecord": identify.deleted_record,
+                "granularity": identify.granularity,
+                "compression": identify.compression,
+                "description": identify.description,
+            })),
+            _ => self.xml(&writer.identify(identify)),
+        }
+    }
+
+    pub fn formats(
+        &mut self,
+        writer: &ResponseWriter,
+        formats: &[MetadataFormat],
+    ) -> io::Result<()> {
+        match self.format {
+            Format::Jsonl => formats.iter().try_for_each(|format| {
+                self.json(&json!({
+                    "metadataPrefix": format.metadata_prefix,
+                    "schema": format.schema,
+                    "metadataNamespace": format.metadata_namespace,
+                }))
+            }),
+            _ => self.xml(&writer.list_metadata_formats(formats)),
+        }
+    }
+
+    pub fn sets(
+        &mut self,
+        writer: &ResponseWriter,
+        sets: &[Set],
+        token: Option<&ResumptionToken>,
+    ) -> io::Result<()> {
+        match self.format {
+            Format::Jsonl => sets.iter().try_for_each(|set| {
+                self.json(&json!({
+                    "setSpec": set.set_spec,
+                    "setName": set.set_name,
+                    "setDescription": set.set_description,
+                }))
+            }),
+            _ => self.xml(&writer.list_sets(sets, token)),
+        }
+    }
+
+    pub fn headers(
+        &mut self,
+        writer: &ResponseWriter,
+        headers: &[Header],
+        token: Option<&ResumptionToken>,
+    ) -> io::Result<()> {
+        match self.format {
+            Format::Jsonl => headers
+                .iter()
+                .try_for_each(|header| self.json(&Value::Object(header_json(header)))),
+            _ => self.xml(&writer.list_identifiers(headers, token)),
+        }
+    }
+
+    pub fn record(&mut self, writer: &ResponseWriter, record: &Record) -> io::Result<()> {
+        match self.format {
+            Format::Xml => self.xml(&writer.get_record(record)),
+            _ => self.records(writer, std::slice::from_ref(record), None),
+        }
+    }
+
+    pub fn records(
+        &mut self,
+        writer: &ResponseWriter,
+        records: &[Record],
+        token: Option<&ResumptionToken>,
+    ) -> io::Result<()> {
+        match self.format {
+            Format::Xml => self.xml(&writer.list_records(records, token)),
+            Format::Jsonl => records
+                .iter()
+                .try_for_each(|record| self.json(&record_json(record))),
+            Format::Files => records.iter().try_for_each(|record| self.file(record)),
+        }
+    }
+
+    /// Flush stdout, so a checkpoint is only saved once its page is written
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.stdout.flush()
+    }
+
+    fn xml(&mut self, xml: &str) -> io::Result<()> {
+        self.stdout.write_all(xml.as_bytes())
+    }
+
+    fn json(&mut self, value: &Value) -> io::Result<()> {
+        serde_json::to_writer(&mut self.stdout, value)?;
+        self.stdout.write_all(b"\n")
+    }
+
+    // `<dir>/<urlencoded identifier>.xml`; a deleted record removes its file
+    fn file(&mut self, record: &Record) -> io::Result<()> {
+        let name: String =
+            url::form_urlencoded::byte_serialize(record.header.identifier.as_bytes()).collect();
+        let path = self.dir.join(format!("{name}.xml"));
+        if record.is_deleted() {
+            return match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        fs::create_dir_all(&self.dir)?;
+        fs::write(
+            path,
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{}\n",
+                record.metadata
+            ),
+        )
+    }
+}
+
+fn header_json(header: &Header) -> Map<String, Value> {
+    let mut fields = Map::new();
+    fields.insert(
+        "identifier".to_string(),
+        Value::from(header.identifier.as_str()),
+    );
+    fields.insert(
+        "datestamp".to_string(),
+        Value::from(header.datestamp.as_str()),
+    );
+    fields.insert("setSpec".to_string(), Value::from(header.set_spec.clone()));
+    fields.insert(
+        "deleted".to_string(),
+        Value::from(header.status.as_deref() == Some("deleted")),
+    );
+    fields
+}
+
+// The header fields with `metadata` (null when deleted) and any `about` as XML strings
+fn record_json(record: &Record) -> Value {
+    let mut fields = header_json(&record.header);
+    let metadata = (!record.is_deleted()).then(|| record.metadata.clone());
+    fields.insert("metadata".to_string(), Value::from(metadata));
+    if !record.about.is_empty() {
+        fields.insert("about".to_string(), Value::from(record.about.clone()));
+    }
+    Value::Object(fields)
+}
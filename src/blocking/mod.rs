@@ -0,0 +1,160 @@
+//! A synchronous client for scripts and batch jobs that don't run an async runtime.
+//!
+//! The verbs mirror [`crate::Client`], but queries that support resumption tokens
+//! return a standard [`Iterator`] of responses rather than an async stream.
+pub(crate) mod resumable;
+pub mod transport;
+
+use crate::Verb;
+use crate::client as shared;
+use crate::client::negotiate;
+use crate::client::query::{
+    GetRecordArgs, ListIdentifiersArgs, ListMetadataFormatsArgs, ListRecordsArgs, Query,
+};
+use crate::client::response::{
+    GetRecordResponse, IdentifyResponse, ListIdentifiersResponse, ListMetadataFormatsResponse,
+    ListRecordsResponse, ListSetsResponse, MetadataFormat,
+};
+
+use crate::error::Result;
+#[cfg(feature = "formats")]
+use crate::{client::response::TypedRecord, formats};
+#[cfg(feature = "formats")]
+pub use resumable::TypedRecordIter;
+pub use resumable::{RecordIter, ResumableIter};
+use serde::Serialize;
+pub use transport::Transport;
+use url::Url;
+
+pub struct Client {
+    transport: Box<dyn Transport>,
+    endpoint: Url,
+}
+
+impl Client {
+    /// Create a client that sends requests with [`ReqwestTransport`](transport::ReqwestTransport)
+    #[cfg(feature = "reqwest")]
+    pub fn new(endpoint: &str) -> Result<Self> {
+        Self::with_transport(endpoint, transport::ReqwestTransport::default())
+    }
+
+    /// Create a client that sends requests with the given transport
+    pub fn with_transport(endpoint: &str, transport: impl Transport + 'static) -> Result<Self> {
+        let client = Self {
+            transport: Box::new(transport),
+            endpoint: shared::parse_endpoint(endpoint)?,
+        };
+        Ok(client)
+    }
+
+    pub fn get_record(&self, args: GetRecordArgs) -> Result<GetRecordResponse> {
+        let xml = self.do_query(Query::new(Verb::GetRecord, args))?;
+        let response = GetRecordResponse::new(&xml)?;
+        Ok(response)
+    }
+
+    pub fn identify(&self) -> Result<IdentifyResponse> {
+        let xml = self.do_query(Query::new(Verb::Identify, ()))?;
+        let response = IdentifyResponse::new(&xml)?;
+        Ok(response)
+    }
+
+    pub fn list_identifiers(
+        &self,
+        args: ListIdentifiersArgs,
+    ) -> Result<ResumableIter<'_, ListIdentifiersResponse>> {
+        ResumableIter::new(self, Verb::ListIdentifiers, args)
+    }
+
+    pub fn list_metadata_formats(
+        &self,
+        args: Option<ListMetadataFormatsArgs>,
+    ) -> Result<ListMetadataFormatsResponse> {
+        let xml = self.do_query(Query::new(Verb::ListMetadataFormats, args))?;
+        let response = ListMetadataFormatsResponse::new(&xml)?;
+        Ok(response)
+    }
+
+    /// Find the prefix for the first of `preferred` the repository supports
+    ///
+    /// See [`crate::Client::negotiate_format`].
+    pub fn negotiate_format(
+        &self,
+        preferred: &[&str],
+        args: Option<ListMetadataFormatsArgs>,
+    ) -> Result<Option<MetadataFormat>> {
+        let response = self.list_metadata_formats(args)?;
+        negotiate::from_response(response, preferred)
+    }
+
+    pub fn list_records(
+        &self,
+        args: ListRecordsArgs,
+    ) -> Result<ResumableIter<'_, ListRecordsResponse>> {
+        ResumableIter::new(self, Verb::ListRecords, args)
+    }
+
+    /// Like [`list_records`](Self::list_records), but yields individual records
+    pub fn records(&self, args: ListRecordsArgs) -> Result<RecordIter<'_>> {
+        let pages = ResumableIter::new(self, Verb::ListRecords, args)?;
+        Ok(RecordIter::new(pages))
+    }
+
+    /// Fetch a record in `T`'s metadata format and parse it
+    ///
+    /// An OAI-PMH error response is returned as [`Error::Oai`](crate::Error::Oai).
+    #[cfg(feature = "formats")]
+    pub fn get_record_as<T: formats::MetadataFormat>(
+        &self,
+        identifier: impl Into<String>,
+    ) -> Result<TypedRecord<T>> {
+        let args = GetRecordArgs::new(identifier, T::PREFIX);
+        self.get_record(args)?.into_record()?.into_typed()
+    }
+
+    /// Like [`records`](Self::records), but parses each record's metadata as `T`
+    #[cfg(feature = "formats")]
+    pub fn records_as<T: formats::MetadataFormat>(
+        &self,
+        args: ListRecordsArgs,
+    ) -> Result<TypedRecordIter<'_, T>> {
+        Ok(TypedRecordIter::new(self.records(args)?))
+    }
+
+    pub fn list_sets(&self) -> Result<ResumableIter<'_, ListSetsResponse>> {
+        ResumableIter::new(self, Verb::ListSets, ())
+    }
+
+    pub(crate) fn do_query<T: Serialize>(&self, query: Query<T>) -> Result<String> {
+        let request = shared::build_request(shared::build_url(&self.endpoint, query)?);
+        let response = self.transport.send(request)?;
+
+        let content_type = response.header("Content-Type").map(|s| s.to_string());
+        shared::check_body(content_type, response.body)
+    }
+}
+
+#[cfg(all(test, feature = "reqwest"))]
+mod tests {
+    use crate::blocking::Client;
+
+    #[test]
+    fn create_blocking_client_with_valid_url() {
+        let endpoint = "https://test.archivesspace.org/oai";
+        let client = Client::new(endpoint);
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn create_blocking_client_with_invalid_url() {
+        let endpoints = vec![
+            "test.archivesspace.org/oai",
+            "ftp://test.archivesspace.org/oai",
+        ];
+
+        for endpoint in endpoints {
+            let client = Client::new(endpoint);
+            assert!(client.is_err());
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::VecDeque;
+#[cfg(feature = "formats")]
+use std::marker::PhantomData;
+
+use crate::error::{Error, Result};
+use serde::Serialize;
+
+use crate::Verb;
+use crate::blocking::Client;
+use crate::client::query::{Query, ResumableArgs};
+use crate::client::response::{ErrorCode, ListRecordsResponse, Record};
+use crate::client::resumable::ResumableResponse;
+#[cfg(feature = "formats")]
+use crate::{client::response::TypedRecord, formats::MetadataFormat};
+
+/// Iterator for OAI-PMH verbs that support resumption tokens
+pub struct ResumableIter<'a, R> {
+    client: &'a Client,
+    verb: Verb,
+    current_response: Option<R>,
+    resumption_token: Option<String>,
+}
+
+impl<'a, R> ResumableIter<'a, R>
+where
+    R: ResumableResponse,
+{
+    pub(crate) fn new<Args>(client: &'a Client, verb: Verb, args: Args) -> Result<Self>
+    where
+        Args: Serialize,
+    {
+        let xml = client.do_query(Query::new(verb, args))?;
+        let response = R::from_xml(&xml)?;
+        let resumption_token = response.resumption_token();
+
+        Ok(Self {
+            client,
+            verb,
+            current_response: Some(response),
+            resumption_token,
+        })
+    }
+
+    fn fetch_next(&mut self) -> Result<R> {
+        let token = self.resumption_token.take().expect("called without token");
+
+        let xml = self
+            .client
+            .do_query(Query::new(self.verb, ResumableArgs::new(token)))?;
+
+        let response = R::from_xml(&xml)?;
+        self.resumption_token = response.resumption_token();
+
+        Ok(response)
+    }
+}
+
+impl<R> Iterator for ResumableIter<'_, R>
+where
+    R: ResumableResponse,
+{
+    type Item = Result<R>;
+
+    /// Returns the next response, or None if out.
+    fn next(&mut self) -> Option<Self::Item> {
+        // Return buffered response if we have one
+        if let Some(response) = self.current_response.take() {
+            return Some(Ok(response));
+        }
+
+        // Fetch next page if we have a resumption token
+        if self.resumption_token.is_some() {
+            Some(self.fetch_next())
+        } else {
+            None
+        }
+    }
+}
+
+/// Iterator over individual records across `ListRecords` pages
+///
+/// An OAI-PMH error response ends the iterator with [`Error::Oai`], except
+/// `noRecordsMatch`, which simply yields no records.
+pub struct RecordIter<'a> {
+    pages: ResumableIter<'a, ListRecordsResponse>,
+    records: VecDeque<Record>,
+}
+
+impl<'a> RecordIter<'a> {
+    pub(crate) fn new(pages: ResumableIter<'a, ListRecordsResponse>) -> Self {
+        Self {
+            pages,
+            records: VecDeque::new(),
+        }
+    }
+}
+
+impl Iterator for RecordIter<'_> {
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.records.pop_front() {
+                return Some(Ok(record));
+            }
+
+            let response = match self.pages.next()? {
+                Ok(response) => response,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if let Some(error) = response.error {
+                if error.code == ErrorCode::NoRecordsMatch {
+                    return None;
+                }
+                return Some(Err(Error::Oai(error)));
+            }
+
+            if let Some(payload) = response.payload {
+                self.records.extend(payload.record);
+            }
+        }
+    }
+}
+
+/// Iterator over records parsed into a [`MetadataFormat`]
+///
+/// A record that fails to parse yields [`Error::Record`]; iteration can
+/// continue with the next record.
+#[cfg(feature = "formats")]
+pub struct TypedRecordIter<'a, T> {
+    records: RecordIter<'a>,
+    format: PhantomData<T>,
+}
+
+#[cfg(feature = "formats")]
+impl<'a, T: MetadataFormat> TypedRecordIter<'a, T> {
+    pub(crate) fn new(records: RecordIter<'a>) -> Self {
+        Self {
+            records,
+            format: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "formats")]
+impl<T: MetadataFormat> Iterator for TypedRecordIter<'_, T> {
+    type Item = Result<TypedRecord<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.records.next()?;
+        Some(record.and_then(Record::into_typed))
+    }
+}
@@ -0,0 +1,68 @@
+//! The synchronous counterpart of [`crate::client::transport`].
+#[cfg(feature = "provider")]
+use crate::client::transport::ProviderTransport;
+use crate::client::transport::{HttpRequest, HttpResponse, MemoryTransport};
+use crate::error::Result;
+#[cfg(feature = "provider")]
+use crate::provider::Repository;
+
+/// Sends OAI-PMH requests and returns the raw responses, blocking the current thread
+pub trait Transport: Send + Sync {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        self.respond(request)
+    }
+}
+
+#[cfg(feature = "provider")]
+impl<R: Repository + Send + Sync> Transport for ProviderTransport<R> {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        Ok(self.respond(request))
+    }
+}
+
+/// The default blocking transport, backed by [`reqwest::blocking::Client`]
+#[cfg(feature = "reqwest")]
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestTransport {
+    pub fn new(client: reqwest::blocking::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Transport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        use crate::client::transport::{Method, collect_headers};
+
+        let mut builder = match request.method {
+            Method::Get => self.client.get(&request.url),
+            Method::Post => self.client.post(&request.url),
+        };
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        let response = builder.send()?;
+        let status = response.status().as_u16();
+        let headers = collect_headers(response.headers());
+        let body = response.text()?;
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
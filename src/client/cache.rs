@@ -0,0 +1,378 @@
+//! Optional response caching for [`Client`](crate::Client).
+//!
+//! Only `Identify`, `ListMetadataFormats` and `ListSets` are cached: these change
+//! rarely and are often requested repeatedly. A `ListSets` response is only cached
+//! when it is the complete list, since a resumption token would likely have expired
+//! by the time it was replayed, and responses carrying an OAI-PMH error are never
+//! cached. Once an entry's TTL has passed it is revalidated with
+//! `If-None-Match`/`If-Modified-Since` when the server supplied an `ETag` or
+//! `Last-Modified` header, and a `304 Not Modified` reply refreshes the entry and
+//! its validators.
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use crate::Verb;
+use crate::client::transport::fnv1a;
+use crate::error::Result;
+
+const CACHEABLE: [Verb; 3] = [Verb::Identify, Verb::ListMetadataFormats, Verb::ListSets];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedResponse {
+    pub body: String,
+    pub content_type: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub stored_at: SystemTime,
+}
+
+impl CachedResponse {
+    fn age(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.stored_at)
+            .unwrap_or_default()
+    }
+
+    fn has_validators(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+}
+
+/// Storage backend for cached responses, keyed by request url
+pub trait CacheStore: Send + Sync {
+    /// Returns the entry for `key`, or None if missing (or unreadable)
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+    fn put(&self, key: &str, response: CachedResponse) -> Result<()>;
+}
+
+/// Cache configuration: a store plus a TTL per cacheable verb
+pub struct ResponseCache {
+    store: Box<dyn CacheStore>,
+    ttls: Vec<(Verb, Duration)>,
+}
+
+impl ResponseCache {
+    /// Create a cache with a one hour TTL for each cacheable verb
+    pub fn new(store: impl CacheStore + 'static) -> Self {
+        Self {
+            store: Box::new(store),
+            ttls: CACHEABLE
+                .iter()
+                .map(|verb| (*verb, Duration::from_secs(3600)))
+                .collect(),
+        }
+    }
+
+    /// Set the TTL for `verb`. Verbs other than `Identify`, `ListMetadataFormats`
+    /// and `ListSets` are never cached, so their TTLs are ignored.
+    pub fn ttl(mut self, verb: Verb, ttl: Duration) -> Self {
+        if let Some(entry) = self.ttls.iter_mut().find(|(v, _)| *v == verb) {
+            entry.1 = ttl;
+        }
+        self
+    }
+
+    pub(crate) fn ttl_for(&self, verb: Verb) -> Option<Duration> {
+        self.ttls
+            .iter()
+            .find(|(v, _)| *v == verb)
+            .map(|(_, ttl)| *ttl)
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.store.get(key)
+    }
+
+    pub(crate) fn put(&self, key: &str, response: CachedResponse) -> Result<()> {
+        self.store.put(key, response)
+    }
+
+    pub(crate) fn is_fresh(response: &CachedResponse, ttl: Duration) -> bool {
+        response.age() < ttl
+    }
+
+    pub(crate) fn can_revalidate(response: &CachedResponse) -> bool {
+        response.has_validators()
+    }
+}
+
+// Whether a response body may be stored: not an OAI-PMH error (often transient,
+// e.g. `badArgument`) and not one page of an incomplete list
+pub(crate) fn is_storable(body: &str) -> bool {
+    let mut reader = Reader::from_str(body);
+    // Depth of the open element: 0 for `<OAI-PMH>`, 1 for `<error>` or `<ListSets>`
+    let mut depth = 0usize;
+    let mut in_token = false;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                match (depth, e.local_name().as_ref()) {
+                    (1, b"error") => return false,
+                    (2, b"resumptionToken") => in_token = true,
+                    _ => {}
+                }
+                depth += 1;
+            }
+            Ok(Event::Empty(e)) if depth == 1 && e.local_name().as_ref() == b"error" => {
+                return false;
+            }
+            Ok(Event::End(_)) => {
+                depth = depth.saturating_sub(1);
+                in_token = false;
+            }
+            Ok(Event::Text(e)) if in_token && !e.iter().all(u8::is_ascii_whitespace) => {
+                return false;
+            }
+            Ok(Event::CData(_) | Event::GeneralRef(_)) if in_token => return false,
+            Ok(Event::Eof) => return true,
+            Err(_) => return false,
+            _ => {}
+        }
+    }
+}
+
+/// In-memory store that evicts the least recently used entry when full
+pub struct MemoryCache {
+    capacity: usize,
+    entries: Mutex<LruEntries>,
+}
+
+#[derive(Default)]
+struct LruEntries {
+    tick: u64,
+    map: HashMap<String, (u64, CachedResponse)>,
+}
+
+impl MemoryCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(LruEntries::default()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("poisoned").map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl CacheStore for MemoryCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut entries = self.entries.lock().expect("poisoned");
+        entries.tick += 1;
+        let tick = entries.tick;
+
+        entries.map.get_mut(key).map(|(used, response)| {
+            *used = tick;
+            response.clone()
+        })
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) -> Result<()> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+
+        let mut entries = self.entries.lock().expect("poisoned");
+        entries.tick += 1;
+        let tick = entries.tick;
+
+        if !entries.map.contains_key(key) && entries.map.len() >= self.capacity {
+            let oldest = entries
+                .map
+                .iter()
+                .min_by_key(|(_, (used, _))| *used)
+                .map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                entries.map.remove(&oldest);
+            }
+        }
+
+        entries.map.insert(key.to_string(), (tick, response));
+        Ok(())
+    }
+}
+
+/// On-disk store with one file per entry, named by a hash of the key
+///
+/// Each file holds the key, the stored time, the validators and content type,
+/// followed by a blank line and the response body.
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.cache", fnv1a(key.as_bytes())))
+    }
+}
+
+impl CacheStore for DiskCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let contents = fs::read_to_string(self.path_for(key)).ok()?;
+        let (head, body) = contents.split_once("\n\n")?;
+
+        let mut lines = head.lines();
+        if lines.next()? != key {
+            // Hash collision
+            return None;
+        }
+
+        let stored_at = UNIX_EPOCH + Duration::from_secs(lines.next()?.parse().ok()?);
+        let mut response = CachedResponse {
+            body: body.to_string(),
+            content_type: None,
+            etag: None,
+            last_modified: None,
+            stored_at,
+        };
+
+        for line in lines {
+            match line.split_once(": ")? {
+                ("content-type", v) => response.content_type = Some(v.to_string()),
+                ("etag", v) => response.etag = Some(v.to_string()),
+                ("last-modified", v) => response.last_modified = Some(v.to_string()),
+                _ => {}
+            }
+        }
+
+        Some(response)
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let stored_at = response
+            .stored_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut contents = format!("{key}\n{stored_at}\n");
+        let headers = [
+            ("content-type", &response.content_type),
+            ("etag", &response.etag),
+            ("last-modified", &response.last_modified),
+        ];
+        for (name, value) in headers {
+            if let Some(value) = value {
+                contents.push_str(&format!("{name}: {value}\n"));
+            }
+        }
+        contents.push('\n');
+        contents.push_str(&response.body);
+
+        fs::write(self.path_for(key), contents)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(body: &str) -> CachedResponse {
+        CachedResponse {
+            body: body.to_string(),
+            content_type: Some("text/xml".to_string()),
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            stored_at: UNIX_EPOCH + Duration::from_secs(1_764_000_000),
+        }
+    }
+
+    #[test]
+    fn memory_cache_evicts_least_recently_used() {
+        let cache = MemoryCache::new(2);
+        cache.put("a", response("a")).unwrap();
+        cache.put("b", response("b")).unwrap();
+
+        // Touch "a" so "b" becomes the eviction candidate
+        assert!(cache.get("a").is_some());
+        cache.put("c", response("c")).unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+    }
+
+    #[test]
+    fn memory_cache_with_zero_capacity_stores_nothing() {
+        let cache = MemoryCache::new(0);
+        cache.put("a", response("a")).unwrap();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn disk_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("oai-pmh-cache-{}", std::process::id()));
+        let cache = DiskCache::new(&dir);
+        let key = "https://test.archivesspace.org/oai?verb=Identify";
+
+        assert!(cache.get(key).is_none());
+        cache
+            .put(key, response("<?xml version=\"1.0\"?>\n\n<OAI-PMH/>"))
+            .unwrap();
+        assert_eq!(
+            cache.get(key),
+            Some(response("<?xml version=\"1.0\"?>\n\n<OAI-PMH/>"))
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn storable_responses() {
+        let sets = |token: &str| {
+            format!(
+                "<OAI-PMH><ListSets><set><setSpec>a</setSpec></set>{token}</ListSets></OAI-PMH>"
+            )
+        };
+        assert!(is_storable(&sets("")));
+        assert!(is_storable(&sets("<resumptionToken/>")));
+        assert!(is_storable(&sets(
+            r#"<resumptionToken completeListSize="1"> </resumptionToken>"#
+        )));
+        assert!(!is_storable(&sets(
+            "<resumptionToken>page2</resumptionToken>"
+        )));
+
+        assert!(!is_storable(
+            r#"<OAI-PMH><error code="noSetHierarchy">No sets</error></OAI-PMH>"#
+        ));
+        assert!(!is_storable(
+            r#"<OAI-PMH><error code="badArgument"/></OAI-PMH>"#
+        ));
+        assert!(is_storable(
+            "<OAI-PMH><Identify><description><error>in a description</error></description></Identify></OAI-PMH>"
+        ));
+    }
+
+    #[test]
+    fn ttl_is_ignored_for_uncacheable_verbs() {
+        let cache = ResponseCache::new(MemoryCache::new(1))
+            .ttl(Verb::Identify, Duration::from_secs(60))
+            .ttl(Verb::ListRecords, Duration::from_secs(60));
+
+        assert_eq!(cache.ttl_for(Verb::Identify), Some(Duration::from_secs(60)));
+        assert_eq!(cache.ttl_for(Verb::ListRecords), None);
+        assert_eq!(cache.ttl_for(Verb::GetRecord), None);
+    }
+}
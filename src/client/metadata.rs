@@ -1,47 +1,201 @@
-use once_cell::sync::Lazy;
-use regex::Regex;
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
 
-// Match content between <metadata> and </metadata> tags
-// Using non-greedy match (.*?) to handle multiple metadata elements
-// (?s) flag makes . match newlines (dotall mode)
-static METADATA_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?s)<metadata>(.*?)</metadata>").unwrap());
+/// `(prefix, uri)` pairs, with an empty prefix for the default namespace
+type Declarations = Vec<(String, String)>;
 
 /// Extract all metadata elements from an OAI-PMH response
 ///
 /// Works for both GetRecord (single record) and ListRecords (multiple records).
-/// Returns a vector of metadata XML strings in document order.
+/// Returns a vector of metadata XML strings in document order; records without
+/// metadata (deleted records) are skipped, see [`extract_record_metadata`].
 ///
-/// Uses regex pattern matching to extract content between `<metadata>` and `</metadata>` tags.
-/// This is simpler than full XML parsing and works reliably for well-formed OAI-PMH responses.
+/// Each fragment is a standalone document: namespace declarations in scope at
+/// `<metadata>` (e.g. made on the `<OAI-PMH>` root) are copied onto the fragment's
+/// root element, unless it redeclares them.
 ///
 /// # Example
 /// ```
 /// use oai_pmh::client::metadata::extract_metadata;
 ///
 /// let xml = r#"
-/// <OAI-PMH>
+/// <OAI-PMH xmlns:dc="http://purl.org/dc/elements/1.1/">
 ///   <ListRecords>
 ///     <record>
 ///       <header><identifier>id1</identifier></header>
-///       <metadata><dc>content1</dc></metadata>
+///       <metadata><dc:title>content1</dc:title></metadata>
 ///     </record>
 ///     <record>
 ///       <header><identifier>id2</identifier></header>
-///       <metadata><dc>content2</dc></metadata>
+///       <metadata><dc:title>content2</dc:title></metadata>
 ///     </record>
 ///   </ListRecords>
 /// </OAI-PMH>"#;
 ///
 /// let results = extract_metadata(xml);
 /// assert_eq!(results.len(), 2);
-/// assert!(results[0].contains("content1"));
+/// assert_eq!(
+///     results[0],
+///     r#"<dc:title xmlns:dc="http://purl.org/dc/elements/1.1/">content1</dc:title>"#
+/// );
 /// assert!(results[1].contains("content2"));
 /// ```
 pub fn extract_metadata(xml: &str) -> Vec<String> {
-    METADATA_RE
-        .captures_iter(xml)
-        .filter_map(|cap| cap.get(1).map(|m| m.as_str().to_string()))
+    extract_record_metadata(xml).into_iter().flatten().collect()
+}
+
+/// Extract the metadata of each `<record>`, in document order
+///
+/// Unlike [`extract_metadata`], records without a `<metadata>` element yield `None`,
+/// so the result lines up with the records in the response. Extraction stops at
+/// the first XML syntax error.
+pub fn extract_record_metadata(xml: &str) -> Vec<Option<String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut records = Vec::new();
+
+    // Local names and namespace declarations of the open elements
+    let mut names: Vec<String> = Vec::new();
+    let mut scopes: Vec<Declarations> = Vec::new();
+    let mut fragment: Option<Fragment> = None;
+
+    loop {
+        let position = reader.buffer_position() as usize;
+        let event = match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(event) => event,
+        };
+
+        // Inside <metadata> only the fragment's bounds matter
+        if let Some(f) = fragment.as_mut() {
+            match &event {
+                Event::Start(e) | Event::Empty(e) if f.root.is_none() => {
+                    f.root = Some(Root {
+                        offset: position,
+                        name_len: e.name().as_ref().len(),
+                        declared: declarations(e),
+                    });
+                    if matches!(event, Event::Start(_)) {
+                        f.depth += 1;
+                    }
+                }
+                Event::Start(_) => f.depth += 1,
+                Event::End(_) if f.depth > 0 => f.depth -= 1,
+                Event::End(_) => {
+                    let f = fragment.take().expect("inside fragment");
+                    if let Some(metadata) = records.last_mut() {
+                        *metadata = Some(f.build(xml, position, &scopes));
+                    }
+                    names.pop();
+                    scopes.pop();
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(e) => {
+                let name = local_name(&e);
+                let parent = names.last().map(|n| n.as_str());
+                match (parent, name.as_str()) {
+                    (Some("GetRecord" | "ListRecords"), "record") => records.push(None),
+                    (Some("record"), "metadata") => {
+                        fragment = Some(Fragment {
+                            start: reader.buffer_position() as usize,
+                            root: None,
+                            depth: 0,
+                        });
+                    }
+                    _ => {}
+                }
+                names.push(name);
+                scopes.push(declarations(&e));
+            }
+            Event::Empty(e) => {
+                let parent = names.last().map(|n| n.as_str());
+                if matches!(parent, Some("GetRecord" | "ListRecords")) && local_name(&e) == "record"
+                {
+                    records.push(None);
+                }
+            }
+            Event::End(_) => {
+                names.pop();
+                scopes.pop();
+            }
+            _ => {}
+        }
+    }
+
+    records
+}
+
+/// Content of a `<metadata>` element being scanned
+struct Fragment {
+    /// Offset just after `<metadata>`
+    start: usize,
+    root: Option<Root>,
+    /// Depth of open elements within the fragment
+    depth: usize,
+}
+
+/// The fragment's root element
+struct Root {
+    /// Offset of its `<`
+    offset: usize,
+    name_len: usize,
+    declared: Declarations,
+}
+
+impl Fragment {
+    /// The content up to `end`, with inherited declarations added to the root
+    fn build(self, xml: &str, end: usize, scopes: &[Declarations]) -> String {
+        let Some(root) = self.root else {
+            return xml[self.start..end].to_string();
+        };
+
+        // Innermost declarations win
+        let mut inherited: Vec<(&str, &str)> = Vec::new();
+        for (prefix, uri) in scopes.iter().rev().flat_map(|scope| scope.iter().rev()) {
+            let redeclared = root.declared.iter().any(|(p, _)| p == prefix);
+            if !redeclared && !inherited.iter().any(|(p, _)| p == prefix) {
+                inherited.push((prefix, uri));
+            }
+        }
+        inherited.reverse();
+
+        let insert_at = root.offset + 1 + root.name_len;
+        let mut fragment = String::with_capacity(end - self.start + 64);
+        fragment.push_str(&xml[self.start..insert_at]);
+        for (prefix, uri) in inherited {
+            match prefix {
+                "" => fragment.push_str(&format!(" xmlns=\"{uri}\"")),
+                p => fragment.push_str(&format!(" xmlns:{p}=\"{uri}\"")),
+            }
+        }
+        fragment.push_str(&xml[insert_at..end]);
+        fragment
+    }
+}
+
+fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).into_owned()
+}
+
+/// Namespace declarations on a start tag, with values left escaped as written
+fn declarations(e: &BytesStart) -> Declarations {
+    e.attributes()
+        .flatten()
+        .filter_map(|attr| {
+            let key = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+            let prefix = match key.strip_prefix("xmlns") {
+                Some("") => String::new(),
+                Some(rest) => rest.strip_prefix(':')?.to_string(),
+                None => return None,
+            };
+            // Re-quoted with double quotes when copied
+            let uri = String::from_utf8_lossy(&attr.value).replace('"', "&quot;");
+            Some((prefix, uri))
+        })
         .collect()
 }
 
@@ -116,4 +270,42 @@ mod tests {
         assert!(results[0].contains("First"));
         assert!(results[1].contains("Second"));
     }
+
+    #[test]
+    fn test_extract_metadata_copies_inherited_namespaces() {
+        let xml = std::fs::read_to_string("tests/fixtures/list_records_root_ns.xml")
+            .expect("Failed to load fixture");
+
+        let results = extract_record_metadata(&xml);
+        assert_eq!(results.len(), 3);
+        assert!(results[1].is_none());
+
+        let first = results[0].as_deref().unwrap();
+        assert!(first.trim_start().starts_with(
+            r#"<oai_dc:dc xmlns="http://www.openarchives.org/OAI/2.0/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/" xsi:schemaLocation="#
+        ));
+
+        // Declarations from <record> are inherited; the root's own are not repeated
+        let third = results[2].as_deref().unwrap();
+        assert!(third.contains(r#"xmlns:dcterms="http://purl.org/dc/terms/""#));
+        assert_eq!(third.matches("xmlns:dc=").count(), 1);
+
+        #[cfg(feature = "xml")]
+        for fragment in results.iter().flatten() {
+            crate::xml::Element::parse(fragment).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_extract_metadata_ignores_nested_record_elements() {
+        let xml = r#"<OAI-PMH><GetRecord><record><header/><metadata><collection><record><leader/></record></collection></metadata><about><metadata/></about></record></GetRecord></OAI-PMH>"#;
+
+        let results = extract_record_metadata(xml);
+        assert_eq!(
+            results,
+            vec![Some(
+                "<collection><record><leader/></record></collection>".to_string()
+            )]
+        );
+    }
 }
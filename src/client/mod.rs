@@ -1,17 +1,30 @@
+pub mod cache;
 pub mod metadata;
+pub mod negotiate;
 pub mod query;
 pub mod response;
 pub(crate) mod resumable;
+pub mod sets;
+#[cfg(feature = "xml")]
+pub mod static_repository;
+pub mod transport;
+
+use std::time::{Duration, SystemTime};
 
 use crate::Verb;
+use crate::client::cache::{CachedResponse, ResponseCache};
 use crate::client::query::{
     GetRecordArgs, ListIdentifiersArgs, ListMetadataFormatsArgs, ListRecordsArgs, Query,
+    ResumableArgs,
 };
 use crate::client::response::{
     GetRecordResponse, IdentifyResponse, ListIdentifiersResponse, ListMetadataFormatsResponse,
-    ListRecordsResponse, ListSetsResponse,
+    ListRecordsResponse, ListSetsResponse, MetadataFormat,
 };
-use crate::client::resumable::ResumableStream;
+use crate::client::resumable::{RecordStream, ResumableStream};
+use crate::client::transport::{HttpRequest, Transport};
+#[cfg(feature = "formats")]
+use crate::{client::response::TypedRecord, client::resumable::TypedRecordStream, formats};
 
 use crate::error::{Error, Result};
 use serde::Serialize;
@@ -21,27 +34,34 @@ const REQUIRED_SCHEME: &str = "http";
 const REQUIRED_CONTENT_TYPE: &str = "text/xml";
 
 pub struct Client {
-    client: reqwest::Client,
+    transport: Box<dyn Transport>,
     endpoint: Url,
+    cache: Option<ResponseCache>,
 }
 
 impl Client {
+    /// Create a client that sends requests with [`ReqwestTransport`](transport::ReqwestTransport)
+    #[cfg(feature = "reqwest")]
     pub fn new(endpoint: &str) -> Result<Self> {
-        let endpoint = Url::parse(endpoint)?;
-
-        if !endpoint.scheme().contains(REQUIRED_SCHEME) {
-            return Err(Error::InvalidEndpoint(format!(
-                "Endpoint must be an http or https url, given: {endpoint}"
-            )));
-        }
+        Self::with_transport(endpoint, transport::ReqwestTransport::default())
+    }
 
+    /// Create a client that sends requests with the given transport
+    pub fn with_transport(endpoint: &str, transport: impl Transport + 'static) -> Result<Self> {
         let client = Self {
-            client: reqwest::Client::new(),
-            endpoint,
+            transport: Box::new(transport),
+            endpoint: parse_endpoint(endpoint)?,
+            cache: None,
         };
         Ok(client)
     }
 
+    /// Cache `Identify`, `ListMetadataFormats` and `ListSets` responses
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
     pub async fn get_record(&self, args: GetRecordArgs) -> Result<GetRecordResponse> {
         let xml = self.do_query(Query::new(Verb::GetRecord, args)).await?;
         let response = GetRecordResponse::new(&xml)?;
@@ -61,6 +81,15 @@ impl Client {
         ResumableStream::new(self, Verb::ListIdentifiers, args).await
     }
 
+    /// Continue a `ListIdentifiers` list from a resumption token, e.g. one saved by
+    /// an interrupted harvest
+    pub async fn resume_list_identifiers(
+        &self,
+        resumption_token: impl Into<String>,
+    ) -> Result<ResumableStream<'_, ListIdentifiersResponse>> {
+        ResumableStream::resume(self, Verb::ListIdentifiers, resumption_token.into()).await
+    }
+
     pub async fn list_metadata_formats(
         &self,
         args: Option<ListMetadataFormatsArgs>,
@@ -72,6 +101,21 @@ impl Client {
         Ok(response)
     }
 
+    /// Find the prefix for the first of `preferred` the repository supports
+    ///
+    /// `preferred` is a ranked list of namespace or schema URIs, see
+    /// [`negotiate::select_format`]. Pass `args` to negotiate for a single item.
+    /// Returns `None` when no preference is supported; an OAI-PMH error response
+    /// (such as `noMetadataFormats`) is returned as [`Error::Oai`].
+    pub async fn negotiate_format(
+        &self,
+        preferred: &[&str],
+        args: Option<ListMetadataFormatsArgs>,
+    ) -> Result<Option<MetadataFormat>> {
+        let response = self.list_metadata_formats(args).await?;
+        negotiate::from_response(response, preferred)
+    }
+
     pub async fn list_records(
         &self,
         args: ListRecordsArgs,
@@ -79,58 +123,192 @@ impl Client {
         ResumableStream::new(self, Verb::ListRecords, args).await
     }
 
+    /// Continue a `ListRecords` list from a resumption token, e.g. one saved by an
+    /// interrupted harvest
+    pub async fn resume_list_records(
+        &self,
+        resumption_token: impl Into<String>,
+    ) -> Result<ResumableStream<'_, ListRecordsResponse>> {
+        ResumableStream::resume(self, Verb::ListRecords, resumption_token.into()).await
+    }
+
+    /// Like [`list_records`](Self::list_records), but yields individual records
+    pub async fn records(&self, args: ListRecordsArgs) -> Result<RecordStream<'_>> {
+        let pages = ResumableStream::new(self, Verb::ListRecords, args).await?;
+        Ok(RecordStream::new(pages))
+    }
+
+    /// Fetch a record in `T`'s metadata format and parse it
+    ///
+    /// An OAI-PMH error response is returned as [`Error::Oai`].
+    #[cfg(feature = "formats")]
+    pub async fn get_record_as<T: formats::MetadataFormat>(
+        &self,
+        identifier: impl Into<String>,
+    ) -> Result<TypedRecord<T>> {
+        let args = GetRecordArgs::new(identifier, T::PREFIX);
+        self.get_record(args).await?.into_record()?.into_typed()
+    }
+
+    /// Like [`records`](Self::records), but parses each record's metadata as `T`
+    ///
+    /// The request uses the prefix from `args`, usually `ListRecordsArgs::new(T::PREFIX)`,
+    /// so formats served under another prefix (e.g. `marcxml`) still work.
+    #[cfg(feature = "formats")]
+    pub async fn records_as<T: formats::MetadataFormat>(
+        &self,
+        args: ListRecordsArgs,
+    ) -> Result<TypedRecordStream<'_, T>> {
+        let records = self.records(args).await?;
+        Ok(TypedRecordStream::new(records))
+    }
+
     pub async fn list_sets(&self) -> Result<ResumableStream<'_, ListSetsResponse>> {
         ResumableStream::new(self, Verb::ListSets, ()).await
     }
 
     fn build_url<T: Serialize>(&self, query: Query<T>) -> Result<String> {
-        let query = serde_qs::to_string(&query)?;
-        let url = format!("{}?{query}", self.endpoint);
-        Ok(url)
+        build_url(&self.endpoint, query)
     }
 
-    fn truncate_body(s: &str, max_chars: usize) -> String {
-        let truncated: String = s.chars().take(max_chars).collect();
-        if truncated.len() < s.len() {
-            format!("{truncated}...")
-        } else {
-            truncated
+    pub(crate) async fn do_query<T: Serialize>(&self, query: Query<T>) -> Result<String> {
+        let verb = query.verb();
+        let url = self.build_url(query)?;
+
+        match &self.cache {
+            Some(cache) => match cache.ttl_for(verb) {
+                Some(ttl) => self.do_cached_query(cache, ttl, url).await,
+                None => self.send(build_request(url)).await,
+            },
+            None => self.send(build_request(url)).await,
         }
     }
 
-    pub(crate) async fn do_query<T: Serialize>(&self, query: Query<T>) -> Result<String> {
-        let url = self.build_url(query)?;
-        let user_agent = format!("oai-pmh-rs/{}", env!("CARGO_PKG_VERSION"));
-        let response = self
-            .client
-            .get(url)
-            .header("Accept", REQUIRED_CONTENT_TYPE)
-            .header("User-Agent", user_agent)
-            .send()
-            .await?;
+    // Resumption pages are never cached
+    pub(crate) async fn do_resumption_query(&self, verb: Verb, token: String) -> Result<String> {
+        let url = self.build_url(Query::new(verb, ResumableArgs::new(token)))?;
+        self.send(build_request(url)).await
+    }
 
-        let content_type = response
-            .headers()
-            .get(reqwest::header::CONTENT_TYPE)
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_string());
+    async fn send(&self, request: HttpRequest) -> Result<String> {
+        let response = self.transport.send(request).await?;
 
-        let body = response.text().await?;
+        let content_type = response.header("Content-Type").map(|s| s.to_string());
+        check_body(content_type, response.body)
+    }
 
-        // Check that response looks like XML
-        let trimmed = body.trim_start();
-        if !trimmed.starts_with("<?xml") {
-            return Err(Error::UnexpectedResponse {
+    async fn do_cached_query(
+        &self,
+        cache: &ResponseCache,
+        ttl: Duration,
+        url: String,
+    ) -> Result<String> {
+        let cached = cache.get(&url);
+
+        let mut request = build_request(url.clone());
+        if let Some(cached) = &cached {
+            if ResponseCache::is_fresh(cached, ttl) {
+                return Ok(cached.body.clone());
+            }
+
+            if let Some(etag) = &cached.etag {
+                request = request.header("If-None-Match", etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header("If-Modified-Since", last_modified);
+            }
+        }
+
+        let response = self.transport.send(request).await?;
+
+        if let Some(mut cached) = cached
+            && response.status == 304
+            && ResponseCache::can_revalidate(&cached)
+        {
+            cached.stored_at = SystemTime::now();
+            if let Some(etag) = response.header("ETag") {
+                cached.etag = Some(etag.to_string());
+            }
+            if let Some(last_modified) = response.header("Last-Modified") {
+                cached.last_modified = Some(last_modified.to_string());
+            }
+            let body = cached.body.clone();
+            cache.put(&url, cached)?;
+            return Ok(body);
+        }
+
+        let header = |name| response.header(name).map(|s| s.to_string());
+        let (content_type, etag, last_modified) = (
+            header("Content-Type"),
+            header("ETag"),
+            header("Last-Modified"),
+        );
+        let is_success = (200..300).contains(&response.status);
+
+        let body = check_body(content_type.clone(), response.body)?;
+        if is_success && cache::is_storable(&body) {
+            let entry = CachedResponse {
+                body: body.clone(),
                 content_type,
-                body: Self::truncate_body(&body, 200),
-            });
+                etag,
+                last_modified,
+                stored_at: SystemTime::now(),
+            };
+            cache.put(&url, entry)?;
         }
 
         Ok(body)
     }
 }
 
-#[cfg(test)]
+pub(crate) fn parse_endpoint(endpoint: &str) -> Result<Url> {
+    let endpoint = Url::parse(endpoint)?;
+
+    if !endpoint.scheme().contains(REQUIRED_SCHEME) {
+        return Err(Error::InvalidEndpoint(format!(
+            "Endpoint must be an http or https url, given: {endpoint}"
+        )));
+    }
+
+    Ok(endpoint)
+}
+
+pub(crate) fn build_url<T: Serialize>(endpoint: &Url, query: Query<T>) -> Result<String> {
+    let query = serde_qs::to_string(&query)?;
+    let url = format!("{endpoint}?{query}");
+    Ok(url)
+}
+
+pub(crate) fn build_request(url: String) -> HttpRequest {
+    let user_agent = format!("oai-pmh-rs/{}", env!("CARGO_PKG_VERSION"));
+    HttpRequest::get(url)
+        .header("Accept", REQUIRED_CONTENT_TYPE)
+        .header("User-Agent", user_agent)
+}
+
+// Check that response looks like XML
+pub(crate) fn check_body(content_type: Option<String>, body: String) -> Result<String> {
+    let trimmed = body.trim_start();
+    if !trimmed.starts_with("<?xml") {
+        return Err(Error::UnexpectedResponse {
+            content_type,
+            body: truncate_body(&body, 200),
+        });
+    }
+
+    Ok(body)
+}
+
+fn truncate_body(s: &str, max_chars: usize) -> String {
+    let truncated: String = s.chars().take(max_chars).collect();
+    if truncated.len() < s.len() {
+        format!("{truncated}...")
+    } else {
+        truncated
+    }
+}
+
+#[cfg(all(test, feature = "reqwest"))]
 mod tests {
     use url::Url;
 
@@ -0,0 +1,148 @@
+//! Choosing a `metadataPrefix` by what a format is rather than what it is called.
+//!
+//! Repositories name the same format differently (`marc21`, `marcxml`, `MARC21`), but
+//! advertise its namespace and schema in `ListMetadataFormats`. These helpers match on
+//! those URIs instead.
+use crate::client::response::{ListMetadataFormatsResponse, MetadataFormat};
+use crate::error::{Error, Result};
+
+/// Pick the format matching the highest-ranked preference
+///
+/// Each preference is compared against both `metadataNamespace` and `schema`.
+/// Comparison ignores the URL scheme, a trailing slash and ASCII case, since
+/// repositories often advertise `https://` variants of the published URIs.
+///
+/// # Example
+/// ```
+/// use oai_pmh::client::negotiate::select_format;
+/// use oai_pmh::client::response::MetadataFormat;
+///
+/// const MARCXML: &str = "http://www.loc.gov/MARC21/slim";
+/// const OAI_DC: &str = "http://www.openarchives.org/OAI/2.0/oai_dc/";
+///
+/// let formats = vec![
+///     MetadataFormat {
+///         metadata_prefix: "oai_dc".to_string(),
+///         metadata_namespace: OAI_DC.to_string(),
+///         schema: "http://www.openarchives.org/OAI/2.0/oai_dc.xsd".to_string(),
+///     },
+///     MetadataFormat {
+///         metadata_prefix: "MARC21".to_string(),
+///         metadata_namespace: "https://www.loc.gov/MARC21/slim/".to_string(),
+///         schema: "http://www.loc.gov/standards/marcxml/schema/MARC21slim.xsd".to_string(),
+///     },
+/// ];
+///
+/// let format = select_format(&formats, &[MARCXML, OAI_DC]).unwrap();
+/// assert_eq!(format.metadata_prefix, "MARC21");
+/// ```
+pub fn select_format<'a>(
+    formats: &'a [MetadataFormat],
+    preferred: &[&str],
+) -> Option<&'a MetadataFormat> {
+    preferred.iter().find_map(|uri| {
+        formats.iter().find(|format| {
+            same_uri(&format.metadata_namespace, uri) || same_uri(&format.schema, uri)
+        })
+    })
+}
+
+pub(crate) fn from_response(
+    response: ListMetadataFormatsResponse,
+    preferred: &[&str],
+) -> Result<Option<MetadataFormat>> {
+    if let Some(error) = response.error {
+        return Err(Error::Oai(error));
+    }
+    let formats = response
+        .payload
+        .map(|p| p.metadata_format)
+        .unwrap_or_default();
+    Ok(select_format(&formats, preferred).cloned())
+}
+
+fn same_uri(a: &str, b: &str) -> bool {
+    let a = normalize_uri(a);
+    !a.is_empty() && a.eq_ignore_ascii_case(normalize_uri(b))
+}
+
+fn normalize_uri(uri: &str) -> &str {
+    let uri = uri.trim();
+    let uri = uri
+        .strip_prefix("https://")
+        .or_else(|| uri.strip_prefix("http://"))
+        .unwrap_or(uri);
+    uri.trim_end_matches('/')
+}
+
+#[cfg(all(test, feature = "formats"))]
+mod tests {
+    use super::*;
+    use crate::formats::{ead, marcxml, mods, oai_dc};
+
+    fn formats() -> Vec<MetadataFormat> {
+        let xml = std::fs::read_to_string("tests/fixtures/list_metadata_formats.xml")
+            .expect("Failed to load fixture");
+        let response = ListMetadataFormatsResponse::new(&xml).unwrap();
+        response.payload.unwrap().metadata_format
+    }
+
+    #[test]
+    fn test_selects_by_namespace() {
+        let formats = formats();
+
+        let format = select_format(&formats, &[marcxml::NAMESPACE]).unwrap();
+        assert_eq!(format.metadata_prefix, "oai_marc");
+
+        let format = select_format(&formats, &[mods::NAMESPACE]).unwrap();
+        assert_eq!(format.metadata_prefix, "oai_mods");
+    }
+
+    #[test]
+    fn test_selects_by_schema() {
+        let formats = formats();
+
+        // The namespace advertised for EAD is not the EAD 2002 namespace
+        assert!(select_format(&formats, &[ead::NAMESPACE]).is_none());
+        let format = select_format(&formats, &[ead::NAMESPACE, ead::SCHEMA]).unwrap();
+        assert_eq!(format.metadata_prefix, "oai_ead");
+
+        // Advertised over https, published over http
+        let format = select_format(&formats, &[marcxml::SCHEMA]).unwrap();
+        assert_eq!(format.metadata_prefix, "oai_marc");
+    }
+
+    #[test]
+    fn test_respects_preference_order() {
+        let formats = formats();
+
+        let format = select_format(&formats, &[mods::NAMESPACE, oai_dc::NAMESPACE]).unwrap();
+        assert_eq!(format.metadata_prefix, "oai_mods");
+
+        let format = select_format(
+            &formats,
+            &["http://datacite.org/schema/kernel-4", oai_dc::NAMESPACE],
+        )
+        .unwrap();
+        assert_eq!(format.metadata_prefix, "oai_dc");
+
+        assert!(select_format(&formats, &[]).is_none());
+        assert!(select_format(&formats, &[""]).is_none());
+    }
+
+    #[test]
+    fn test_normalizes_uris() {
+        assert!(same_uri(
+            "https://www.loc.gov/mods/v3/",
+            "http://www.loc.gov/mods/v3"
+        ));
+        assert!(same_uri(
+            " http://purl.org/DC/terms/ ",
+            "http://purl.org/dc/terms/"
+        ));
+        assert!(!same_uri(
+            "http://purl.org/dc/terms/",
+            "http://purl.org/dc/"
+        ));
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::Verb;
 
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Query<T> {
     verb: Verb,
@@ -14,6 +14,10 @@ impl<T> Query<T> {
     pub fn new(verb: Verb, args: T) -> Self {
         Self { verb, args }
     }
+
+    pub(crate) fn verb(&self) -> Verb {
+        self.verb
+    }
 }
 
 macro_rules! metadata_prefix_list_args {
@@ -42,11 +46,27 @@ macro_rules! metadata_prefix_list_args {
                 self.set = Some(set.into());
                 self
             }
+
+            pub fn metadata_prefix(&self) -> &str {
+                &self.metadata_prefix
+            }
+
+            pub fn from_datestamp(&self) -> Option<&str> {
+                self.from.as_deref()
+            }
+
+            pub fn until_datestamp(&self) -> Option<&str> {
+                self.until.as_deref()
+            }
+
+            pub fn set_spec(&self) -> Option<&str> {
+                self.set.as_deref()
+            }
         }
     };
 }
 
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetRecordArgs {
     identifier: String,
@@ -60,9 +80,17 @@ impl GetRecordArgs {
             metadata_prefix: metadata_prefix.into(),
         }
     }
+
+    pub fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    pub fn metadata_prefix(&self) -> &str {
+        &self.metadata_prefix
+    }
 }
 
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListIdentifiersArgs {
     metadata_prefix: String,
@@ -72,7 +100,7 @@ pub struct ListIdentifiersArgs {
 }
 metadata_prefix_list_args!(ListIdentifiersArgs);
 
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListMetadataFormatsArgs {
     identifier: String,
@@ -84,10 +112,14 @@ impl ListMetadataFormatsArgs {
             identifier: identifier.into(),
         }
     }
+
+    pub fn identifier(&self) -> &str {
+        &self.identifier
+    }
 }
 
 // TODO: ISO8601 (consider chrono in the future for stricter from/until handling)
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListRecordsArgs {
     metadata_prefix: String,
@@ -97,7 +129,40 @@ pub struct ListRecordsArgs {
 }
 metadata_prefix_list_args!(ListRecordsArgs);
 
-#[derive(Debug, PartialEq, Deserialize, Serialize)]
+impl ListRecordsArgs {
+    /// The same selection in another metadata format
+    #[cfg(feature = "provider")]
+    pub(crate) fn with_metadata_prefix(&self, metadata_prefix: impl Into<String>) -> Self {
+        Self {
+            metadata_prefix: metadata_prefix.into(),
+            ..self.clone()
+        }
+    }
+}
+
+impl From<ListIdentifiersArgs> for ListRecordsArgs {
+    fn from(args: ListIdentifiersArgs) -> Self {
+        Self {
+            metadata_prefix: args.metadata_prefix,
+            from: args.from,
+            until: args.until,
+            set: args.set,
+        }
+    }
+}
+
+impl From<ListRecordsArgs> for ListIdentifiersArgs {
+    fn from(args: ListRecordsArgs) -> Self {
+        Self {
+            metadata_prefix: args.metadata_prefix,
+            from: args.from,
+            until: args.until,
+            set: args.set,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResumableArgs {
     resumption_token: String,
@@ -109,6 +174,10 @@ impl ResumableArgs {
             resumption_token: resumption_token.into(),
         }
     }
+
+    pub fn resumption_token(&self) -> &str {
+        &self.resumption_token
+    }
 }
 
 #[cfg(test)]
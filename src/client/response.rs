@@ -1,11 +1,17 @@
-use crate::error::Result;
+use crate::error::{Error, Result};
 use serde::Deserialize;
 use std::fmt;
 
 use crate::client::metadata;
+#[cfg(feature = "formats")]
+use crate::formats::{
+    self, datacite::DataCite, ead::Ead, marcxml::MarcRecord, mods::Mods, oai_dc::DublinCore,
+};
+#[cfg(feature = "xml")]
+use crate::xml::Element;
 
 // Response error implementation
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
 pub struct ResponseError {
     #[serde(rename = "@code")]
     pub code: ErrorCode,
@@ -89,9 +95,10 @@ impl GetRecordResponse {
     pub fn new(xml: &str) -> Result<Self> {
         let mut response: Self = quick_xml::de::from_str(xml)?;
 
-        let metadata = metadata::extract_metadata(xml)
+        let metadata = metadata::extract_record_metadata(xml)
             .into_iter()
             .next()
+            .flatten()
             .unwrap_or_default();
 
         if let Some(ref mut payload) = response.payload {
@@ -100,6 +107,16 @@ impl GetRecordResponse {
 
         Ok(response)
     }
+
+    /// The record, or the repository's error as [`Error::Oai`]
+    pub fn into_record(self) -> Result<Record> {
+        if let Some(error) = self.error {
+            return Err(Error::Oai(error));
+        }
+        self.payload
+            .map(|payload| payload.record)
+            .ok_or_else(|| Error::Metadata("GetRecord response has no record".to_string()))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -117,7 +134,7 @@ impl IdentifyResponse {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Identify {
     pub repository_name: String,
@@ -150,7 +167,8 @@ impl ListIdentifiersResponse {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListIdentifiers {
-    #[serde(rename = "header")]
+    // Empty when a page holds nothing but a resumptionToken
+    #[serde(rename = "header", default)]
     pub header: Vec<Header>,
 
     #[serde(default)]
@@ -183,11 +201,11 @@ impl ListRecordsResponse {
     pub fn new(xml: &str) -> Result<Self> {
         let mut response: Self = quick_xml::de::from_str(xml)?;
 
-        let metadata = metadata::extract_metadata(xml);
+        let metadata = metadata::extract_record_metadata(xml);
 
         if let Some(ref mut payload) = response.payload {
             for (record, meta) in payload.record.iter_mut().zip(metadata) {
-                record.metadata = meta;
+                record.metadata = meta.unwrap_or_default();
             }
         }
 
@@ -198,7 +216,8 @@ impl ListRecordsResponse {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListRecords {
-    #[serde(rename = "record")]
+    // Empty when a page holds nothing but a resumptionToken
+    #[serde(rename = "record", default)]
     pub record: Vec<Record>,
 
     #[serde(default)]
@@ -224,7 +243,7 @@ pub struct ListSets {
 }
 
 // General elements
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Header {
     pub identifier: String,
@@ -237,7 +256,7 @@ pub struct Header {
     pub set_spec: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MetadataFormat {
     pub metadata_prefix: String,
@@ -245,7 +264,7 @@ pub struct MetadataFormat {
     pub schema: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Record {
     pub header: Header,
@@ -257,9 +276,83 @@ pub struct Record {
     pub about: String,
 }
 
-#[derive(Debug, Deserialize)]
+impl Record {
+    /// Parse the metadata as Dublin Core (`oai_dc`)
+    #[cfg(feature = "formats")]
+    pub fn parse_dc(&self) -> Result<DublinCore> {
+        DublinCore::parse(&self.metadata)
+    }
+
+    /// Parse the metadata as a MARCXML record (`marc21`/`marcxml`)
+    #[cfg(feature = "formats")]
+    pub fn parse_marc(&self) -> Result<MarcRecord> {
+        MarcRecord::parse(&self.metadata)
+    }
+
+    /// Parse the metadata as DataCite (`oai_datacite` or a bare kernel resource)
+    #[cfg(feature = "formats")]
+    pub fn parse_datacite(&self) -> Result<DataCite> {
+        DataCite::parse(&self.metadata)
+    }
+
+    /// Parse the metadata as an EAD finding aid (`oai_ead`)
+    #[cfg(feature = "formats")]
+    pub fn parse_ead(&self) -> Result<Ead> {
+        Ead::parse(&self.metadata)
+    }
+
+    /// Parse the metadata as MODS (`mods`)
+    #[cfg(feature = "formats")]
+    pub fn parse_mods(&self) -> Result<Mods> {
+        Mods::parse(&self.metadata)
+    }
+
+    /// Parse the metadata into an [`xml`](crate::xml) tree for path queries
+    #[cfg(feature = "xml")]
+    pub fn metadata_tree(&self) -> Result<Element> {
+        Element::parse(&self.metadata)
+    }
+
+    /// Whether the header marks the record as deleted
+    pub fn is_deleted(&self) -> bool {
+        self.header.status.as_deref() == Some("deleted")
+    }
+
+    /// Parse the metadata as `T`, keeping the header
+    ///
+    /// Deleted records have no metadata. Parse failures are reported as
+    /// [`Error::Record`] with the record's identifier.
+    #[cfg(feature = "formats")]
+    pub fn into_typed<T: formats::MetadataFormat>(self) -> Result<TypedRecord<T>> {
+        let metadata = if self.is_deleted() {
+            None
+        } else {
+            let metadata = T::from_metadata(&self.metadata).map_err(|e| Error::Record {
+                identifier: self.header.identifier.clone(),
+                source: Box::new(e),
+            })?;
+            Some(metadata)
+        };
+
+        Ok(TypedRecord {
+            header: self.header,
+            metadata,
+        })
+    }
+}
+
+/// A record with metadata parsed into a [`MetadataFormat`](formats::MetadataFormat)
+#[cfg(feature = "formats")]
+#[derive(Debug)]
+pub struct TypedRecord<T> {
+    pub header: Header,
+    /// `None` for deleted records
+    pub metadata: Option<T>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
 pub struct ResumptionToken {
-    #[serde(rename = "$value")]
+    #[serde(rename = "$value", default)]
     pub token: String,
 
     #[serde(rename = "@expirationDate", default)]
@@ -272,7 +365,7 @@ pub struct ResumptionToken {
     pub cursor: Option<u64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Set {
     pub set_spec: String,
@@ -335,10 +428,62 @@ mod tests {
         );
         assert_eq!(payload.record.header.datestamp, "2025-11-11T14:28:08Z");
 
-        let metadata = payload.record.metadata;
+        let metadata = &payload.record.metadata;
         assert!(metadata.contains("<ead"));
         assert!(metadata.contains("xmlns=\"urn:isbn:1-931666-22-9\""));
         assert!(metadata.contains("</ead>"));
+        #[cfg(feature = "formats")]
+        assert_eq!(
+            payload.record.parse_ead().unwrap().archdesc.did.unit_id[0].value,
+            "arc.A0059"
+        );
+
+        #[cfg(feature = "xml")]
+        {
+            let tree = payload.record.metadata_tree().unwrap();
+            let ns = crate::xml::Namespaces::common();
+            assert_eq!(
+                tree.value("//ead:dsc//ead:unitid[@type='aspace_uri']", &ns)
+                    .unwrap()
+                    .as_deref(),
+                Some("/repositories/2/archival_objects/17832")
+            );
+        }
+    }
+
+    #[test]
+    fn test_list_records_root_declared_namespaces() {
+        let xml = std::fs::read_to_string("tests/fixtures/list_records_root_ns.xml")
+            .expect("Failed to load fixture");
+
+        let response = ListRecordsResponse::new(&xml).unwrap();
+        let records = response.payload.unwrap().record;
+        assert_eq!(records.len(), 3);
+
+        #[cfg(feature = "formats")]
+        assert_eq!(
+            records[0].parse_dc().unwrap().title[0].value,
+            "Tidal patterns in enclosed harbors"
+        );
+
+        // A deleted record in the middle doesn't shift metadata onto the wrong record
+        assert!(records[1].is_deleted());
+        assert!(records[1].metadata.is_empty());
+        #[cfg(feature = "formats")]
+        assert_eq!(
+            records[2].parse_dc().unwrap().title[0].value,
+            "Sediment transport & dredging"
+        );
+
+        #[cfg(feature = "xml")]
+        {
+            let tree = records[2].metadata_tree().unwrap();
+            let ns = crate::xml::Namespaces::common();
+            assert_eq!(
+                tree.value("dcterms:license", &ns).unwrap().as_deref(),
+                Some("CC BY 4.0")
+            );
+        }
     }
 
     #[test]
@@ -394,6 +539,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_list_identifiers_empty_resumption_token() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/">
+  <responseDate>2025-11-26T21:58:16Z</responseDate>
+  <request verb="ListIdentifiers">https://test.archivesspace.org</request>
+  <ListIdentifiers>
+    <header>
+      <identifier>oai:archivesspace:/repositories/2/resources/2</identifier>
+      <datestamp>2025-11-11T14:28:08Z</datestamp>
+    </header>
+    <resumptionToken completeListSize="1" cursor="0"/>
+  </ListIdentifiers>
+</OAI-PMH>"#;
+
+        let response = ListIdentifiersResponse::new(xml).unwrap();
+        let payload = response.payload.unwrap();
+
+        let token = payload.resumption_token.as_ref().unwrap();
+        assert!(token.token.is_empty());
+        assert_eq!(token.complete_list_size, Some(1));
+        assert_eq!(token.cursor, Some(0));
+    }
+
     #[test]
     fn test_list_metadata_formats_success() {
         let xml = std::fs::read_to_string("tests/fixtures/list_metadata_formats.xml")
@@ -1,12 +1,19 @@
-use crate::error::Result;
+use std::collections::VecDeque;
+#[cfg(feature = "formats")]
+use std::marker::PhantomData;
+
+use crate::error::{Error, Result};
 use serde::Serialize;
 
 use crate::Verb;
 use crate::client::Client;
-use crate::client::query::{Query, ResumableArgs};
+use crate::client::query::Query;
 use crate::client::response::{
-    ListIdentifiersResponse, ListRecordsResponse, ListSetsResponse, ResumptionToken,
+    ErrorCode, ListIdentifiersResponse, ListRecordsResponse, ListSetsResponse, Record,
+    ResumptionToken,
 };
+#[cfg(feature = "formats")]
+use crate::{client::response::TypedRecord, formats::MetadataFormat};
 
 /// Async stream for OAI-PMH verbs that support resumption tokens
 pub struct ResumableStream<'a, R> {
@@ -25,7 +32,17 @@ where
         Args: Serialize,
     {
         let xml = client.do_query(Query::new(verb, args)).await?;
-        let response = R::from_xml(&xml)?;
+        Self::starting_with(client, verb, &xml)
+    }
+
+    /// Continue a list from `token`, as the next page of an earlier stream would
+    pub(crate) async fn resume(client: &'a Client, verb: Verb, token: String) -> Result<Self> {
+        let xml = client.do_resumption_query(verb, token).await?;
+        Self::starting_with(client, verb, &xml)
+    }
+
+    fn starting_with(client: &'a Client, verb: Verb, xml: &str) -> Result<Self> {
+        let response = R::from_xml(xml)?;
         let resumption_token = response.resumption_token();
 
         Ok(Self {
@@ -39,10 +56,7 @@ where
     async fn fetch_next(&mut self) -> Result<R> {
         let token = self.resumption_token.take().expect("called without token");
 
-        let xml = self
-            .client
-            .do_query(Query::new(self.verb, ResumableArgs::new(token)))
-            .await?;
+        let xml = self.client.do_resumption_query(self.verb, token).await?;
 
         let response = R::from_xml(&xml)?;
         self.resumption_token = response.resumption_token();
@@ -66,6 +80,75 @@ where
     }
 }
 
+/// Async stream of individual records across `ListRecords` pages
+///
+/// An OAI-PMH error response ends the stream with [`Error::Oai`], except
+/// `noRecordsMatch`, which simply yields no records.
+pub struct RecordStream<'a> {
+    pages: ResumableStream<'a, ListRecordsResponse>,
+    records: VecDeque<Record>,
+}
+
+impl<'a> RecordStream<'a> {
+    pub(crate) fn new(pages: ResumableStream<'a, ListRecordsResponse>) -> Self {
+        Self {
+            pages,
+            records: VecDeque::new(),
+        }
+    }
+
+    /// Returns the next record, or None if out.
+    pub async fn next(&mut self) -> Option<Result<Record>> {
+        loop {
+            if let Some(record) = self.records.pop_front() {
+                return Some(Ok(record));
+            }
+
+            let response = match self.pages.next().await? {
+                Ok(response) => response,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if let Some(error) = response.error {
+                if error.code == ErrorCode::NoRecordsMatch {
+                    return None;
+                }
+                return Some(Err(Error::Oai(error)));
+            }
+
+            if let Some(payload) = response.payload {
+                self.records.extend(payload.record);
+            }
+        }
+    }
+}
+
+/// Async stream of records parsed into a [`MetadataFormat`]
+///
+/// A record that fails to parse yields [`Error::Record`]; the stream can be
+/// polled again to continue with the next record.
+#[cfg(feature = "formats")]
+pub struct TypedRecordStream<'a, T> {
+    records: RecordStream<'a>,
+    format: PhantomData<T>,
+}
+
+#[cfg(feature = "formats")]
+impl<'a, T: MetadataFormat> TypedRecordStream<'a, T> {
+    pub(crate) fn new(records: RecordStream<'a>) -> Self {
+        Self {
+            records,
+            format: PhantomData,
+        }
+    }
+
+    /// Returns the next record, or None if out.
+    pub async fn next(&mut self) -> Option<Result<TypedRecord<T>>> {
+        let record = self.records.next().await?;
+        Some(record.and_then(Record::into_typed))
+    }
+}
+
 /// Trait for OAI-PMH responses that support resumption tokens
 pub trait ResumableResponse: Sized {
     fn from_xml(xml: &str) -> Result<Self>;
@@ -0,0 +1,227 @@
+//! Set hierarchies.
+//!
+//! OAI-PMH nests sets by `:`-delimited setSpecs: `math:algebra` is a subset of `math`,
+//! and an item in `math:algebra` is also in `math`. [`SetTree`] arranges the sets a
+//! repository lists for navigation, and [`is_within`] and [`parent`] work on the
+//! setSpecs themselves.
+//!
+//! # Example
+//! ```no_run
+//! use oai_pmh::Client;
+//! use oai_pmh::client::sets::SetTree;
+//!
+//! # async fn example() -> oai_pmh::Result<()> {
+//! let client = Client::new("https://example.org/oai")?;
+//! let tree = SetTree::from_stream(client.list_sets().await?).await?;
+//!
+//! for set in tree.iter() {
+//!     let indent = "  ".repeat(tree.depth(&set.set_spec));
+//!     println!("{indent}{}", set.set_name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::collections::BTreeMap;
+
+use crate::client::response::{ErrorCode, ListSetsResponse, Set};
+use crate::client::resumable::ResumableStream;
+use crate::error::{Error, Result};
+
+/// The setSpec one level up, e.g. `a:b` for `a:b:c`
+pub fn parent(spec: &str) -> Option<&str> {
+    spec.rsplit_once(':').map(|(parent, _)| parent)
+}
+
+/// Whether `spec` is `set` or nested below it, i.e. an item in `spec` is also in `set`
+pub fn is_within(spec: &str, set: &str) -> bool {
+    spec.strip_prefix(set)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(':'))
+}
+
+/// The sets of a repository, arranged by their setSpec hierarchy
+///
+/// A set's parent is its nearest ancestor in the tree, so a repository listing
+/// `a` and `a:b:c` but not `a:b` has `a:b:c` as a child of `a`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SetTree {
+    sets: BTreeMap<String, Set>,
+}
+
+impl SetTree {
+    /// Collect every page of a `ListSets` stream
+    ///
+    /// A repository without sets (`noSetHierarchy`) gives an empty tree; other OAI-PMH
+    /// errors are returned as [`Error::Oai`].
+    pub async fn from_stream(mut pages: ResumableStream<'_, ListSetsResponse>) -> Result<Self> {
+        let mut sets = Vec::new();
+        while let Some(page) = pages.next().await {
+            let page = page?;
+            if let Some(error) = page.error {
+                if error.code == ErrorCode::NoSetHierarchy {
+                    break;
+                }
+                return Err(Error::Oai(error));
+            }
+            if let Some(payload) = page.payload {
+                sets.extend(payload.set);
+            }
+        }
+        Ok(sets.into_iter().collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.sets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty()
+    }
+
+    pub fn get(&self, spec: &str) -> Option<&Set> {
+        self.sets.get(spec)
+    }
+
+    /// The nearest ancestor of `spec` in the tree
+    pub fn parent(&self, spec: &str) -> Option<&Set> {
+        let mut spec = parent(spec)?;
+        loop {
+            if let Some(set) = self.sets.get(spec) {
+                return Some(set);
+            }
+            spec = parent(spec)?;
+        }
+    }
+
+    /// Ancestors of `spec` in the tree, starting at the root
+    pub fn ancestors(&self, spec: &str) -> Vec<&Set> {
+        let mut ancestors = Vec::new();
+        let mut current = spec;
+        while let Some(set) = self.parent(current) {
+            ancestors.push(set);
+            current = &set.set_spec;
+        }
+        ancestors.reverse();
+        ancestors
+    }
+
+    /// Number of ancestors of `spec` in the tree, 0 for a root
+    pub fn depth(&self, spec: &str) -> usize {
+        self.ancestors(spec).len()
+    }
+
+    /// Sets without a parent, in setSpec order
+    pub fn roots(&self) -> impl Iterator<Item = &Set> {
+        self.sets
+            .values()
+            .filter(|set| self.parent(&set.set_spec).is_none())
+    }
+
+    /// Sets whose parent is `spec`, in setSpec order
+    pub fn children<'a>(&'a self, spec: &'a str) -> impl Iterator<Item = &'a Set> {
+        self.descendants(spec).filter(move |set| {
+            self.parent(&set.set_spec)
+                .is_some_and(|parent| parent.set_spec == spec)
+        })
+    }
+
+    /// All sets nested below `spec`, in setSpec order
+    pub fn descendants<'a>(&'a self, spec: &'a str) -> impl Iterator<Item = &'a Set> {
+        self.sets
+            .range(format!("{spec}:")..)
+            .take_while(move |(s, _)| is_within(s, spec))
+            .map(|(_, set)| set)
+    }
+
+    /// All sets, depth-first with each set before its children
+    pub fn iter(&self) -> impl Iterator<Item = &Set> {
+        let mut sets = Vec::with_capacity(self.sets.len());
+        let mut stack: Vec<&Set> = self.roots().collect();
+        stack.reverse();
+        while let Some(set) = stack.pop() {
+            sets.push(set);
+            let start = stack.len();
+            stack.extend(self.children(&set.set_spec));
+            stack[start..].reverse();
+        }
+        sets.into_iter()
+    }
+}
+
+/// Later sets replace earlier ones with the same setSpec
+impl FromIterator<Set> for SetTree {
+    fn from_iter<I: IntoIterator<Item = Set>>(sets: I) -> Self {
+        Self {
+            sets: sets
+                .into_iter()
+                .map(|set| (set.set_spec.clone(), set))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(specs: &[&str]) -> SetTree {
+        specs
+            .iter()
+            .map(|spec| Set {
+                set_spec: spec.to_string(),
+                set_name: spec.to_uppercase(),
+                set_description: Vec::new(),
+            })
+            .collect()
+    }
+
+    fn specs<'a>(sets: impl IntoIterator<Item = &'a Set>) -> Vec<&'a str> {
+        sets.into_iter().map(|s| s.set_spec.as_str()).collect()
+    }
+
+    #[test]
+    fn spec_helpers() {
+        assert_eq!(parent("a:b:c"), Some("a:b"));
+        assert_eq!(parent("a"), None);
+        assert!(is_within("a:b", "a"));
+        assert!(is_within("a", "a"));
+        assert!(!is_within("ab", "a"));
+        assert!(!is_within("a", "a:b"));
+    }
+
+    #[test]
+    fn navigation() {
+        let tree = tree(&[
+            "math:algebra:linear",
+            "math",
+            "physics",
+            "math:geometry",
+            "math-ed",
+        ]);
+
+        assert_eq!(specs(tree.roots()), ["math", "math-ed", "physics"]);
+        // math:algebra is not listed, so linear algebra hangs off math
+        assert_eq!(
+            specs(tree.children("math")),
+            ["math:algebra:linear", "math:geometry"]
+        );
+        assert_eq!(tree.parent("math:algebra:linear").unwrap().set_name, "MATH");
+        assert_eq!(specs(tree.ancestors("math:geometry")), ["math"]);
+        assert_eq!(tree.depth("math:algebra:linear"), 1);
+        assert_eq!(tree.depth("physics"), 0);
+        assert!(tree.parent("physics").is_none());
+        assert_eq!(tree.get("math-ed").unwrap().set_name, "MATH-ED");
+        assert_eq!(tree.descendants("math").count(), 2);
+
+        assert_eq!(
+            specs(tree.iter()),
+            [
+                "math",
+                "math:algebra:linear",
+                "math:geometry",
+                "math-ed",
+                "physics"
+            ]
+        );
+        assert_eq!(tree.len(), 5);
+    }
+}
@@ -0,0 +1,251 @@
+//! Parsing [OAI Static Repository](https://www.openarchives.org/OAI/2.0/guidelines-static-repository.htm)
+//! documents.
+//!
+//! A static repository is a single XML file holding a repository's `Identify`
+//! description, its metadata formats and a `ListRecords` block per format. A gateway
+//! serves it as a regular OAI-PMH endpoint, see `provider::gateway::StaticGateway`
+//! with the `provider` feature. Static repositories
+//! have no sets, resumption or deletions, and use day granularity.
+//!
+//! # Example
+//! ```
+//! use oai_pmh::client::static_repository::StaticRepository;
+//!
+//! let xml = std::fs::read_to_string("tests/fixtures/static_repository.xml").unwrap();
+//! let repository = StaticRepository::parse(&xml).unwrap();
+//!
+//! assert_eq!(repository.identify.repository_name, "Demo repository");
+//! let records = &repository.records["oai_dc"];
+//! assert_eq!(records[0].header.identifier, "oai:an.oai.org:ma/mini.xml:1");
+//! ```
+use std::collections::BTreeMap;
+
+use crate::client::response::{Header, Identify, MetadataFormat, Record};
+use crate::error::{Error, Result};
+use crate::xml::Element;
+
+pub const NAMESPACE: &str = "http://www.openarchives.org/OAI/2.0/static-repository";
+
+/// A parsed static repository document
+#[derive(Clone, Debug, PartialEq)]
+pub struct StaticRepository {
+    pub identify: Identify,
+    pub metadata_formats: Vec<MetadataFormat>,
+    /// Records by metadata prefix, in document order
+    pub records: BTreeMap<String, Vec<Record>>,
+}
+
+impl StaticRepository {
+    /// Parse a static repository document
+    ///
+    /// Fails with [`Error::StaticRepository`] when required elements are missing or a
+    /// `ListRecords` block uses a prefix not listed in `ListMetadataFormats`.
+    pub fn parse(xml: &str) -> Result<Self> {
+        let root = Element::parse(xml)?;
+        if root.local_name() != "Repository" || root.namespace.as_deref() != Some(NAMESPACE) {
+            return Err(invalid(format!(
+                "expected a Repository element in {NAMESPACE}, found {}",
+                root.name
+            )));
+        }
+        let scope = root.scope(&[]);
+
+        let identify = root
+            .child("Identify")
+            .ok_or_else(|| invalid("missing Identify"))
+            .and_then(|identify| parse_identify(identify, &scope))?;
+
+        let metadata_formats = root
+            .child("ListMetadataFormats")
+            .ok_or_else(|| invalid("missing ListMetadataFormats"))?
+            .elements_named("metadataFormat")
+            .map(parse_format)
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut records = BTreeMap::new();
+        for list in root.elements_named("ListRecords") {
+            let prefix = list
+                .attr("metadataPrefix")
+                .ok_or_else(|| invalid("ListRecords without metadataPrefix"))?;
+            if !metadata_formats.iter().any(|f| f.metadata_prefix == prefix) {
+                return Err(invalid(format!(
+                    "ListRecords for undeclared format {prefix}"
+                )));
+            }
+            let scope = list.scope(&scope);
+            let list_records = list
+                .elements_named("record")
+                .map(|record| parse_record(record, &scope))
+                .collect::<Result<Vec<_>>>()?;
+            if records.insert(prefix.to_string(), list_records).is_some() {
+                return Err(invalid(format!("more than one ListRecords for {prefix}")));
+            }
+        }
+
+        Ok(Self {
+            identify,
+            metadata_formats,
+            records,
+        })
+    }
+}
+
+fn invalid(msg: impl Into<String>) -> Error {
+    Error::StaticRepository(msg.into())
+}
+
+fn required(element: &Element, name: &str) -> Result<String> {
+    element
+        .child_text(name)
+        .ok_or_else(|| invalid(format!("{} without {name}", element.local_name())))
+}
+
+// The first child element of `container` as a standalone fragment
+fn fragment(container: &Element, parent: &[(String, String)]) -> Option<String> {
+    let scope = container.scope(parent);
+    container
+        .elements()
+        .next()
+        .map(|element| element.to_xml_in_scope(&scope))
+}
+
+fn parse_identify(identify: &Element, scope: &[(String, String)]) -> Result<Identify> {
+    let scope = identify.scope(scope);
+    Ok(Identify {
+        repository_name: required(identify, "repositoryName")?,
+        base_url: required(identify, "baseURL")?,
+        protocol_version: required(identify, "protocolVersion")?,
+        admin_email: identify.child_texts("adminEmail"),
+        earliest_datestamp: required(identify, "earliestDatestamp")?,
+        deleted_record: required(identify, "deletedRecord")?,
+        granularity: required(identify, "granularity")?,
+        compression: identify.child_texts("compression"),
+        description: identify
+            .elements_named("description")
+            .filter_map(|e| fragment(e, &scope))
+            .collect(),
+    })
+}
+
+fn parse_format(format: &Element) -> Result<MetadataFormat> {
+    Ok(MetadataFormat {
+        metadata_prefix: required(format, "metadataPrefix")?,
+        schema: required(format, "schema")?,
+        metadata_namespace: required(format, "metadataNamespace")?,
+    })
+}
+
+fn parse_record(record: &Element, scope: &[(String, String)]) -> Result<Record> {
+    let scope = record.scope(scope);
+    let header = record
+        .child("header")
+        .ok_or_else(|| invalid("record without header"))?;
+    Ok(Record {
+        header: Header {
+            identifier: required(header, "identifier")?,
+            datestamp: required(header, "datestamp")?,
+            status: header.attr("status").map(str::to_string),
+            set_spec: header.child_texts("setSpec"),
+        },
+        metadata: record
+            .child("metadata")
+            .and_then(|metadata| fragment(metadata, &scope))
+            .unwrap_or_default(),
+        about: record
+            .child("about")
+            .and_then(|about| fragment(about, &scope))
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(body: &str) -> String {
+        format!(
+            r#"<Repository xmlns="http://www.openarchives.org/OAI/2.0/static-repository" xmlns:oai="http://www.openarchives.org/OAI/2.0/">
+  <Identify>
+    <oai:repositoryName>Test</oai:repositoryName>
+    <oai:baseURL>http://gateway.example.org/oai/example.org/static.xml</oai:baseURL>
+    <oai:protocolVersion>2.0</oai:protocolVersion>
+    <oai:adminEmail>admin@example.org</oai:adminEmail>
+    <oai:earliestDatestamp>2002-09-19</oai:earliestDatestamp>
+    <oai:deletedRecord>no</oai:deletedRecord>
+    <oai:granularity>YYYY-MM-DD</oai:granularity>
+  </Identify>
+  <ListMetadataFormats>
+    <oai:metadataFormat>
+      <oai:metadataPrefix>oai_dc</oai:metadataPrefix>
+      <oai:schema>http://www.openarchives.org/OAI/2.0/oai_dc.xsd</oai:schema>
+      <oai:metadataNamespace>http://www.openarchives.org/OAI/2.0/oai_dc/</oai:metadataNamespace>
+    </oai:metadataFormat>
+  </ListMetadataFormats>
+  {body}
+</Repository>"#
+        )
+    }
+
+    fn error(xml: &str) -> String {
+        match StaticRepository::parse(xml) {
+            Err(Error::StaticRepository(msg)) => msg,
+            other => panic!("expected a static repository error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_records_with_inherited_namespaces() {
+        let xml = document(
+            r#"<ListRecords metadataPrefix="oai_dc" xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <oai:record>
+      <oai:header>
+        <oai:identifier>oai:example.org:1</oai:identifier>
+        <oai:datestamp>2002-09-19</oai:datestamp>
+      </oai:header>
+      <oai:metadata>
+        <oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/"><dc:title>One</dc:title></oai_dc:dc>
+      </oai:metadata>
+    </oai:record>
+  </ListRecords>"#,
+        );
+
+        let repository = StaticRepository::parse(&xml).unwrap();
+        assert_eq!(repository.identify.admin_email, ["admin@example.org"]);
+        assert_eq!(repository.metadata_formats[0].metadata_prefix, "oai_dc");
+
+        let record = &repository.records["oai_dc"][0];
+        assert_eq!(record.header.identifier, "oai:example.org:1");
+        assert_eq!(record.header.datestamp, "2002-09-19");
+        assert!(record.metadata.contains("<dc:title>One</dc:title>"));
+        assert!(
+            record
+                .metadata
+                .contains(r#"xmlns:dc="http://purl.org/dc/elements/1.1/""#)
+        );
+    }
+
+    #[test]
+    fn rejects_undeclared_prefix() {
+        let xml = document(r#"<ListRecords metadataPrefix="marc21"/>"#);
+        assert_eq!(error(&xml), "ListRecords for undeclared format marc21");
+    }
+
+    #[test]
+    fn rejects_duplicate_lists() {
+        let xml = document(
+            r#"<ListRecords metadataPrefix="oai_dc"/><ListRecords metadataPrefix="oai_dc"/>"#,
+        );
+        assert_eq!(error(&xml), "more than one ListRecords for oai_dc");
+    }
+
+    #[test]
+    fn rejects_missing_elements() {
+        let xml = document(
+            r#"<ListRecords metadataPrefix="oai_dc"><oai:record><oai:header><oai:identifier>x</oai:identifier></oai:header></oai:record></ListRecords>"#,
+        );
+        assert_eq!(error(&xml), "header without datestamp");
+
+        let xml = r#"<OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/"/>"#;
+        assert!(error(xml).starts_with("expected a Repository element"));
+    }
+}
@@ -0,0 +1,183 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::client::transport::{
+    BoxFuture, HttpRequest, HttpResponse, Method, Transport, fnv1a, normalize_query,
+};
+use crate::error::{Error, Result};
+
+/// A transport that records responses to a directory, or replays them offline
+///
+/// In record mode every request is sent through the wrapped transport and the raw
+/// response is written to the cassette directory: the body as `<Verb>-<hash>.xml`
+/// and the request line, status and headers as `<Verb>-<hash>.http`. In replay mode
+/// the same requests are answered from those files, so a harvest (including its
+/// resumption-token chain) runs offline. Replaying a request that was not recorded
+/// fails with [`Error::Transport`].
+///
+/// Requests are matched on method and OAI-PMH arguments, not on the endpoint, so use
+/// one cassette directory per endpoint.
+///
+/// # Example
+/// ```no_run
+/// # #[cfg(feature = "reqwest")]
+/// # mod example {
+/// use oai_pmh::client::transport::{Cassette, ReqwestTransport};
+/// use oai_pmh::{Client, ListRecordsArgs, Result};
+///
+/// async fn harvest(record: bool) -> Result<()> {
+///     let cassette = if record {
+///         Cassette::record("tests/cassettes/demo", ReqwestTransport::default())
+///     } else {
+///         Cassette::replay("tests/cassettes/demo")
+///     };
+///
+///     let client = Client::with_transport("https://demo.archivesspace.org/oai", cassette)?;
+///     let mut stream = client.list_records(ListRecordsArgs::new("oai_dc")).await?;
+///     while let Some(response) = stream.next().await {
+///         println!("{:?}", response?);
+///     }
+///     Ok(())
+/// }
+/// # }
+/// ```
+pub struct Cassette {
+    dir: PathBuf,
+    transport: Option<Box<dyn Transport>>,
+}
+
+impl Cassette {
+    /// Send requests through `transport` and record each response to `dir`
+    pub fn record(dir: impl Into<PathBuf>, transport: impl Transport + 'static) -> Self {
+        Self {
+            dir: dir.into(),
+            transport: Some(Box::new(transport)),
+        }
+    }
+
+    /// Answer requests from the responses previously recorded to `dir`
+    pub fn replay(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            transport: None,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.transport.is_some()
+    }
+
+    fn save(&self, request: &HttpRequest, response: &HttpResponse) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path_for(request);
+
+        let mut meta = format!("{}\n{}\n", request_line(request), response.status);
+        for (name, value) in &response.headers {
+            meta.push_str(&format!("{name}: {value}\n"));
+        }
+
+        fs::write(path.with_extension("http"), meta)?;
+        fs::write(path.with_extension("xml"), &response.body)?;
+        Ok(())
+    }
+
+    fn load(&self, request: &HttpRequest) -> Result<HttpResponse> {
+        let path = self.path_for(request);
+        let meta_path = path.with_extension("http");
+
+        if !meta_path.exists() {
+            return Err(Error::Transport(format!(
+                "request not recorded in cassette {}: {}",
+                self.dir.display(),
+                request_line(request)
+            )));
+        }
+
+        let meta = fs::read_to_string(&meta_path)?;
+        let mut lines = meta.lines().skip(1);
+        let status = lines
+            .next()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| corrupt(&meta_path))?;
+
+        let mut response =
+            HttpResponse::new(status, fs::read_to_string(path.with_extension("xml"))?);
+        for line in lines {
+            let (name, value) = line.split_once(": ").ok_or_else(|| corrupt(&meta_path))?;
+            response = response.with_header(name, value);
+        }
+
+        Ok(response)
+    }
+
+    fn path_for(&self, request: &HttpRequest) -> PathBuf {
+        let verb = normalize_query(request.query())
+            .into_iter()
+            .find(|(k, _)| k == "verb")
+            .map(|(_, v)| v)
+            .filter(|v| v.chars().all(|c| c.is_ascii_alphanumeric()))
+            .unwrap_or_else(|| "request".to_string());
+
+        let hash = fnv1a(request_line(request).as_bytes());
+        self.dir.join(format!("{verb}-{hash:016x}"))
+    }
+}
+
+impl Transport for Cassette {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        Box::pin(async move {
+            match &self.transport {
+                Some(transport) => {
+                    let response = transport.send(request.clone()).await?;
+                    self.save(&request, &response)?;
+                    Ok(response)
+                }
+                None => self.load(&request),
+            }
+        })
+    }
+}
+
+// Method and normalized arguments, e.g. "GET metadataPrefix=oai_dc&verb=ListRecords"
+fn request_line(request: &HttpRequest) -> String {
+    let method = match request.method {
+        Method::Get => "GET",
+        Method::Post => "POST",
+    };
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(normalize_query(request.query()))
+        .finish();
+    format!("{method} {query}")
+}
+
+fn corrupt(path: &Path) -> Error {
+    Error::Transport(format!("corrupt cassette entry: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_line_is_normalized() {
+        let a = HttpRequest::get("https://example.org/oai?verb=ListRecords&metadataPrefix=oai_dc");
+        let b = HttpRequest::get("https://example.org/oai?metadataPrefix=oai_dc&verb=ListRecords");
+
+        assert_eq!(
+            request_line(&a),
+            "GET metadataPrefix=oai_dc&verb=ListRecords"
+        );
+        assert_eq!(request_line(&a), request_line(&b));
+    }
+
+    #[test]
+    fn path_is_named_by_verb_and_hash() {
+        let cassette = Cassette::replay("cassette");
+        let request = HttpRequest::get("https://example.org/oai?verb=Identify");
+
+        let path = cassette.path_for(&request);
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("Identify-"));
+        assert_eq!(name.len(), "Identify-".len() + 16);
+    }
+}
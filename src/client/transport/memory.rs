@@ -0,0 +1,108 @@
+use std::sync::Mutex;
+
+use crate::client::transport::{BoxFuture, HttpRequest, HttpResponse, Transport, normalize_query};
+use crate::error::{Error, Result};
+
+/// A transport that answers from canned responses instead of the network
+///
+/// Responses are matched on the request's OAI-PMH arguments, ignoring order and
+/// percent-encoding, so `"verb=GetRecord&identifier=oai:x:1&metadataPrefix=oai_dc"`
+/// matches whatever query string the client builds for those arguments.
+/// Requests without a canned response fail with [`Error::Transport`].
+///
+/// # Example
+/// ```
+/// use oai_pmh::Client;
+/// use oai_pmh::client::transport::MemoryTransport;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let xml = std::fs::read_to_string("tests/fixtures/identify.xml").unwrap();
+/// let transport = MemoryTransport::new().with_response("verb=Identify", xml);
+///
+/// let client = Client::with_transport("https://test.archivesspace.org/oai", transport).unwrap();
+/// let response = client.identify().await.unwrap();
+/// assert_eq!(response.payload.unwrap().repository_name, "ArchivesSpace OAI Provider");
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryTransport {
+    responses: Vec<(Vec<(String, String)>, HttpResponse)>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Respond to `query` with a `200 text/xml` response containing `body`
+    pub fn with_response(self, query: &str, body: impl Into<String>) -> Self {
+        let response = HttpResponse::new(200, body).with_header("Content-Type", "text/xml");
+        self.with_http_response(query, response)
+    }
+
+    /// Respond to `query` with an arbitrary response
+    pub fn with_http_response(mut self, query: &str, response: HttpResponse) -> Self {
+        self.responses.push((normalize_query(query), response));
+        self
+    }
+
+    /// Returns the requests sent so far, oldest first
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().expect("poisoned").clone()
+    }
+
+    pub(crate) fn respond(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let query = normalize_query(request.query());
+        self.requests.lock().expect("poisoned").push(request);
+
+        self.responses
+            .iter()
+            .find(|(q, _)| *q == query)
+            .map(|(_, response)| response.clone())
+            .ok_or_else(|| {
+                let query = url::form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(&query)
+                    .finish();
+                Error::Transport(format!("no response for request: {query}"))
+            })
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        Box::pin(async move { self.respond(request) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_queries_regardless_of_order_and_encoding() {
+        let transport = MemoryTransport::new().with_response(
+            "verb=GetRecord&identifier=oai:archivesspace:/repositories/2/resources/2&metadataPrefix=oai_ead",
+            "<?xml version=\"1.0\"?><OAI-PMH/>",
+        );
+
+        let request = HttpRequest::get(
+            "https://test.archivesspace.org/oai?metadataPrefix=oai_ead&verb=GetRecord&identifier=oai%3Aarchivesspace%3A%2Frepositories%2F2%2Fresources%2F2",
+        );
+        let response = transport.respond(request).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.header("content-type"), Some("text/xml"));
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[test]
+    fn unknown_request_is_an_error() {
+        let transport = MemoryTransport::new();
+        let request = HttpRequest::get("https://test.archivesspace.org/oai?verb=Identify");
+
+        let err = transport.respond(request).unwrap_err();
+        assert!(matches!(err, Error::Transport(_)));
+        assert_eq!(transport.requests().len(), 1);
+    }
+}
@@ -0,0 +1,202 @@
+//! The HTTP layer used by [`Client`](crate::Client).
+//!
+//! A [`Transport`] takes a fully built [`HttpRequest`] and returns the raw
+//! [`HttpResponse`]. The default, [`ReqwestTransport`], is enabled by the `reqwest`
+//! feature. [`MemoryTransport`] answers from canned responses, [`Cassette`] records
+//! and replays real responses and, with the `provider` feature, `ProviderTransport`
+//! answers from a local `Provider`; all are useful in tests.
+mod cassette;
+mod memory;
+#[cfg(feature = "provider")]
+mod provider;
+
+pub use cassette::Cassette;
+pub use memory::MemoryTransport;
+#[cfg(feature = "provider")]
+pub use provider::ProviderTransport;
+
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::error::Result;
+
+/// A boxed future returned by [`Transport::send`]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Sends OAI-PMH requests and returns the raw responses
+pub trait Transport: Send + Sync {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>>;
+}
+
+impl<T: Transport + ?Sized> Transport for std::sync::Arc<T> {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        (**self).send(request)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    pub fn get(url: impl Into<String>) -> Self {
+        Self {
+            method: Method::Get,
+            url: url.into(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// The OAI-PMH arguments: the form body for POST, otherwise the url query string.
+    pub fn query(&self) -> &str {
+        match (self.method, &self.body) {
+            (Method::Post, Some(body)) => body,
+            _ => self.url.split_once('?').map(|(_, q)| q).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Returns the first header value matching `name` (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// The default transport, backed by [`reqwest::Client`]
+#[cfg(feature = "reqwest")]
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Transport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        Box::pin(async move {
+            let mut builder = match request.method {
+                Method::Get => self.client.get(&request.url),
+                Method::Post => self.client.post(&request.url),
+            };
+            for (name, value) in &request.headers {
+                builder = builder.header(name, value);
+            }
+            if let Some(body) = request.body {
+                builder = builder.body(body);
+            }
+
+            let response = builder.send().await?;
+            let status = response.status().as_u16();
+            let headers = collect_headers(response.headers());
+            let body = response.text().await?;
+
+            Ok(HttpResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}
+
+// Decode and sort query pairs so equivalent queries compare equal
+pub(crate) fn normalize_query(query: &str) -> Vec<(String, String)> {
+    let mut pairs: Vec<(String, String)> = url::form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    pairs.sort();
+    pairs
+}
+
+// FNV-1a, stable across platforms and Rust releases (unlike `DefaultHasher`)
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(feature = "reqwest")]
+pub(crate) fn collect_headers(headers: &reqwest::header::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_query_from_url() {
+        let request = HttpRequest::get("https://test.archivesspace.org/oai?verb=Identify");
+        assert_eq!(request.query(), "verb=Identify");
+
+        let request = HttpRequest::get("https://test.archivesspace.org/oai");
+        assert_eq!(request.query(), "");
+    }
+
+    #[test]
+    fn request_query_from_form_body() {
+        let request = HttpRequest {
+            method: Method::Post,
+            url: "https://test.archivesspace.org/oai".to_string(),
+            headers: Vec::new(),
+            body: Some("verb=ListSets".to_string()),
+        };
+        assert_eq!(request.query(), "verb=ListSets");
+    }
+
+    #[test]
+    fn response_header_lookup_is_case_insensitive() {
+        let response = HttpResponse::new(200, "").with_header("Content-Type", "text/xml");
+        assert_eq!(response.header("content-type"), Some("text/xml"));
+        assert_eq!(response.header("etag"), None);
+    }
+}
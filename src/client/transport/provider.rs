@@ -0,0 +1,66 @@
+use crate::client::transport::{BoxFuture, HttpRequest, HttpResponse, Transport};
+use crate::error::Result;
+use crate::provider::{Provider, Repository};
+
+/// A transport that answers from a [`Provider`] in the same process
+///
+/// Every request is handed to [`Provider::handle_query`], so a client can harvest a
+/// [`Repository`] without an HTTP server, including resumption and error responses.
+/// Internal repository errors are answered with status `500`.
+///
+/// # Example
+/// ```
+/// use oai_pmh::Client;
+/// use oai_pmh::client::response::Identify;
+/// use oai_pmh::client::transport::ProviderTransport;
+/// use oai_pmh::provider::Provider;
+/// use oai_pmh::provider::memory::MemoryRepository;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let identify = Identify {
+///     repository_name: "Local".to_string(),
+///     base_url: "https://example.org/oai".to_string(),
+///     protocol_version: "2.0".to_string(),
+///     admin_email: vec!["admin@example.org".to_string()],
+///     earliest_datestamp: "2024-01-01".to_string(),
+///     deleted_record: "no".to_string(),
+///     granularity: "YYYY-MM-DD".to_string(),
+///     compression: Vec::new(),
+///     description: Vec::new(),
+/// };
+/// let transport = ProviderTransport::new(Provider::new(MemoryRepository::new(identify)));
+///
+/// let client = Client::with_transport("https://example.org/oai", transport).unwrap();
+/// let response = client.identify().await.unwrap();
+/// assert_eq!(response.payload.unwrap().repository_name, "Local");
+/// # }
+/// ```
+pub struct ProviderTransport<R> {
+    provider: Provider<R>,
+}
+
+impl<R: Repository> ProviderTransport<R> {
+    pub fn new(provider: Provider<R>) -> Self {
+        Self { provider }
+    }
+
+    pub fn provider(&self) -> &Provider<R> {
+        &self.provider
+    }
+
+    pub(crate) fn respond(&self, request: HttpRequest) -> HttpResponse {
+        match self.provider.handle_query(request.query()) {
+            Ok(xml) => {
+                HttpResponse::new(200, xml).with_header("Content-Type", "text/xml; charset=utf-8")
+            }
+            Err(error) => HttpResponse::new(500, error.to_string()),
+        }
+    }
+}
+
+impl<R: Repository + Send + Sync> Transport for ProviderTransport<R> {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        Box::pin(async move { Ok(self.respond(request)) })
+    }
+}
@@ -0,0 +1,148 @@
+use serde_json::{Map, Value};
+
+use crate::client::response::Record;
+use crate::crosswalk::{Crosswalk, header_fields, parse};
+use crate::error::Result;
+use crate::xml::{Element, Node};
+
+/// Any XML metadata to JSON, keeping namespaces
+///
+/// Produces `{"header": {...}, "metadata": {...}}`, with `metadata` set to `null`
+/// for deleted records. Elements are converted BadgerFish-style:
+///
+/// - child elements are keyed by their qualified name and always hold an array
+/// - attributes are keyed `@name`, and namespace declarations go in an `@xmlns`
+///   object (with `$` for the default namespace)
+/// - text content, when not just whitespace, is kept under `$`
+///
+/// # Example
+/// ```
+/// use oai_pmh::client::response::GetRecordResponse;
+/// use oai_pmh::crosswalk::{Crosswalk, XmlToJson};
+///
+/// let xml = std::fs::read_to_string("tests/fixtures/get_record.xml").unwrap();
+/// let record = GetRecordResponse::new(&xml).unwrap().into_record().unwrap();
+///
+/// let json = XmlToJson.convert(&record).unwrap();
+/// let ead = &json["metadata"]["ead"];
+/// assert_eq!(ead["@xmlns"]["$"], "urn:isbn:1-931666-22-9");
+/// assert_eq!(ead["archdesc"][0]["@level"], "collection");
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct XmlToJson;
+
+impl Crosswalk for XmlToJson {
+    fn convert(&self, record: &Record) -> Result<Value> {
+        let metadata = if record.is_deleted() {
+            Value::Null
+        } else {
+            let root = parse(record, Element::parse)?;
+            let mut metadata = Map::new();
+            metadata.insert(root.name.clone(), element(&root));
+            Value::Object(metadata)
+        };
+
+        let mut doc = Map::new();
+        doc.insert(
+            "header".to_string(),
+            Value::Object(header_fields(&record.header, "")),
+        );
+        doc.insert("metadata".to_string(), metadata);
+        Ok(Value::Object(doc))
+    }
+}
+
+fn element(element: &Element) -> Value {
+    let mut object = Map::new();
+    let mut xmlns = Map::new();
+
+    for attribute in &element.attributes {
+        if attribute.name == "xmlns" {
+            xmlns.insert("$".to_string(), Value::from(attribute.value.as_str()));
+        } else if let Some(prefix) = attribute.name.strip_prefix("xmlns:") {
+            xmlns.insert(prefix.to_string(), Value::from(attribute.value.as_str()));
+        } else {
+            object.insert(
+                format!("@{}", attribute.name),
+                Value::from(attribute.value.as_str()),
+            );
+        }
+    }
+    if !xmlns.is_empty() {
+        object.insert("@xmlns".to_string(), Value::Object(xmlns));
+    }
+
+    let mut text = String::new();
+    for node in &element.children {
+        match node {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(child) => {
+                let children = object
+                    .entry(child.name.clone())
+                    .or_insert_with(|| Value::Array(Vec::new()));
+                if let Value::Array(children) = children {
+                    children.push(self::element(child));
+                }
+            }
+        }
+    }
+    if !text.trim().is_empty() {
+        object.insert("$".to_string(), Value::from(text.trim()));
+    }
+
+    Value::Object(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::response::ListRecordsResponse;
+
+    #[test]
+    fn test_keeps_namespaces_and_structure() {
+        let xml = std::fs::read_to_string("tests/fixtures/list_records.xml")
+            .expect("Failed to load fixture");
+        let response = ListRecordsResponse::new(&xml).unwrap();
+        let record = &response.payload.unwrap().record[0];
+
+        let json = XmlToJson.convert(record).unwrap();
+        assert_eq!(
+            json["header"]["identifier"],
+            record.header.identifier.as_str()
+        );
+        assert_eq!(json["header"]["deleted"], false);
+
+        let dc = &json["metadata"]["oai_dc:dc"];
+        assert_eq!(
+            dc["@xmlns"]["oai_dc"],
+            "http://www.openarchives.org/OAI/2.0/oai_dc/"
+        );
+        let identifiers = dc["dc:identifier"].as_array().unwrap();
+        assert_eq!(identifiers.len(), 2);
+        assert_eq!(
+            identifiers[0]["@xmlns"]["dc"],
+            "http://purl.org/dc/elements/1.1/"
+        );
+        assert_eq!(
+            identifiers[0]["$"],
+            "https://test.archivesspace.org/ark:/99999/10027"
+        );
+    }
+
+    #[test]
+    fn test_converts_attributes_and_mixed_content() {
+        let root =
+            Element::parse(r#"<a xmlns="urn:a" xmlns:x="urn:x" x:id="1"> lead <b>one</b><b/></a>"#)
+                .unwrap();
+
+        assert_eq!(
+            element(&root),
+            serde_json::json!({
+                "@xmlns": { "$": "urn:a", "x": "urn:x" },
+                "@x:id": "1",
+                "b": [{ "$": "one" }, {}],
+                "$": "lead",
+            })
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Converting harvested records to JSON documents, e.g. for a search index.
+//!
+//! A [`Crosswalk`] maps a [`Record`] to a [`serde_json::Value`]. Built-in crosswalks
+//! produce Schema.org JSON-LD from Dublin Core ([`DublinCoreToSchemaOrg`]) and MARCXML
+//! ([`MarcToSchemaOrg`]), and a generic, namespace-preserving mapping from any XML
+//! ([`XmlToJson`]). All of them include the header's identifier, datestamp, setSpecs
+//! and deleted status, and deleted records convert to the header alone.
+//!
+//! Requires the `crosswalk` feature.
+mod generic;
+mod schema_org;
+
+pub use generic::XmlToJson;
+pub use schema_org::{DublinCoreToSchemaOrg, MarcToSchemaOrg};
+
+use crate::client::response::{Header, Record};
+use crate::error::{Error, Result};
+use serde_json::{Map, Value};
+
+/// Conversion from a harvested record to a JSON document
+pub trait Crosswalk {
+    fn convert(&self, record: &Record) -> Result<Value>;
+}
+
+/// Header fields under the given key prefix, e.g. `oai:` for JSON-LD
+fn header_fields(header: &Header, prefix: &str) -> Map<String, Value> {
+    let mut fields = Map::new();
+    fields.insert(
+        format!("{prefix}identifier"),
+        Value::from(header.identifier.as_str()),
+    );
+    fields.insert(
+        format!("{prefix}datestamp"),
+        Value::from(header.datestamp.as_str()),
+    );
+    fields.insert(
+        format!("{prefix}setSpec"),
+        Value::from(header.set_spec.clone()),
+    );
+    fields.insert(
+        format!("{prefix}deleted"),
+        Value::from(header.status.as_deref() == Some("deleted")),
+    );
+    fields
+}
+
+/// Attach the record identifier to metadata parse errors, as typed records do
+fn parse<T>(record: &Record, parse: impl FnOnce(&str) -> Result<T>) -> Result<T> {
+    parse(&record.metadata).map_err(|e| Error::Record {
+        identifier: record.header.identifier.clone(),
+        source: Box::new(e),
+    })
+}
+
+/// A single value as a string, several as an array, none as `None`
+fn one_or_many(mut values: Vec<Value>) -> Option<Value> {
+    match values.len() {
+        0 => None,
+        1 => values.pop(),
+        _ => Some(Value::Array(values)),
+    }
+}
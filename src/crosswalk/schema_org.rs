@@ -0,0 +1,301 @@
+use serde_json::{Map, Value, json};
+
+use crate::client::response::Record;
+use crate::crosswalk::{Crosswalk, header_fields, one_or_many, parse};
+use crate::error::Result;
+use crate::formats::marcxml::MarcRecord;
+use crate::formats::oai_dc::{self, DublinCore};
+
+const OAI_NAMESPACE: &str = "http://www.openarchives.org/OAI/2.0/";
+
+/// Dublin Core (`oai_dc`) to Schema.org `CreativeWork` JSON-LD
+///
+/// `relation` and `coverage` have no unambiguous Schema.org equivalent and are
+/// left out. Header fields use the `oai:` prefix.
+///
+/// # Example
+/// ```
+/// use oai_pmh::client::response::ListRecordsResponse;
+/// use oai_pmh::crosswalk::{Crosswalk, DublinCoreToSchemaOrg};
+///
+/// let xml = std::fs::read_to_string("tests/fixtures/list_records.xml").unwrap();
+/// let response = ListRecordsResponse::new(&xml).unwrap();
+/// let record = &response.payload.unwrap().record[0];
+///
+/// let json = DublinCoreToSchemaOrg.convert(record).unwrap();
+/// assert_eq!(json["@type"], "CreativeWork");
+/// assert_eq!(json["oai:identifier"], "oai:archivesspace:/repositories/2/archival_objects/1");
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DublinCoreToSchemaOrg;
+
+impl Crosswalk for DublinCoreToSchemaOrg {
+    fn convert(&self, record: &Record) -> Result<Value> {
+        let mut doc = document(record, "CreativeWork");
+        if record.is_deleted() {
+            return Ok(Value::Object(doc));
+        }
+
+        let dc = parse(record, DublinCore::parse)?;
+        let strings = |values: &[oai_dc::Value]| {
+            values
+                .iter()
+                .map(|v| Value::from(v.value.as_str()))
+                .collect::<Vec<_>>()
+        };
+        let named = |values: &[oai_dc::Value]| {
+            values
+                .iter()
+                .map(|v| json!({ "name": v.value }))
+                .collect::<Vec<_>>()
+        };
+
+        let mut titles = strings(&dc.title).into_iter();
+        insert(&mut doc, "name", titles.next());
+        insert(&mut doc, "alternateName", one_or_many(titles.collect()));
+        insert(&mut doc, "creator", one_or_many(named(&dc.creator)));
+        insert(&mut doc, "contributor", one_or_many(named(&dc.contributor)));
+        insert(&mut doc, "publisher", one_or_many(named(&dc.publisher)));
+        insert(&mut doc, "keywords", one_or_many(strings(&dc.subject)));
+        insert(
+            &mut doc,
+            "description",
+            one_or_many(strings(&dc.description)),
+        );
+        insert(
+            &mut doc,
+            "datePublished",
+            strings(&dc.date).into_iter().next(),
+        );
+        insert(&mut doc, "genre", one_or_many(strings(&dc.r#type)));
+        insert(&mut doc, "encodingFormat", one_or_many(strings(&dc.format)));
+        insert(&mut doc, "identifier", one_or_many(strings(&dc.identifier)));
+        insert(
+            &mut doc,
+            "url",
+            dc.identifier.iter().find_map(|v| web_url(&v.value)),
+        );
+        insert(&mut doc, "inLanguage", one_or_many(strings(&dc.language)));
+        insert(&mut doc, "isBasedOn", one_or_many(strings(&dc.source)));
+        insert(&mut doc, "license", one_or_many(strings(&dc.rights)));
+
+        Ok(Value::Object(doc))
+    }
+}
+
+/// MARCXML to Schema.org JSON-LD
+///
+/// The type is chosen from the leader (`Book`, `Map`, `MusicRecording`, `ImageObject`,
+/// otherwise `CreativeWork`). Common fields are mapped, with trailing ISBD punctuation
+/// removed: 245 (title), 1XX/7XX (creator, contributor), 260/264 (publication),
+/// 020 (ISBN), 250 (edition), 520 (summary), 650 (subjects) and 856 (URL).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MarcToSchemaOrg;
+
+impl Crosswalk for MarcToSchemaOrg {
+    fn convert(&self, record: &Record) -> Result<Value> {
+        if record.is_deleted() {
+            return Ok(Value::Object(document(record, "CreativeWork")));
+        }
+
+        let marc = parse(record, MarcRecord::parse)?;
+        let mut doc = document(record, marc_type(&marc.leader));
+
+        let title = marc.field("245").map(|f| {
+            let title = clean(f.subfield('a').unwrap_or_default());
+            match f.subfield('b') {
+                Some(sub_title) => format!("{title}: {}", clean(sub_title)),
+                None => title.to_string(),
+            }
+        });
+        insert(&mut doc, "name", title.map(Value::from));
+
+        let names = |tags: &[&str]| {
+            tags.iter()
+                .flat_map(|tag| marc.subfields(tag, 'a'))
+                .map(|name| json!({ "name": clean(name) }))
+                .collect::<Vec<_>>()
+        };
+        insert(
+            &mut doc,
+            "creator",
+            one_or_many(names(&["100", "110", "111"])),
+        );
+        insert(
+            &mut doc,
+            "contributor",
+            one_or_many(names(&["700", "710", "711"])),
+        );
+
+        let publication = marc.field("264").or_else(|| marc.field("260"));
+        insert(
+            &mut doc,
+            "publisher",
+            publication
+                .and_then(|f| f.subfield('b'))
+                .map(|name| json!({ "name": clean(name) })),
+        );
+        insert(
+            &mut doc,
+            "datePublished",
+            publication
+                .and_then(|f| f.subfield('c'))
+                .map(|date| Value::from(clean(date).trim_start_matches(['c', '©']))),
+        );
+
+        let language = marc
+            .control_field("008")
+            .and_then(|f| f.get(35..38))
+            .filter(|code| code.trim().len() == 3)
+            .or_else(|| marc.subfield("041", 'a'));
+        insert(&mut doc, "inLanguage", language.map(Value::from));
+
+        insert(
+            &mut doc,
+            "identifier",
+            marc.control_field("001").map(Value::from),
+        );
+        insert(
+            &mut doc,
+            "isbn",
+            one_or_many(
+                marc.subfields("020", 'a')
+                    .filter_map(|isbn| isbn.split_whitespace().next())
+                    .map(Value::from)
+                    .collect(),
+            ),
+        );
+        insert(
+            &mut doc,
+            "bookEdition",
+            marc.subfield("250", 'a').map(|e| Value::from(clean(e))),
+        );
+        insert(
+            &mut doc,
+            "description",
+            one_or_many(marc.subfields("520", 'a').map(Value::from).collect()),
+        );
+        insert(
+            &mut doc,
+            "about",
+            one_or_many(
+                marc.subfields("650", 'a')
+                    .map(|s| Value::from(clean(s)))
+                    .collect(),
+            ),
+        );
+        insert(
+            &mut doc,
+            "url",
+            marc.subfields("856", 'u').find_map(web_url),
+        );
+
+        Ok(Value::Object(doc))
+    }
+}
+
+fn document(record: &Record, r#type: &str) -> Map<String, Value> {
+    let mut doc = Map::new();
+    doc.insert(
+        "@context".to_string(),
+        json!({ "@vocab": "https://schema.org/", "oai": OAI_NAMESPACE }),
+    );
+    doc.insert("@type".to_string(), Value::from(r#type));
+    doc.extend(header_fields(&record.header, "oai:"));
+    doc
+}
+
+fn insert(doc: &mut Map<String, Value>, key: &str, value: Option<Value>) {
+    if let Some(value) = value {
+        doc.insert(key.to_string(), value);
+    }
+}
+
+fn web_url(value: &str) -> Option<Value> {
+    (value.starts_with("http://") || value.starts_with("https://")).then(|| Value::from(value))
+}
+
+/// Leader/06 (type of record) and /07 (bibliographic level)
+fn marc_type(leader: &str) -> &'static str {
+    let mut codes = leader.chars().skip(6);
+    match (codes.next(), codes.next()) {
+        (Some('a' | 't'), Some('m')) => "Book",
+        (Some('e' | 'f'), _) => "Map",
+        (Some('j'), _) => "MusicRecording",
+        (Some('k'), _) => "ImageObject",
+        _ => "CreativeWork",
+    }
+}
+
+/// Strip the ISBD punctuation MARC subfields end with
+fn clean(value: &str) -> &str {
+    value
+        .trim()
+        .trim_end_matches([' ', '/', ':', ';', ',', '='])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::response::ListRecordsResponse;
+
+    fn records(fixture: &str) -> Vec<Record> {
+        let xml = std::fs::read_to_string(format!("tests/fixtures/{fixture}"))
+            .expect("Failed to load fixture");
+        ListRecordsResponse::new(&xml)
+            .unwrap()
+            .payload
+            .unwrap()
+            .record
+    }
+
+    #[test]
+    fn test_dublin_core_to_schema_org() {
+        let record = &records("list_records.xml")[0];
+        let dc = record.parse_dc().unwrap();
+        let json = DublinCoreToSchemaOrg.convert(record).unwrap();
+
+        assert_eq!(json["@context"]["@vocab"], "https://schema.org/");
+        assert_eq!(json["@type"], "CreativeWork");
+        assert_eq!(json["name"], dc.title[0].value.as_str());
+        assert_eq!(json["oai:datestamp"], record.header.datestamp.as_str());
+        assert_eq!(json["oai:deleted"], false);
+        assert!(json["oai:setSpec"].is_array());
+        assert_eq!(
+            json["url"],
+            "https://test.archivesspace.org/ark:/99999/10027"
+        );
+    }
+
+    #[test]
+    fn test_marc_to_schema_org() {
+        let records = records("list_records_marc21.xml");
+        let json = MarcToSchemaOrg.convert(&records[0]).unwrap();
+
+        assert_eq!(json["@type"], "Book");
+        assert_eq!(json["oai:identifier"], "oai:catalog.example.edu:991000123");
+        assert_eq!(json["name"], "Arithmetic");
+        assert_eq!(json["creator"]["name"], "Sandburg, Carl");
+
+        // Deleted records keep only the header
+        let json = MarcToSchemaOrg.convert(&records[2]).unwrap();
+        assert_eq!(json["oai:deleted"], true);
+        assert!(json.get("name").is_none());
+    }
+
+    #[test]
+    fn test_maps_leader_to_type() {
+        assert_eq!(marc_type("00714cam a2200205 a 4500"), "Book");
+        assert_eq!(marc_type("00714cem a2200205 a 4500"), "Map");
+        assert_eq!(marc_type("00714cjm a2200205 a 4500"), "MusicRecording");
+        assert_eq!(marc_type("00714cas a2200205 a 4500"), "CreativeWork");
+        assert_eq!(marc_type(""), "CreativeWork");
+    }
+
+    #[test]
+    fn test_cleans_isbd_punctuation() {
+        assert_eq!(clean("The harbor at dusk /"), "The harbor at dusk");
+        assert_eq!(clean("New York :"), "New York");
+        assert_eq!(clean("1880."), "1880.");
+    }
+}
@@ -1,13 +1,45 @@
 use std::fmt;
 
+use crate::client::response::ResponseError;
+
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// HTTP request failed (network error, timeout, etc.)
+    #[cfg(feature = "reqwest")]
     Http(reqwest::Error),
 
+    /// The transport could not produce a response
+    Transport(String),
+
+    /// Reading or writing a local file failed
+    Io(std::io::Error),
+
     /// Failed to parse XML response
     XmlParse(quick_xml::DeError),
 
+    /// The repository answered with an OAI-PMH error
+    Oai(ResponseError),
+
+    /// Failed to read XML (e.g., record metadata)
+    Xml(quick_xml::Error),
+
+    /// Record metadata did not match the expected format
+    Metadata(String),
+
+    /// A record's metadata could not be parsed
+    Record {
+        /// The identifier from the record header
+        identifier: String,
+        source: Box<Error>,
+    },
+
+    /// A path query could not be parsed
+    InvalidPath(String),
+
+    /// A static repository document is malformed
+    StaticRepository(String),
+
     /// Failed to parse the endpoint URL
     UrlParse(url::ParseError),
 
@@ -29,8 +61,17 @@ pub enum Error {
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
+            #[cfg(feature = "reqwest")]
             Error::Http(e) => Some(e),
+            Error::Transport(_) => None,
+            Error::Io(e) => Some(e),
             Error::XmlParse(e) => Some(e),
+            Error::Oai(e) => Some(e),
+            Error::Xml(e) => Some(e),
+            Error::Metadata(_) => None,
+            Error::Record { source, .. } => Some(source.as_ref()),
+            Error::InvalidPath(_) => None,
+            Error::StaticRepository(_) => None,
             Error::UrlParse(e) => Some(e),
             Error::QuerySerialize(e) => Some(e),
             Error::InvalidEndpoint(_) => None,
@@ -42,8 +83,17 @@ impl std::error::Error for Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            #[cfg(feature = "reqwest")]
             Error::Http(e) => write!(f, "HTTP request failed: {e}"),
+            Error::Transport(msg) => write!(f, "transport failed: {msg}"),
+            Error::Io(e) => write!(f, "I/O failed: {e}"),
             Error::XmlParse(e) => write!(f, "XML parsing failed: {e}"),
+            Error::Oai(e) => write!(f, "OAI-PMH error: {e}"),
+            Error::Xml(e) => write!(f, "XML reading failed: {e}"),
+            Error::Metadata(msg) => write!(f, "unexpected metadata: {msg}"),
+            Error::Record { identifier, source } => write!(f, "record {identifier}: {source}"),
+            Error::InvalidPath(msg) => write!(f, "invalid path: {msg}"),
+            Error::StaticRepository(msg) => write!(f, "invalid static repository: {msg}"),
             Error::UrlParse(e) => write!(f, "URL parsing failed: {e}"),
             Error::QuerySerialize(e) => write!(f, "query serialization failed: {e}"),
             Error::InvalidEndpoint(msg) => write!(f, "invalid endpoint: {msg}"),
@@ -55,18 +105,31 @@ impl fmt::Display for Error {
     }
 }
 
+#[cfg(feature = "reqwest")]
 impl From<reqwest::Error> for Error {
     fn from(err: reqwest::Error) -> Self {
         Error::Http(err)
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
 impl From<quick_xml::DeError> for Error {
     fn from(err: quick_xml::DeError) -> Self {
         Error::XmlParse(err)
     }
 }
 
+impl From<quick_xml::Error> for Error {
+    fn from(err: quick_xml::Error) -> Self {
+        Error::Xml(err)
+    }
+}
+
 impl From<url::ParseError> for Error {
     fn from(err: url::ParseError) -> Self {
         Error::UrlParse(err)
@@ -0,0 +1,406 @@
+//! DataCite (`oai_datacite`), exposed by research data repositories such as Zenodo and Dataverse.
+//!
+//! `oai_datacite` wraps a DataCite kernel `resource` in a `payload` element. Both the
+//! envelope and a bare `resource` (as served under the `datacite` prefix) are accepted.
+use crate::error::{Error, Result};
+use crate::formats::MetadataFormat;
+use crate::xml::Element;
+
+pub const METADATA_PREFIX: &str = "oai_datacite";
+pub const NAMESPACE: &str = "http://schema.datacite.org/oai/oai-1.1/";
+pub const SCHEMA: &str = "http://schema.datacite.org/oai/oai-1.1/oai.xsd";
+pub const KERNEL_3_NAMESPACE: &str = "http://datacite.org/schema/kernel-3";
+pub const KERNEL_4_NAMESPACE: &str = "http://datacite.org/schema/kernel-4";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kernel {
+    Kernel3,
+    Kernel4,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataCite {
+    pub kernel: Kernel,
+    /// `schemaVersion` from the envelope, if present
+    pub schema_version: Option<String>,
+    /// `datacentreSymbol` from the envelope, if present
+    pub datacentre_symbol: Option<String>,
+    pub identifier: Identifier,
+    pub alternate_identifiers: Vec<Identifier>,
+    pub creators: Vec<Creator>,
+    pub titles: Vec<Title>,
+    pub publisher: Option<String>,
+    pub publication_year: Option<String>,
+    pub resource_type: Option<ResourceType>,
+    pub related_identifiers: Vec<RelatedIdentifier>,
+    pub rights: Vec<Rights>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Identifier {
+    /// e.g. `DOI`
+    pub r#type: String,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Creator {
+    pub name: String,
+    /// `Personal` or `Organizational` (kernel-4 only)
+    pub name_type: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub name_identifiers: Vec<NameIdentifier>,
+    pub affiliations: Vec<Affiliation>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NameIdentifier {
+    pub value: String,
+    /// e.g. `ORCID`
+    pub scheme: Option<String>,
+    pub scheme_uri: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Affiliation {
+    pub value: String,
+    pub identifier: Option<String>,
+    /// e.g. `ROR`
+    pub identifier_scheme: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Title {
+    pub value: String,
+    /// `None` for the main title, otherwise e.g. `Subtitle` or `TranslatedTitle`
+    pub r#type: Option<String>,
+    pub lang: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResourceType {
+    /// Controlled `resourceTypeGeneral`, e.g. `Dataset`
+    pub general: String,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RelatedIdentifier {
+    pub value: String,
+    pub r#type: String,
+    /// e.g. `IsSupplementTo`
+    pub relation_type: String,
+    pub resource_type_general: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rights {
+    pub value: String,
+    pub uri: Option<String>,
+    /// e.g. an SPDX license identifier (kernel-4 only)
+    pub identifier: Option<String>,
+    pub identifier_scheme: Option<String>,
+}
+
+impl DataCite {
+    /// Parse an `oai_datacite` envelope or a bare kernel-3/4 `resource`
+    ///
+    /// # Example
+    /// ```
+    /// use oai_pmh::formats::datacite::{DataCite, Kernel};
+    ///
+    /// let xml = r#"<resource xmlns="http://datacite.org/schema/kernel-4">
+    ///   <identifier identifierType="DOI">10.5281/zenodo.1</identifier>
+    ///   <creators><creator><creatorName>Garcia, Lucia</creatorName></creator></creators>
+    ///   <titles><title>Harbor temperatures</title></titles>
+    ///   <publicationYear>2021</publicationYear>
+    /// </resource>"#;
+    ///
+    /// let datacite = DataCite::parse(xml).unwrap();
+    /// assert_eq!(datacite.kernel, Kernel::Kernel4);
+    /// assert_eq!(datacite.doi(), Some("10.5281/zenodo.1"));
+    /// assert_eq!(datacite.title(), Some("Harbor temperatures"));
+    /// ```
+    pub fn parse(xml: &str) -> Result<Self> {
+        let root = Element::parse(xml)?;
+
+        if root.local_name() == "oai_datacite" {
+            let resource = root
+                .child("payload")
+                .and_then(|payload| payload.elements().next())
+                .ok_or_else(|| {
+                    Error::Metadata("oai_datacite is missing a payload resource".to_string())
+                })?;
+
+            let mut datacite = Self::from_resource(resource)?;
+            datacite.schema_version = root.child("schemaVersion").map(Element::trimmed_text);
+            datacite.datacentre_symbol = root.child("datacentreSymbol").map(Element::trimmed_text);
+            Ok(datacite)
+        } else {
+            Self::from_resource(&root)
+        }
+    }
+
+    fn from_resource(resource: &Element) -> Result<Self> {
+        let kernel = match resource.namespace.as_deref() {
+            Some(KERNEL_3_NAMESPACE) => Kernel::Kernel3,
+            Some(KERNEL_4_NAMESPACE) => Kernel::Kernel4,
+            _ => {
+                return Err(Error::Metadata(format!(
+                    "expected a DataCite kernel-3 or kernel-4 resource, found {}",
+                    resource.name
+                )));
+            }
+        };
+        if resource.local_name() != "resource" {
+            return Err(Error::Metadata(format!(
+                "expected DataCite resource, found {}",
+                resource.name
+            )));
+        }
+
+        Ok(Self {
+            kernel,
+            schema_version: None,
+            datacentre_symbol: None,
+            identifier: resource
+                .child("identifier")
+                .map(|e| identifier(e, "identifierType"))
+                .unwrap_or_default(),
+            alternate_identifiers: grandchildren(resource, "alternateIdentifiers")
+                .map(|e| identifier(e, "alternateIdentifierType"))
+                .collect(),
+            creators: grandchildren(resource, "creators").map(creator).collect(),
+            titles: grandchildren(resource, "titles")
+                .map(|e| Title {
+                    value: e.trimmed_text(),
+                    r#type: e.attr("titleType").map(String::from),
+                    lang: e.attr("xml:lang").map(String::from),
+                })
+                .collect(),
+            publisher: resource.child("publisher").map(Element::trimmed_text),
+            publication_year: resource.child("publicationYear").map(Element::trimmed_text),
+            resource_type: resource.child("resourceType").map(|e| ResourceType {
+                general: e
+                    .attr("resourceTypeGeneral")
+                    .map(String::from)
+                    .unwrap_or_default(),
+                value: e.trimmed_text(),
+            }),
+            related_identifiers: grandchildren(resource, "relatedIdentifiers")
+                .map(|e| RelatedIdentifier {
+                    value: e.trimmed_text(),
+                    r#type: e
+                        .attr("relatedIdentifierType")
+                        .map(String::from)
+                        .unwrap_or_default(),
+                    relation_type: e.attr("relationType").map(String::from).unwrap_or_default(),
+                    resource_type_general: e.attr("resourceTypeGeneral").map(String::from),
+                })
+                .collect(),
+            rights: grandchildren(resource, "rightsList")
+                .map(|e| Rights {
+                    value: e.trimmed_text(),
+                    uri: e.attr("rightsURI").map(String::from),
+                    identifier: e.attr("rightsIdentifier").map(String::from),
+                    identifier_scheme: e.attr("rightsIdentifierScheme").map(String::from),
+                })
+                .collect(),
+        })
+    }
+
+    /// The DOI, when the primary identifier is one
+    pub fn doi(&self) -> Option<&str> {
+        self.identifier
+            .r#type
+            .eq_ignore_ascii_case("DOI")
+            .then_some(self.identifier.value.as_str())
+    }
+
+    /// The main title, i.e. the first without a `titleType`
+    pub fn title(&self) -> Option<&str> {
+        self.titles
+            .iter()
+            .find(|t| t.r#type.is_none())
+            .or_else(|| self.titles.first())
+            .map(|t| t.value.as_str())
+    }
+}
+
+fn identifier(element: &Element, type_attr: &str) -> Identifier {
+    Identifier {
+        r#type: element
+            .attr(type_attr)
+            .map(String::from)
+            .unwrap_or_default(),
+        value: element.trimmed_text(),
+    }
+}
+
+fn creator(element: &Element) -> Creator {
+    let name = element.child("creatorName");
+    Creator {
+        name: name.map(Element::trimmed_text).unwrap_or_default(),
+        name_type: name.and_then(|e| e.attr("nameType").map(String::from)),
+        given_name: element.child("givenName").map(Element::trimmed_text),
+        family_name: element.child("familyName").map(Element::trimmed_text),
+        name_identifiers: element
+            .elements_named("nameIdentifier")
+            .map(|e| NameIdentifier {
+                value: e.trimmed_text(),
+                scheme: e.attr("nameIdentifierScheme").map(String::from),
+                scheme_uri: e.attr("schemeURI").map(String::from),
+            })
+            .collect(),
+        affiliations: element
+            .elements_named("affiliation")
+            .map(|e| Affiliation {
+                value: e.trimmed_text(),
+                identifier: e.attr("affiliationIdentifier").map(String::from),
+                identifier_scheme: e.attr("affiliationIdentifierScheme").map(String::from),
+            })
+            .collect(),
+    }
+}
+
+/// Items of a wrapper element such as `creators` or `rightsList`
+fn grandchildren<'a>(element: &'a Element, wrapper: &'a str) -> impl Iterator<Item = &'a Element> {
+    element.elements_named(wrapper).flat_map(|e| e.elements())
+}
+
+impl MetadataFormat for DataCite {
+    const PREFIX: &'static str = METADATA_PREFIX;
+    const NAMESPACE: &'static str = NAMESPACE;
+
+    fn from_metadata(xml: &str) -> Result<Self> {
+        Self::parse(xml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> DataCite {
+        let xml = std::fs::read_to_string("tests/fixtures/oai_datacite.xml")
+            .expect("Failed to load fixture");
+        DataCite::parse(&xml).unwrap()
+    }
+
+    #[test]
+    fn test_parse_envelope_and_identifiers() {
+        let datacite = fixture();
+
+        assert_eq!(datacite.kernel, Kernel::Kernel4);
+        assert_eq!(datacite.schema_version.as_deref(), Some("4.3"));
+        assert_eq!(datacite.datacentre_symbol.as_deref(), Some("CERN.ZENODO"));
+        assert_eq!(datacite.doi(), Some("10.5281/zenodo.1234567"));
+        assert_eq!(datacite.alternate_identifiers[0].r#type, "url");
+        assert_eq!(datacite.publisher.as_deref(), Some("Zenodo"));
+        assert_eq!(datacite.publication_year.as_deref(), Some("2021"));
+
+        let resource_type = datacite.resource_type.as_ref().unwrap();
+        assert_eq!(resource_type.general, "Dataset");
+        assert_eq!(resource_type.value, "Time series");
+    }
+
+    #[test]
+    fn test_parse_creators() {
+        let datacite = fixture();
+        assert_eq!(datacite.creators.len(), 2);
+
+        let creator = &datacite.creators[0];
+        assert_eq!(creator.name, "Garcia, Lucia");
+        assert_eq!(creator.name_type.as_deref(), Some("Personal"));
+        assert_eq!(creator.given_name.as_deref(), Some("Lucia"));
+        assert_eq!(creator.family_name.as_deref(), Some("Garcia"));
+        assert_eq!(creator.name_identifiers[0].value, "0000-0002-1825-0097");
+        assert_eq!(creator.name_identifiers[0].scheme.as_deref(), Some("ORCID"));
+        assert_eq!(creator.affiliations.len(), 2);
+        assert_eq!(
+            creator.affiliations[0].identifier_scheme.as_deref(),
+            Some("ROR")
+        );
+        assert_eq!(creator.affiliations[1].value, "Marine Data Lab");
+        assert_eq!(creator.affiliations[1].identifier, None);
+
+        assert_eq!(
+            datacite.creators[1].name_type.as_deref(),
+            Some("Organizational")
+        );
+    }
+
+    #[test]
+    fn test_parse_titles_relations_and_rights() {
+        let datacite = fixture();
+
+        assert_eq!(
+            datacite.title(),
+            Some("Harbor water temperature, 2010-2020")
+        );
+        assert_eq!(
+            datacite.titles[1].r#type.as_deref(),
+            Some("TranslatedTitle")
+        );
+        assert_eq!(datacite.titles[1].lang.as_deref(), Some("es"));
+
+        let related = &datacite.related_identifiers[0];
+        assert_eq!(related.value, "10.1000/example.2021.42");
+        assert_eq!(related.relation_type, "IsSupplementTo");
+        assert_eq!(
+            related.resource_type_general.as_deref(),
+            Some("JournalArticle")
+        );
+
+        assert_eq!(datacite.rights.len(), 2);
+        assert_eq!(datacite.rights[0].identifier.as_deref(), Some("cc-by-4.0"));
+        assert_eq!(
+            datacite.rights[1].uri.as_deref(),
+            Some("info:eu-repo/semantics/openAccess")
+        );
+    }
+
+    #[test]
+    fn test_parse_kernel_3() {
+        let xml = r#"<oai_datacite xmlns="http://schema.datacite.org/oai/oai-1.1/">
+          <payload>
+            <resource xmlns="http://datacite.org/schema/kernel-3">
+              <identifier identifierType="DOI">10.7910/DVN/ABC123</identifier>
+              <creators>
+                <creator>
+                  <creatorName>Okafor, Chidi</creatorName>
+                  <nameIdentifier nameIdentifierScheme="ORCID">0000-0001-2345-6789</nameIdentifier>
+                  <affiliation>Harvard University</affiliation>
+                </creator>
+              </creators>
+              <titles><title>Replication data</title></titles>
+              <publisher>Harvard Dataverse</publisher>
+              <publicationYear>2016</publicationYear>
+              <rightsList><rights rightsURI="info:eu-repo/semantics/openAccess"/></rightsList>
+            </resource>
+          </payload>
+        </oai_datacite>"#;
+
+        let datacite = DataCite::parse(xml).unwrap();
+        assert_eq!(datacite.kernel, Kernel::Kernel3);
+        assert_eq!(datacite.schema_version, None);
+        assert_eq!(datacite.doi(), Some("10.7910/DVN/ABC123"));
+        assert_eq!(
+            datacite.creators[0].affiliations[0].value,
+            "Harvard University"
+        );
+        assert_eq!(datacite.creators[0].name_type, None);
+        assert_eq!(datacite.rights[0].value, "");
+        assert_eq!(datacite.resource_type, None);
+    }
+
+    #[test]
+    fn test_rejects_other_documents() {
+        let xml = r#"<resource xmlns="http://datacite.org/schema/kernel-2.2"/>"#;
+        assert!(matches!(DataCite::parse(xml), Err(Error::Metadata(_))));
+
+        let xml = r#"<oai_datacite xmlns="http://schema.datacite.org/oai/oai-1.1/"><payload/></oai_datacite>"#;
+        assert!(matches!(DataCite::parse(xml), Err(Error::Metadata(_))));
+    }
+}
@@ -0,0 +1,710 @@
+//! EAD finding aids (`oai_ead`), as exposed by ArchivesSpace and most archival systems.
+//!
+//! EAD 2002 is the primary target. EAD3 documents are accepted as well: `control`
+//! is read in place of `eadheader`, and the descriptive identification and
+//! component tree share the same structure in both versions.
+use crate::error::{Error, Result};
+use crate::formats::MetadataFormat;
+use crate::xml::{Element, Node};
+
+pub const METADATA_PREFIX: &str = "oai_ead";
+pub const NAMESPACE: &str = "urn:isbn:1-931666-22-9";
+pub const SCHEMA: &str = "https://www.loc.gov/ead/ead.xsd";
+pub const EAD3_NAMESPACE: &str = "http://ead3.archivists.org/schema/";
+
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Version {
+    Ead2002,
+    Ead3,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ead {
+    pub version: Version,
+    pub header: Header,
+    pub archdesc: ArchDesc,
+}
+
+/// The `eadheader` (EAD 2002) or `control` (EAD3) element
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Header {
+    /// `eadid` in EAD 2002, `recordid` in EAD3
+    pub eadid: Option<EadId>,
+    pub title_proper: Option<String>,
+    pub subtitle: Option<String>,
+    pub author: Option<String>,
+    pub publisher: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EadId {
+    pub value: String,
+    pub country_code: Option<String>,
+    pub main_agency_code: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ArchDesc {
+    pub level: Option<String>,
+    pub did: Did,
+    /// Descriptive notes such as `scopecontent`, `bioghist` and `accessrestrict`
+    pub notes: Vec<Note>,
+    pub components: Vec<Component>,
+}
+
+/// Descriptive identification shared by `archdesc` and every component
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Did {
+    pub unit_title: Option<String>,
+    pub unit_id: Vec<UnitId>,
+    pub unit_date: Vec<UnitDate>,
+    pub origination: Vec<String>,
+    pub repository: Option<String>,
+    pub extent: Vec<String>,
+    pub abstract_: Option<String>,
+    pub language: Vec<Language>,
+    pub container: Vec<Container>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnitId {
+    pub r#type: Option<String>,
+    pub value: String,
+    /// Target of a nested `extref`, such as an ARK
+    pub href: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnitDate {
+    pub value: String,
+    pub normal: Option<String>,
+    pub r#type: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Language {
+    pub code: Option<String>,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Container {
+    pub r#type: Option<String>,
+    pub label: Option<String>,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Note {
+    /// Element name, e.g. `scopecontent`
+    pub name: String,
+    pub head: Option<String>,
+    /// Paragraph text, one entry per `p`
+    pub paragraphs: Vec<String>,
+}
+
+/// A `c` or numbered `c01`..`c12` component
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Component {
+    pub id: Option<String>,
+    pub level: Option<String>,
+    pub did: Did,
+    pub notes: Vec<Note>,
+    pub components: Vec<Component>,
+}
+
+// Notes recognized at archdesc and component level
+const NOTES: &[&str] = &[
+    "accessrestrict",
+    "accruals",
+    "acqinfo",
+    "altformavail",
+    "appraisal",
+    "arrangement",
+    "bibliography",
+    "bioghist",
+    "custodhist",
+    "fileplan",
+    "legalstatus",
+    "odd",
+    "originalsloc",
+    "otherfindaid",
+    "phystech",
+    "prefercite",
+    "processinfo",
+    "relatedmaterial",
+    "scopecontent",
+    "separatedmaterial",
+    "userestrict",
+];
+
+impl Ead {
+    /// Parse an `ead` element
+    ///
+    /// Documents without a namespace are treated as EAD 2002, as produced by the DTD.
+    pub fn parse(xml: &str) -> Result<Self> {
+        let root = Element::parse(xml)?;
+
+        let version = match root.namespace.as_deref() {
+            None | Some(NAMESPACE) => Version::Ead2002,
+            Some(EAD3_NAMESPACE) => Version::Ead3,
+            Some(other) => {
+                return Err(Error::Metadata(format!(
+                    "unsupported EAD namespace {other}"
+                )));
+            }
+        };
+        if root.local_name() != "ead" {
+            return Err(Error::Metadata(format!(
+                "expected EAD ead, found {}",
+                root.name
+            )));
+        }
+
+        let header = match version {
+            Version::Ead2002 => root.child("eadheader").map(eadheader),
+            Version::Ead3 => root.child("control").map(control),
+        }
+        .unwrap_or_default();
+
+        let archdesc = root
+            .child("archdesc")
+            .ok_or_else(|| Error::Metadata("EAD is missing archdesc".to_string()))?;
+
+        Ok(Self {
+            version,
+            header,
+            archdesc: ArchDesc {
+                level: archdesc.attr("level").map(String::from),
+                did: archdesc.child("did").map(did).unwrap_or_default(),
+                notes: notes(archdesc),
+                components: archdesc.child("dsc").map(components).unwrap_or_default(),
+            },
+        })
+    }
+
+    /// Title of the finding aid, falling back to the collection's unit title
+    pub fn title(&self) -> Option<&str> {
+        self.header
+            .title_proper
+            .as_deref()
+            .or(self.archdesc.did.unit_title.as_deref())
+    }
+
+    /// Iterate over every component depth-first, in document order
+    ///
+    /// # Example
+    /// ```
+    /// use oai_pmh::formats::ead::Ead;
+    ///
+    /// let xml = r#"<ead xmlns="urn:isbn:1-931666-22-9">
+    ///   <archdesc level="collection">
+    ///     <did><unittitle>Family papers</unittitle></did>
+    ///     <dsc>
+    ///       <c01 level="series">
+    ///         <did><unittitle>Correspondence</unittitle></did>
+    ///         <c02 level="file"><did><unittitle>Letters, 1880</unittitle></did></c02>
+    ///       </c01>
+    ///     </dsc>
+    ///   </archdesc>
+    /// </ead>"#;
+    ///
+    /// let ead = Ead::parse(xml).unwrap();
+    /// let paths: Vec<Vec<&str>> = ead.components().map(|c| c.path().collect()).collect();
+    /// assert_eq!(
+    ///     paths,
+    ///     vec![vec!["Correspondence"], vec!["Correspondence", "Letters, 1880"]]
+    /// );
+    /// ```
+    pub fn components(&self) -> Components<'_> {
+        Components {
+            stack: vec![self.archdesc.components.iter()],
+            ancestors: Vec::new(),
+        }
+    }
+}
+
+/// Depth-first iterator over a component tree, see [`Ead::components`]
+pub struct Components<'a> {
+    stack: Vec<std::slice::Iter<'a, Component>>,
+    ancestors: Vec<&'a Component>,
+}
+
+/// A component together with the components that contain it
+#[derive(Clone, Debug)]
+pub struct FlatComponent<'a> {
+    pub component: &'a Component,
+    /// Enclosing components, outermost first
+    pub ancestors: Vec<&'a Component>,
+}
+
+impl<'a> FlatComponent<'a> {
+    /// Nesting depth, where top-level components are at depth 1
+    pub fn depth(&self) -> usize {
+        self.ancestors.len() + 1
+    }
+
+    /// Unit titles from the outermost ancestor down to this component
+    ///
+    /// Components without a title are skipped.
+    pub fn path(&self) -> impl Iterator<Item = &'a str> {
+        self.ancestors
+            .iter()
+            .chain(std::iter::once(&self.component))
+            .filter_map(|&c| c.did.unit_title.as_deref())
+    }
+}
+
+impl<'a> Iterator for Components<'a> {
+    type Item = FlatComponent<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some(component) => {
+                    let ancestors = self.ancestors.clone();
+                    self.ancestors.push(component);
+                    self.stack.push(component.components.iter());
+                    return Some(FlatComponent {
+                        component,
+                        ancestors,
+                    });
+                }
+                None => {
+                    self.stack.pop();
+                    self.ancestors.pop();
+                }
+            }
+        }
+    }
+}
+
+fn eadheader(element: &Element) -> Header {
+    let mut header = filedesc(element);
+    header.eadid = element.child("eadid").map(|e| EadId {
+        value: text(e),
+        country_code: e.attr("countrycode").map(String::from),
+        main_agency_code: e.attr("mainagencycode").map(String::from),
+        url: e.attr("url").map(String::from),
+    });
+    header.status = element.attr("findaidstatus").map(String::from);
+    header
+}
+
+fn control(element: &Element) -> Header {
+    let mut header = filedesc(element);
+    header.eadid = element.child("recordid").map(|e| EadId {
+        value: text(e),
+        country_code: None,
+        main_agency_code: element
+            .child("maintenanceagency")
+            .and_then(|agency| agency.child("agencycode"))
+            .map(text),
+        url: e.attr("instanceurl").map(String::from),
+    });
+    header.status = element
+        .child("maintenancestatus")
+        .and_then(|e| e.attr("value").map(String::from));
+    header
+}
+
+fn filedesc(element: &Element) -> Header {
+    let filedesc = element.child("filedesc");
+    let titlestmt = filedesc.and_then(|e| e.child("titlestmt"));
+    let publicationstmt = filedesc.and_then(|e| e.child("publicationstmt"));
+
+    Header {
+        // num holds identifiers rather than title text
+        title_proper: titlestmt
+            .and_then(|e| e.child("titleproper"))
+            .map(|e| text_without(e, "num")),
+        subtitle: titlestmt.and_then(|e| e.child("subtitle")).map(text),
+        author: titlestmt.and_then(|e| e.child("author")).map(text),
+        publisher: publicationstmt.and_then(|e| e.child("publisher")).map(text),
+        ..Default::default()
+    }
+}
+
+fn did(element: &Element) -> Did {
+    let mut did = Did::default();
+
+    for e in element.elements() {
+        match e.local_name() {
+            "unittitle" => did.unit_title = Some(text(e)),
+            "unitid" => did.unit_id.push(UnitId {
+                r#type: e
+                    .attr("type")
+                    .map(String::from)
+                    .or_else(|| e.attr("localtype").map(String::from))
+                    .or_else(|| e.attr("label").map(String::from)),
+                value: text(e),
+                href: e.child("extref").and_then(extref_href),
+            }),
+            "unitdate" => did.unit_date.push(UnitDate {
+                value: text(e),
+                normal: e.attr("normal").map(String::from),
+                r#type: e.attr("type").map(String::from),
+            }),
+            "unitdatestructured" => did.unit_date.push(unitdatestructured(e)),
+            "origination" => did.origination.push(text(e)),
+            "repository" => did.repository = Some(text(e)),
+            "physdesc" => did.extent.extend(e.elements_named("extent").map(text)),
+            "physdescstructured" => {
+                let quantity = e.child("quantity").map(text).unwrap_or_default();
+                let unit = e.child("unittype").map(text).unwrap_or_default();
+                did.extent
+                    .push(format!("{quantity} {unit}").trim().to_string());
+            }
+            "abstract" => did.abstract_ = Some(text(e)),
+            "langmaterial" => did.language.extend(
+                e.elements_named("language")
+                    .chain(
+                        e.elements_named("languageset")
+                            .flat_map(|set| set.elements_named("language")),
+                    )
+                    .map(|l| Language {
+                        code: l.attr("langcode").map(String::from),
+                        value: text(l),
+                    }),
+            ),
+            "container" => did.container.push(Container {
+                r#type: e
+                    .attr("type")
+                    .map(String::from)
+                    .or_else(|| e.attr("localtype").map(String::from)),
+                label: e.attr("label").map(String::from),
+                value: text(e),
+            }),
+            _ => {}
+        }
+    }
+
+    did
+}
+
+/// The XLink target of an `extref`, which EAD3 carries in a plain `href`
+fn extref_href(element: &Element) -> Option<String> {
+    element
+        .attr_ns(XLINK_NAMESPACE, "href")
+        .or_else(|| {
+            (element.namespace.as_deref() == Some(EAD3_NAMESPACE))
+                .then(|| element.attr("href"))
+                .flatten()
+        })
+        .map(String::from)
+}
+
+/// EAD3 structured dates, rendered like their EAD 2002 `unitdate` equivalent
+fn unitdatestructured(element: &Element) -> UnitDate {
+    // standarddate holds the ISO 8601 form of each date
+    let date = |e: &Element| {
+        (
+            text(e),
+            e.attr("standarddate")
+                .map(String::from)
+                .unwrap_or_else(|| text(e)),
+        )
+    };
+    let range = element.child("daterange").map(|range| {
+        (
+            range.child("fromdate").map(date).unwrap_or_default(),
+            range.child("todate").map(date).unwrap_or_default(),
+        )
+    });
+
+    let (value, normal) = match range {
+        Some(((from, from_normal), (to, to_normal))) => {
+            (format!("{from}-{to}"), format!("{from_normal}/{to_normal}"))
+        }
+        None => element.child("datesingle").map(date).unwrap_or_default(),
+    };
+
+    UnitDate {
+        value,
+        normal: Some(normal).filter(|n| !n.is_empty()),
+        r#type: element.attr("unitdatetype").map(String::from),
+    }
+}
+
+fn notes(element: &Element) -> Vec<Note> {
+    element
+        .elements()
+        .filter(|e| NOTES.contains(&e.local_name()))
+        .map(|e| Note {
+            name: e.local_name().to_string(),
+            head: e.child("head").map(text),
+            paragraphs: e.elements_named("p").map(text).collect(),
+        })
+        .collect()
+}
+
+fn components(element: &Element) -> Vec<Component> {
+    element
+        .elements()
+        .filter(|e| is_component(e.local_name()))
+        .map(|e| Component {
+            id: e.attr("id").map(String::from),
+            level: e.attr("level").map(String::from),
+            did: e.child("did").map(did).unwrap_or_default(),
+            notes: notes(e),
+            components: components(e),
+        })
+        .collect()
+}
+
+/// `c`, or `c01` through `c12`
+fn is_component(local_name: &str) -> bool {
+    match local_name.strip_prefix('c') {
+        Some("") => true,
+        Some(n) if n.len() == 2 => matches!(n.parse::<u8>(), Ok(1..=12)),
+        _ => false,
+    }
+}
+
+/// Text content with whitespace collapsed, since EAD text is often indented mixed content
+fn text(element: &Element) -> String {
+    collapse(&element.text())
+}
+
+fn text_without(element: &Element, skip: &str) -> String {
+    let mut text = String::new();
+    for node in &element.children {
+        match node {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(e) if e.local_name() != skip => {
+                text.push(' ');
+                text.push_str(&e.text());
+                text.push(' ');
+            }
+            Node::Element(_) => {}
+        }
+    }
+    collapse(&text)
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl MetadataFormat for Ead {
+    const PREFIX: &'static str = METADATA_PREFIX;
+    const NAMESPACE: &'static str = NAMESPACE;
+
+    fn from_metadata(xml: &str) -> Result<Self> {
+        Self::parse(xml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::metadata::extract_metadata;
+
+    fn fixture() -> Ead {
+        let xml = std::fs::read_to_string("tests/fixtures/get_record.xml")
+            .expect("Failed to load fixture");
+        Ead::parse(&extract_metadata(&xml)[0]).unwrap()
+    }
+
+    #[test]
+    fn test_parse_eadheader() {
+        let ead = fixture();
+        assert_eq!(ead.version, Version::Ead2002);
+
+        let header = &ead.header;
+        let eadid = header.eadid.as_ref().unwrap();
+        assert_eq!(eadid.country_code.as_deref(), Some("US"));
+        assert_eq!(
+            eadid.url.as_deref(),
+            Some("https://test.archivesspace.org/ark:/99999/6261")
+        );
+        assert_eq!(
+            header.title_proper.as_deref(),
+            Some("A Guide to the Diary of Emma Cummins Snively Crosier Pauling")
+        );
+        assert_eq!(header.author.as_deref(), Some("Clara Scholtz, Intern."));
+        assert_eq!(
+            header.publisher.as_deref(),
+            Some("Allen Doe Research Center")
+        );
+        assert_eq!(header.status.as_deref(), Some("completed"));
+    }
+
+    #[test]
+    fn test_parse_archdesc_did() {
+        let ead = fixture();
+        assert_eq!(ead.archdesc.level.as_deref(), Some("collection"));
+
+        let did = &ead.archdesc.did;
+        assert_eq!(
+            did.unit_title.as_deref(),
+            Some("Diary of Emma Cummins Snively Crosier Pauling")
+        );
+        assert_eq!(did.unit_id.len(), 3);
+        assert_eq!(did.unit_id[0].value, "arc.A0059");
+        assert_eq!(did.unit_id[1].r#type.as_deref(), Some("ark"));
+        assert_eq!(
+            did.unit_id[1].href.as_deref(),
+            Some("https://test.archivesspace.org/ark:/99999/6261")
+        );
+        assert_eq!(did.unit_date[0].normal.as_deref(), Some("1842/1936"));
+        assert_eq!(did.unit_date[1].r#type.as_deref(), Some("bulk"));
+        assert_eq!(
+            did.origination,
+            vec!["Cummins Snively Crosier Pauling, Emma"]
+        );
+        assert_eq!(did.repository.as_deref(), Some("Allen Doe Research Center"));
+        assert_eq!(did.extent, vec!["1 Linear Feet", "1 flat box"]);
+        assert!(
+            did.abstract_
+                .as_ref()
+                .unwrap()
+                .starts_with("This collection")
+        );
+        assert_eq!(did.language[0].code.as_deref(), Some("eng"));
+        assert_eq!(did.container[0].r#type.as_deref(), Some("box"));
+
+        let notes = &ead.archdesc.notes;
+        assert_eq!(notes.len(), 10);
+        assert_eq!(notes[0].name, "accessrestrict");
+        assert_eq!(
+            notes[0].head.as_deref(),
+            Some("Conditions Governing Access")
+        );
+        assert_eq!(
+            notes[0].paragraphs,
+            vec!["The collection is open for research."]
+        );
+    }
+
+    #[test]
+    fn test_flattens_components() {
+        let ead = fixture();
+        let components: Vec<_> = ead.components().collect();
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].component.level.as_deref(), Some("file"));
+        assert_eq!(
+            components[0].path().collect::<Vec<_>>(),
+            vec!["Contextual information"]
+        );
+
+        let xml = r#"<ead xmlns="urn:isbn:1-931666-22-9"><archdesc level="collection"><dsc>
+            <c01 id="s1"><did><unittitle>Series 1</unittitle></did>
+              <c02 id="s1f1"><did><unittitle>Folder 1</unittitle></did>
+                <c03 id="s1f1i1"><did><unittitle>Item 1</unittitle></did></c03>
+              </c02>
+              <c02 id="s1f2"><did><unittitle>Folder 2</unittitle></did></c02>
+            </c01>
+            <c01 id="s2"><did><unittitle>Series 2</unittitle></did></c01>
+        </dsc></archdesc></ead>"#;
+
+        let ead = Ead::parse(xml).unwrap();
+        let flat: Vec<_> = ead
+            .components()
+            .map(|c| (c.component.id.clone().unwrap(), c.depth()))
+            .collect();
+        assert_eq!(
+            flat,
+            vec![
+                ("s1".to_string(), 1),
+                ("s1f1".to_string(), 2),
+                ("s1f1i1".to_string(), 3),
+                ("s1f2".to_string(), 2),
+                ("s2".to_string(), 1),
+            ]
+        );
+
+        let item = ead.components().nth(2).unwrap();
+        assert_eq!(
+            item.path().collect::<Vec<_>>(),
+            vec!["Series 1", "Folder 1", "Item 1"]
+        );
+    }
+
+    #[test]
+    fn test_parse_ead3() {
+        let xml = r#"<ead xmlns="http://ead3.archivists.org/schema/">
+          <control>
+            <recordid instanceurl="https://example.edu/findingaids/mss1">mss1</recordid>
+            <filedesc><titlestmt><titleproper>Guide to the Harbor Papers</titleproper></titlestmt></filedesc>
+            <maintenancestatus value="derived"/>
+            <maintenanceagency><agencycode>US-ExU</agencycode></maintenanceagency>
+          </control>
+          <archdesc level="collection">
+            <did>
+              <unittitle>Harbor Papers</unittitle>
+              <unitdatestructured unitdatetype="inclusive"><daterange><fromdate standarddate="1880">1880</fromdate><todate>1890</todate></daterange></unitdatestructured>
+              <physdescstructured physdescstructuredtype="spaceoccupied" coverage="whole">
+                <quantity>2</quantity><unittype>linear feet</unittype>
+              </physdescstructured>
+            </did>
+            <dsc><c level="series"><did><unittitle>Logs</unittitle></did><c level="file"><did><unittitle>1880</unittitle></did></c></c></dsc>
+          </archdesc>
+        </ead>"#;
+
+        let ead = Ead::parse(xml).unwrap();
+        assert_eq!(ead.version, Version::Ead3);
+        let eadid = ead.header.eadid.as_ref().unwrap();
+        assert_eq!(eadid.value, "mss1");
+        assert_eq!(eadid.main_agency_code.as_deref(), Some("US-ExU"));
+        assert_eq!(ead.title(), Some("Guide to the Harbor Papers"));
+        assert_eq!(ead.header.status.as_deref(), Some("derived"));
+        assert_eq!(ead.archdesc.did.unit_date[0].value, "1880-1890");
+        assert_eq!(
+            ead.archdesc.did.unit_date[0].normal.as_deref(),
+            Some("1880/1890")
+        );
+        assert_eq!(
+            ead.archdesc.did.unit_date[0].r#type.as_deref(),
+            Some("inclusive")
+        );
+        assert_eq!(ead.archdesc.did.extent, vec!["2 linear feet"]);
+        assert_eq!(
+            ead.components().last().unwrap().path().collect::<Vec<_>>(),
+            vec!["Logs", "1880"]
+        );
+    }
+
+    #[test]
+    fn test_unitid_links() {
+        let unitid = |xml: &str| {
+            Ead::parse(xml).unwrap().archdesc.did.unit_id[0]
+                .href
+                .clone()
+        };
+
+        // XLink is matched by namespace, whatever the prefix
+        let xml = r#"<ead xmlns="urn:isbn:1-931666-22-9" xmlns:xl="http://www.w3.org/1999/xlink"><eadheader/><archdesc>
+            <did><unitid><extref xl:href="https://example.org/ark:/1">ark</extref></unitid></did>
+        </archdesc></ead>"#;
+        assert_eq!(unitid(xml).as_deref(), Some("https://example.org/ark:/1"));
+
+        let xml = r#"<ead xmlns="urn:isbn:1-931666-22-9"><eadheader/><archdesc>
+            <did><unitid><extref href="https://example.org/ark:/1">ark</extref></unitid></did>
+        </archdesc></ead>"#;
+        assert_eq!(unitid(xml), None);
+
+        let xml = r#"<ead xmlns="http://ead3.archivists.org/schema/"><control/><archdesc>
+            <did><unitid><extref href="https://example.org/ark:/1">ark</extref></unitid></did>
+        </archdesc></ead>"#;
+        assert_eq!(unitid(xml).as_deref(), Some("https://example.org/ark:/1"));
+    }
+
+    #[test]
+    fn test_rejects_other_documents() {
+        let xml = r#"<mods xmlns="http://www.loc.gov/mods/v3"/>"#;
+        assert!(matches!(Ead::parse(xml), Err(Error::Metadata(_))));
+
+        let xml = r#"<ead xmlns="urn:isbn:1-931666-22-9"><eadheader/></ead>"#;
+        assert!(matches!(Ead::parse(xml), Err(Error::Metadata(_))));
+
+        assert!(is_component("c"));
+        assert!(is_component("c07"));
+        assert!(!is_component("c13"));
+        assert!(!is_component("controlaccess"));
+    }
+}
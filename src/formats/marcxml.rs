@@ -0,0 +1,248 @@
+//! MARC 21 in MARCXML (`marc21`/`marcxml`), as exposed by most library catalogs.
+use crate::error::{Error, Result};
+use crate::formats::MetadataFormat;
+use crate::xml::Element;
+
+pub const METADATA_PREFIX: &str = "marc21";
+pub const NAMESPACE: &str = "http://www.loc.gov/MARC21/slim";
+pub const SCHEMA: &str = "http://www.loc.gov/standards/marcxml/schema/MARC21slim.xsd";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MarcRecord {
+    pub leader: String,
+    pub control_fields: Vec<ControlField>,
+    pub data_fields: Vec<DataField>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ControlField {
+    pub tag: String,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataField {
+    pub tag: String,
+    pub ind1: char,
+    pub ind2: char,
+    pub subfields: Vec<Subfield>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Subfield {
+    pub code: char,
+    pub value: String,
+}
+
+impl MarcRecord {
+    /// Parse a single `record`, or a `collection` containing exactly one record
+    ///
+    /// Elements are matched by local name, so unprefixed, prefixed and
+    /// namespace-less MARCXML are all accepted.
+    ///
+    /// # Example
+    /// ```
+    /// use oai_pmh::formats::marcxml::MarcRecord;
+    ///
+    /// let xml = r#"<record xmlns="http://www.loc.gov/MARC21/slim">
+    ///   <leader>00714cam a2200205 a 4500</leader>
+    ///   <controlfield tag="001">991000456</controlfield>
+    ///   <datafield tag="245" ind1="1" ind2="4">
+    ///     <subfield code="a">The waves /</subfield>
+    ///     <subfield code="c">Virginia Woolf.</subfield>
+    ///   </datafield>
+    /// </record>"#;
+    ///
+    /// let record = MarcRecord::parse(xml).unwrap();
+    /// assert_eq!(record.control_field("001"), Some("991000456"));
+    /// assert_eq!(record.field("245").unwrap().ind2, '4');
+    /// assert_eq!(record.subfield("245", 'a'), Some("The waves /"));
+    /// ```
+    pub fn parse(xml: &str) -> Result<Self> {
+        let mut records = Self::parse_collection(xml)?;
+        match records.len() {
+            1 => Ok(records.remove(0)),
+            n => Err(Error::Metadata(format!(
+                "expected a single MARC record, found {n}"
+            ))),
+        }
+    }
+
+    /// Parse a `collection` of records (a lone `record` yields one item)
+    pub fn parse_collection(xml: &str) -> Result<Vec<Self>> {
+        let root = Element::parse(xml)?;
+        match root.local_name() {
+            "record" => Ok(vec![Self::from_element(&root)?]),
+            "collection" => root
+                .elements()
+                .filter(|e| e.local_name() == "record")
+                .map(Self::from_element)
+                .collect(),
+            _ => Err(Error::Metadata(format!(
+                "expected MARCXML record or collection, found {}",
+                root.name
+            ))),
+        }
+    }
+
+    fn from_element(element: &Element) -> Result<Self> {
+        let mut record = Self::default();
+
+        for child in element.elements() {
+            match child.local_name() {
+                "leader" => record.leader = child.text(),
+                "controlfield" => record.control_fields.push(ControlField {
+                    tag: required_attr(child, "tag")?,
+                    value: child.text(),
+                }),
+                "datafield" => record.data_fields.push(DataField {
+                    tag: required_attr(child, "tag")?,
+                    ind1: indicator(child, "ind1"),
+                    ind2: indicator(child, "ind2"),
+                    subfields: child
+                        .elements()
+                        .filter(|e| e.local_name() == "subfield")
+                        .map(|e| Subfield {
+                            code: e.attr("code").and_then(|c| c.chars().next()).unwrap_or(' '),
+                            value: e.text(),
+                        })
+                        .collect(),
+                }),
+                _ => {}
+            }
+        }
+
+        Ok(record)
+    }
+
+    /// Returns the value of the first control field with `tag`
+    pub fn control_field(&self, tag: &str) -> Option<&str> {
+        self.control_fields
+            .iter()
+            .find(|f| f.tag == tag)
+            .map(|f| f.value.as_str())
+    }
+
+    /// Returns the first data field with `tag`
+    pub fn field(&self, tag: &str) -> Option<&DataField> {
+        self.data_fields.iter().find(|f| f.tag == tag)
+    }
+
+    /// Iterates over all data fields with `tag`
+    pub fn fields<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a DataField> {
+        self.data_fields.iter().filter(move |f| f.tag == tag)
+    }
+
+    /// Returns the first `code` subfield value of the first field with `tag`
+    pub fn subfield(&self, tag: &str, code: char) -> Option<&str> {
+        self.data_fields
+            .iter()
+            .filter(|f| f.tag == tag)
+            .find_map(|f| f.subfield(code))
+    }
+
+    /// Iterates over every `code` subfield value across all fields with `tag`
+    pub fn subfields<'a>(&'a self, tag: &'a str, code: char) -> impl Iterator<Item = &'a str> {
+        self.fields(tag).flat_map(move |f| f.subfields(code))
+    }
+}
+
+impl DataField {
+    /// Returns the first subfield value with `code`
+    pub fn subfield(&self, code: char) -> Option<&str> {
+        self.subfields(code).next()
+    }
+
+    /// Iterates over every subfield value with `code`
+    pub fn subfields(&self, code: char) -> impl Iterator<Item = &str> {
+        self.subfields
+            .iter()
+            .filter(move |s| s.code == code)
+            .map(|s| s.value.as_str())
+    }
+}
+
+fn required_attr(element: &Element, name: &str) -> Result<String> {
+    element
+        .attr(name)
+        .map(|s| s.to_string())
+        .ok_or_else(|| Error::Metadata(format!("{} is missing the {name} attribute", element.name)))
+}
+
+fn indicator(element: &Element, name: &str) -> char {
+    element
+        .attr(name)
+        .and_then(|s| s.chars().next())
+        .unwrap_or(' ')
+}
+
+impl MetadataFormat for MarcRecord {
+    const PREFIX: &'static str = METADATA_PREFIX;
+    const NAMESPACE: &'static str = NAMESPACE;
+
+    fn from_metadata(xml: &str) -> Result<Self> {
+        Self::parse(xml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::response::ListRecordsResponse;
+
+    #[test]
+    fn test_parse_list_records_fixture() {
+        let xml = std::fs::read_to_string("tests/fixtures/list_records_marc21.xml")
+            .expect("Failed to load fixture");
+
+        let response = ListRecordsResponse::new(&xml).unwrap();
+        let records = response.payload.unwrap().record;
+
+        let marc = records[0].parse_marc().unwrap();
+        assert_eq!(marc.leader, "01142cam  2200301 a 4500");
+        assert_eq!(marc.control_field("001"), Some("991000123"));
+        assert_eq!(marc.control_field("005"), None);
+
+        let author = marc.field("100").unwrap();
+        assert_eq!((author.ind1, author.ind2), ('1', ' '));
+        assert_eq!(author.subfield('a'), Some("Sandburg, Carl,"));
+        assert_eq!(author.subfield('d'), Some("1878-1967."));
+
+        assert_eq!(marc.subfield("245", 'a'), Some("Arithmetic /"));
+        assert_eq!(
+            marc.subfields("650", 'a').collect::<Vec<_>>(),
+            vec!["Arithmetic", "Children's poetry, American."]
+        );
+        assert_eq!(marc.fields("650").count(), 2);
+
+        // Default namespace instead of a prefix
+        let marc = records[1].parse_marc().unwrap();
+        assert_eq!(marc.subfield("100", 'a'), Some("Woolf, Virginia,"));
+        assert_eq!(marc.field("245").unwrap().ind2, '4');
+    }
+
+    #[test]
+    fn test_parse_collection() {
+        let xml = r#"<collection xmlns="http://www.loc.gov/MARC21/slim">
+          <record><leader>a</leader><controlfield tag="001">1</controlfield></record>
+          <record><leader>b</leader><controlfield tag="001">2</controlfield></record>
+        </collection>"#;
+
+        let records = MarcRecord::parse_collection(xml).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].control_field("001"), Some("2"));
+
+        assert!(matches!(MarcRecord::parse(xml), Err(Error::Metadata(_))));
+    }
+
+    #[test]
+    fn test_parse_missing_indicators_and_tag() {
+        let xml = r#"<record><datafield tag="500"><subfield code="a">Note</subfield></datafield></record>"#;
+        let record = MarcRecord::parse(xml).unwrap();
+        let note = record.field("500").unwrap();
+        assert_eq!((note.ind1, note.ind2), (' ', ' '));
+
+        let xml = r#"<record><controlfield>1</controlfield></record>"#;
+        assert!(matches!(MarcRecord::parse(xml), Err(Error::Metadata(_))));
+    }
+}
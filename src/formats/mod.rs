@@ -0,0 +1,59 @@
+//! Parsers for common metadata formats.
+//!
+//! [`Record::metadata`](crate::client::response::Record::metadata) is kept as a raw
+//! string so any format can be handled. The modules here parse the formats most
+//! providers support into typed models.
+pub mod datacite;
+pub mod ead;
+pub mod marcxml;
+pub mod mods;
+pub mod oai_dc;
+
+use crate::error::Result;
+use serde::de::DeserializeOwned;
+
+/// A metadata format that records can be parsed into
+///
+/// Implemented by the parsers in this module, and by user types to plug custom
+/// schemas into [`Client::get_record_as`](crate::Client::get_record_as) and
+/// [`Client::records_as`](crate::Client::records_as).
+///
+/// # Example
+/// ```
+/// use oai_pmh::Result;
+/// use oai_pmh::formats::{self, MetadataFormat};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Thesis {
+///     title: String,
+///     degree: String,
+/// }
+///
+/// impl MetadataFormat for Thesis {
+///     const PREFIX: &'static str = "etdms";
+///     const NAMESPACE: &'static str = "http://www.ndltd.org/standards/metadata/etdms/1.0/";
+///
+///     fn from_metadata(xml: &str) -> Result<Self> {
+///         formats::deserialize(xml)
+///     }
+/// }
+///
+/// let xml = "<thesis><title>Tidal harbors</title><degree>PhD</degree></thesis>";
+/// let thesis = Thesis::from_metadata(xml).unwrap();
+/// assert_eq!(thesis.degree, "PhD");
+/// ```
+pub trait MetadataFormat: Sized {
+    /// The `metadataPrefix` this format is usually served under
+    const PREFIX: &'static str;
+    /// The XML namespace of the metadata root element
+    const NAMESPACE: &'static str;
+
+    /// Parse the contents of a record's `metadata` element
+    fn from_metadata(xml: &str) -> Result<Self>;
+}
+
+/// Deserialize metadata into a serde type, for use in [`MetadataFormat::from_metadata`]
+pub fn deserialize<T: DeserializeOwned>(xml: &str) -> Result<T> {
+    Ok(quick_xml::de::from_str(xml)?)
+}
@@ -0,0 +1,613 @@
+//! MODS (`mods`), common in Islandora and Fedora-based digital libraries.
+//!
+//! The main descriptive elements are modeled. Every other element is kept as raw XML
+//! in an `other` field: top-level ones in [`Mods::other`], and unmodeled children of
+//! the modeled elements (e.g. `hierarchicalGeographic` in a `subject`) in the `other`
+//! field of their parent, so nothing is lost when reading a record.
+use crate::error::{Error, Result};
+use crate::formats::MetadataFormat;
+use crate::xml::Element;
+
+pub const METADATA_PREFIX: &str = "mods";
+pub const NAMESPACE: &str = "http://www.loc.gov/mods/v3";
+pub const SCHEMA: &str = "http://www.loc.gov/standards/mods/v3/mods-3-8.xsd";
+
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mods {
+    pub title_info: Vec<TitleInfo>,
+    pub name: Vec<Name>,
+    pub origin_info: Vec<OriginInfo>,
+    pub subject: Vec<Subject>,
+    pub identifier: Vec<Identifier>,
+    pub location: Vec<Location>,
+    pub related_item: Vec<RelatedItem>,
+    pub access_condition: Vec<AccessCondition>,
+    /// Unmodeled elements as standalone XML, in document order
+    pub other: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TitleInfo {
+    pub r#type: Option<String>,
+    pub lang: Option<String>,
+    pub non_sort: Option<String>,
+    pub title: Option<String>,
+    pub sub_title: Option<String>,
+    pub part_number: Vec<String>,
+    pub part_name: Vec<String>,
+    /// Unmodeled child elements as standalone XML, in document order
+    pub other: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Name {
+    pub r#type: Option<String>,
+    pub authority: Option<String>,
+    pub value_uri: Option<String>,
+    pub name_part: Vec<NamePart>,
+    pub display_form: Option<String>,
+    pub affiliation: Vec<String>,
+    pub role: Vec<Role>,
+    /// Unmodeled child elements as standalone XML, e.g. `description`, in document order
+    pub other: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamePart {
+    pub r#type: Option<String>,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Role {
+    pub role_term: Vec<Term>,
+}
+
+/// A controlled term such as `roleTerm` or `placeTerm`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Term {
+    pub r#type: Option<String>,
+    pub authority: Option<String>,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OriginInfo {
+    pub event_type: Option<String>,
+    pub place: Vec<Term>,
+    pub publisher: Vec<String>,
+    pub date_issued: Vec<Date>,
+    pub date_created: Vec<Date>,
+    pub date_captured: Vec<Date>,
+    pub date_modified: Vec<Date>,
+    pub copyright_date: Vec<Date>,
+    pub date_other: Vec<Date>,
+    pub edition: Option<String>,
+    pub issuance: Option<String>,
+    /// Unmodeled child elements as standalone XML, e.g. `frequency`, in document order
+    pub other: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Date {
+    pub value: String,
+    pub encoding: Option<String>,
+    pub point: Option<String>,
+    pub qualifier: Option<String>,
+    pub key_date: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Subject {
+    pub authority: Option<String>,
+    pub topic: Vec<String>,
+    pub geographic: Vec<String>,
+    pub temporal: Vec<String>,
+    pub genre: Vec<String>,
+    pub name: Vec<Name>,
+    pub title_info: Vec<TitleInfo>,
+    /// Unmodeled child elements as standalone XML, e.g. `hierarchicalGeographic`,
+    /// `cartographics` or `occupation`, in document order
+    pub other: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Identifier {
+    pub r#type: Option<String>,
+    pub value: String,
+    pub invalid: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Location {
+    pub physical_location: Vec<String>,
+    pub shelf_locator: Vec<String>,
+    pub url: Vec<LocationUrl>,
+    /// Unmodeled child elements as standalone XML, e.g. `holdingSimple`, in document order
+    pub other: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocationUrl {
+    pub value: String,
+    pub usage: Option<String>,
+    pub access: Option<String>,
+    pub display_label: Option<String>,
+}
+
+/// A related resource, described with the same elements as the record itself
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RelatedItem {
+    pub r#type: Option<String>,
+    pub display_label: Option<String>,
+    pub href: Option<String>,
+    pub mods: Mods,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessCondition {
+    pub r#type: Option<String>,
+    pub href: Option<String>,
+    pub value: String,
+}
+
+// In-scope namespace declarations, used to make preserved elements standalone
+type Scope = Vec<(String, String)>;
+
+impl Mods {
+    /// Parse a single `mods` element, or a `modsCollection` containing exactly one
+    ///
+    /// # Example
+    /// ```
+    /// use oai_pmh::formats::mods::Mods;
+    ///
+    /// let xml = r#"<mods xmlns="http://www.loc.gov/mods/v3">
+    ///   <titleInfo><title>Harbor at dusk</title></titleInfo>
+    ///   <typeOfResource>still image</typeOfResource>
+    /// </mods>"#;
+    ///
+    /// let mods = Mods::parse(xml).unwrap();
+    /// assert_eq!(mods.title_info[0].title.as_deref(), Some("Harbor at dusk"));
+    /// assert_eq!(
+    ///     mods.other,
+    ///     vec![r#"<typeOfResource xmlns="http://www.loc.gov/mods/v3">still image</typeOfResource>"#]
+    /// );
+    /// ```
+    pub fn parse(xml: &str) -> Result<Self> {
+        let mut records = Self::parse_collection(xml)?;
+        match records.len() {
+            1 => Ok(records.remove(0)),
+            n => Err(Error::Metadata(format!(
+                "expected a single MODS record, found {n}"
+            ))),
+        }
+    }
+
+    /// Parse a `modsCollection` (a lone `mods` element yields one item)
+    pub fn parse_collection(xml: &str) -> Result<Vec<Self>> {
+        let root = Element::parse(xml)?;
+        let scope = root.scope(&[]);
+
+        match root.local_name() {
+            "mods" => Ok(vec![Self::from_element(&root, &scope)]),
+            "modsCollection" => Ok(root
+                .elements_named("mods")
+                .map(|e| Self::from_element(e, &e.scope(&scope)))
+                .collect()),
+            _ => Err(Error::Metadata(format!(
+                "expected MODS mods or modsCollection, found {}",
+                root.name
+            ))),
+        }
+    }
+
+    fn from_element(element: &Element, scope: &Scope) -> Self {
+        let mut mods = Self::default();
+
+        for child in element.elements() {
+            let is_mods = matches!(child.namespace.as_deref(), None | Some(NAMESPACE));
+            match child.local_name() {
+                "titleInfo" if is_mods => mods.title_info.push(title_info(child, scope)),
+                "name" if is_mods => mods.name.push(name(child, scope)),
+                "originInfo" if is_mods => mods.origin_info.push(origin_info(child, scope)),
+                "subject" if is_mods => mods.subject.push(subject(child, scope)),
+                "identifier" if is_mods => mods.identifier.push(Identifier {
+                    r#type: child.attr("type").map(String::from),
+                    value: child.trimmed_text(),
+                    invalid: child.attr("invalid") == Some("yes"),
+                }),
+                "location" if is_mods => mods.location.push(location(child, scope)),
+                "relatedItem" if is_mods => {
+                    let scope = child.scope(scope);
+                    mods.related_item.push(RelatedItem {
+                        r#type: child.attr("type").map(String::from),
+                        display_label: child.attr("displayLabel").map(String::from),
+                        href: xlink_href(child),
+                        mods: Self::from_element(child, &scope),
+                    })
+                }
+                "accessCondition" if is_mods => mods.access_condition.push(AccessCondition {
+                    r#type: child.attr("type").map(String::from),
+                    href: xlink_href(child),
+                    value: child.trimmed_text(),
+                }),
+                _ => mods.other.push(child.to_xml_in_scope(scope)),
+            }
+        }
+
+        mods
+    }
+
+    /// The first title, with any non-sorting prefix and subtitle
+    pub fn title(&self) -> Option<String> {
+        let info = self
+            .title_info
+            .iter()
+            .find(|t| t.r#type.is_none())
+            .or_else(|| self.title_info.first())?;
+
+        let mut title = format!(
+            "{}{}",
+            info.non_sort.as_deref().unwrap_or_default(),
+            info.title.as_deref()?
+        );
+        if let Some(sub_title) = &info.sub_title {
+            title.push_str(": ");
+            title.push_str(sub_title);
+        }
+        Some(title)
+    }
+}
+
+fn xlink_href(element: &Element) -> Option<String> {
+    element
+        .attr_ns(XLINK_NAMESPACE, "href")
+        .map(|s| s.to_string())
+}
+
+// Children other than the `modeled` ones, as standalone XML; `scope` is the parent's
+fn other(element: &Element, scope: &Scope, modeled: &[&str]) -> Vec<String> {
+    let scope = element.scope(scope);
+    element
+        .elements()
+        .filter(|e| !modeled.contains(&e.local_name()))
+        .map(|e| e.to_xml_in_scope(&scope))
+        .collect()
+}
+
+fn term(element: &Element) -> Term {
+    Term {
+        r#type: element.attr("type").map(String::from),
+        authority: element.attr("authority").map(String::from),
+        value: element.trimmed_text(),
+    }
+}
+
+fn date(element: &Element) -> Date {
+    Date {
+        value: element.trimmed_text(),
+        encoding: element.attr("encoding").map(String::from),
+        point: element.attr("point").map(String::from),
+        qualifier: element.attr("qualifier").map(String::from),
+        key_date: element.attr("keyDate") == Some("yes"),
+    }
+}
+
+fn title_info(element: &Element, scope: &Scope) -> TitleInfo {
+    TitleInfo {
+        r#type: element.attr("type").map(String::from),
+        lang: element
+            .attr("lang")
+            .map(String::from)
+            .or_else(|| element.attr("xml:lang").map(String::from)),
+        non_sort: element
+            .elements_named("nonSort")
+            .next()
+            .map(|e| e.text().trim_start().to_string()),
+        title: element.child_text("title"),
+        sub_title: element.child_text("subTitle"),
+        part_number: element.child_texts("partNumber"),
+        part_name: element.child_texts("partName"),
+        other: other(
+            element,
+            scope,
+            &["nonSort", "title", "subTitle", "partNumber", "partName"],
+        ),
+    }
+}
+
+fn name(element: &Element, scope: &Scope) -> Name {
+    Name {
+        r#type: element.attr("type").map(String::from),
+        authority: element.attr("authority").map(String::from),
+        value_uri: element.attr("valueURI").map(String::from),
+        name_part: element
+            .elements_named("namePart")
+            .map(|e| NamePart {
+                r#type: e.attr("type").map(String::from),
+                value: e.trimmed_text(),
+            })
+            .collect(),
+        display_form: element.child_text("displayForm"),
+        affiliation: element.child_texts("affiliation"),
+        role: element
+            .elements_named("role")
+            .map(|e| Role {
+                role_term: e.elements_named("roleTerm").map(term).collect(),
+            })
+            .collect(),
+        other: other(
+            element,
+            scope,
+            &["namePart", "displayForm", "affiliation", "role"],
+        ),
+    }
+}
+
+fn origin_info(element: &Element, scope: &Scope) -> OriginInfo {
+    let dates = |local_name| element.elements_named(local_name).map(date).collect();
+    OriginInfo {
+        event_type: element.attr("eventType").map(String::from),
+        place: element
+            .elements_named("place")
+            .flat_map(|e| e.elements_named("placeTerm"))
+            .map(term)
+            .collect(),
+        publisher: element.child_texts("publisher"),
+        date_issued: dates("dateIssued"),
+        date_created: dates("dateCreated"),
+        date_captured: dates("dateCaptured"),
+        date_modified: dates("dateModified"),
+        copyright_date: dates("copyrightDate"),
+        date_other: dates("dateOther"),
+        edition: element.child_text("edition"),
+        issuance: element.child_text("issuance"),
+        other: other(
+            element,
+            scope,
+            &[
+                "place",
+                "publisher",
+                "dateIssued",
+                "dateCreated",
+                "dateCaptured",
+                "dateModified",
+                "copyrightDate",
+                "dateOther",
+                "edition",
+                "issuance",
+            ],
+        ),
+    }
+}
+
+fn subject(element: &Element, scope: &Scope) -> Subject {
+    let subject_scope = element.scope(scope);
+    Subject {
+        authority: element.attr("authority").map(String::from),
+        topic: element.child_texts("topic"),
+        geographic: element.child_texts("geographic"),
+        temporal: element.child_texts("temporal"),
+        genre: element.child_texts("genre"),
+        name: element
+            .elements_named("name")
+            .map(|e| name(e, &subject_scope))
+            .collect(),
+        title_info: element
+            .elements_named("titleInfo")
+            .map(|e| title_info(e, &subject_scope))
+            .collect(),
+        other: other(
+            element,
+            scope,
+            &[
+                "topic",
+                "geographic",
+                "temporal",
+                "genre",
+                "name",
+                "titleInfo",
+            ],
+        ),
+    }
+}
+
+fn location(element: &Element, scope: &Scope) -> Location {
+    Location {
+        physical_location: element.child_texts("physicalLocation"),
+        shelf_locator: element.child_texts("shelfLocator"),
+        url: element
+            .elements_named("url")
+            .map(|e| LocationUrl {
+                value: e.trimmed_text(),
+                usage: e.attr("usage").map(String::from),
+                access: e.attr("access").map(String::from),
+                display_label: e.attr("displayLabel").map(String::from),
+            })
+            .collect(),
+        other: other(element, scope, &["physicalLocation", "shelfLocator", "url"]),
+    }
+}
+
+impl MetadataFormat for Mods {
+    const PREFIX: &'static str = METADATA_PREFIX;
+    const NAMESPACE: &'static str = NAMESPACE;
+
+    fn from_metadata(xml: &str) -> Result<Self> {
+        Self::parse(xml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> Mods {
+        let xml =
+            std::fs::read_to_string("tests/fixtures/mods.xml").expect("Failed to load fixture");
+        Mods::parse(&xml).unwrap()
+    }
+
+    #[test]
+    fn test_parse_titles_and_names() {
+        let mods = fixture();
+
+        assert_eq!(mods.title_info.len(), 2);
+        assert_eq!(mods.title_info[0].non_sort.as_deref(), Some("The "));
+        assert_eq!(mods.title_info[1].r#type.as_deref(), Some("alternative"));
+        assert_eq!(
+            mods.title().as_deref(),
+            Some("The harbor at dusk: a study in light")
+        );
+
+        let name = &mods.name[0];
+        assert_eq!(name.r#type.as_deref(), Some("personal"));
+        assert_eq!(
+            name.value_uri.as_deref(),
+            Some("http://id.loc.gov/authorities/names/n79021164")
+        );
+        assert_eq!(name.name_part[0].value, "Homer, Winslow");
+        assert_eq!(name.name_part[1].r#type.as_deref(), Some("date"));
+        assert_eq!(name.role[0].role_term[0].value, "Artist");
+        assert_eq!(name.role[0].role_term[1].r#type.as_deref(), Some("code"));
+        assert_eq!(name.affiliation, vec!["National Academy of Design"]);
+    }
+
+    #[test]
+    fn test_parse_origin_subject_identifier_location() {
+        let mods = fixture();
+
+        let origin = &mods.origin_info[0];
+        assert_eq!(origin.place[0].value, "New York");
+        assert_eq!(origin.publisher, vec!["Harper & Brothers"]);
+        assert_eq!(origin.date_created[0].value, "1880");
+        assert!(origin.date_created[0].key_date);
+        assert_eq!(origin.date_created[0].encoding.as_deref(), Some("w3cdtf"));
+        assert_eq!(origin.issuance.as_deref(), Some("monographic"));
+
+        assert_eq!(mods.subject[0].authority.as_deref(), Some("lcsh"));
+        assert_eq!(mods.subject[0].topic, vec!["Harbors", "Painting"]);
+        assert_eq!(mods.subject[0].geographic, vec!["Gloucester (Mass.)"]);
+        assert_eq!(mods.subject[1].name[0].name_part[0].value, "Homer, Winslow");
+
+        assert_eq!(mods.identifier.len(), 2);
+        assert_eq!(mods.identifier[0].r#type.as_deref(), Some("hdl"));
+        assert!(mods.identifier[1].invalid);
+
+        let url = &mods.location[0].url[0];
+        assert_eq!(url.value, "https://hdl.handle.net/2027/abc123");
+        assert_eq!(url.usage.as_deref(), Some("primary display"));
+        assert_eq!(mods.location[0].physical_location, vec!["Gallery 4"]);
+    }
+
+    #[test]
+    fn test_parse_related_items_recursively() {
+        let mods = fixture();
+
+        let host = &mods.related_item[0];
+        assert_eq!(host.r#type.as_deref(), Some("host"));
+        assert_eq!(
+            host.mods.title_info[0].title.as_deref(),
+            Some("Marine paintings collection")
+        );
+
+        let series = &host.mods.related_item[0];
+        assert_eq!(series.r#type.as_deref(), Some("series"));
+        assert_eq!(series.href.as_deref(), Some("https://example.edu/series/7"));
+        assert_eq!(series.mods.identifier[0].value, "series-7");
+
+        // Unmodeled elements inside related items are preserved too
+        assert_eq!(host.mods.other.len(), 1);
+        assert!(host.mods.other[0].starts_with("<mods:note"));
+    }
+
+    #[test]
+    fn test_parse_access_conditions_and_preserves_unknown_elements() {
+        let mods = fixture();
+
+        assert_eq!(
+            mods.access_condition[0].r#type.as_deref(),
+            Some("use and reproduction")
+        );
+        assert_eq!(
+            mods.access_condition[0].href.as_deref(),
+            Some("http://rightsstatements.org/vocab/NoC-US/1.0/")
+        );
+        assert_eq!(
+            mods.access_condition[0].value,
+            "No Copyright - United States"
+        );
+
+        // typeOfResource, abstract, and the extension with its own namespace
+        assert_eq!(mods.other.len(), 3);
+        assert!(mods.other[0].starts_with("<mods:typeOfResource xmlns:mods="));
+
+        let extension = Element::parse(&mods.other[2]).unwrap();
+        let local = extension.elements().next().unwrap();
+        assert_eq!(
+            local.namespace.as_deref(),
+            Some("https://example.edu/local")
+        );
+        assert_eq!(local.text(), "Shelved with oversize items");
+    }
+
+    #[test]
+    fn test_preserves_unknown_nested_elements() {
+        let xml = r#"<mods xmlns="http://www.loc.gov/mods/v3" xmlns:xl="http://www.w3.org/1999/xlink">
+          <subject>
+            <topic>Harbors</topic>
+            <hierarchicalGeographic><country>United States</country><city>Gloucester</city></hierarchicalGeographic>
+            <cartographics><coordinates>42.6N 70.7W</coordinates></cartographics>
+            <occupation>Painters</occupation>
+          </subject>
+          <name><namePart>Homer, Winslow</namePart><description>Painter</description></name>
+          <accessCondition xl:href="http://rightsstatements.org/vocab/InC/1.0/">In Copyright</accessCondition>
+        </mods>"#;
+
+        let mods = Mods::parse(xml).unwrap();
+        let subject = &mods.subject[0];
+        assert_eq!(subject.topic, vec!["Harbors"]);
+        let other: Vec<_> = subject
+            .other
+            .iter()
+            .map(|xml| Element::parse(xml).unwrap())
+            .collect();
+        let names: Vec<_> = other.iter().map(|e| e.local_name()).collect();
+        assert_eq!(
+            names,
+            ["hierarchicalGeographic", "cartographics", "occupation"]
+        );
+        assert_eq!(other[0].namespace.as_deref(), Some(NAMESPACE));
+        assert_eq!(other[0].text(), "United StatesGloucester");
+        assert_eq!(
+            mods.name[0].other,
+            [
+                r#"<description xmlns="http://www.loc.gov/mods/v3" xmlns:xl="http://www.w3.org/1999/xlink">Painter</description>"#
+            ]
+        );
+
+        // xlink is matched by namespace, not by the `xlink` prefix
+        assert_eq!(
+            mods.access_condition[0].href.as_deref(),
+            Some("http://rightsstatements.org/vocab/InC/1.0/")
+        );
+    }
+
+    #[test]
+    fn test_parse_collection() {
+        let xml = r#"<modsCollection xmlns="http://www.loc.gov/mods/v3">
+          <mods><titleInfo><title>One</title></titleInfo></mods>
+          <mods><titleInfo><title>Two</title></titleInfo></mods>
+        </modsCollection>"#;
+
+        let records = Mods::parse_collection(xml).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].title().as_deref(), Some("Two"));
+        assert!(matches!(Mods::parse(xml), Err(Error::Metadata(_))));
+
+        let xml = r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/"/>"#;
+        assert!(matches!(Mods::parse(xml), Err(Error::Metadata(_))));
+    }
+}
@@ -0,0 +1,190 @@
+//! Dublin Core (`oai_dc`), the format every OAI-PMH repository must support.
+use crate::error::{Error, Result};
+use crate::formats::MetadataFormat;
+use crate::xml::Element;
+
+pub const METADATA_PREFIX: &str = "oai_dc";
+pub const NAMESPACE: &str = "http://www.openarchives.org/OAI/2.0/oai_dc/";
+pub const SCHEMA: &str = "http://www.openarchives.org/OAI/2.0/oai_dc.xsd";
+pub const DC_NAMESPACE: &str = "http://purl.org/dc/elements/1.1/";
+
+/// A Dublin Core element value with its optional `xml:lang`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Value {
+    pub value: String,
+    pub lang: Option<String>,
+}
+
+/// The fifteen Dublin Core elements, each repeatable
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DublinCore {
+    pub title: Vec<Value>,
+    pub creator: Vec<Value>,
+    pub subject: Vec<Value>,
+    pub description: Vec<Value>,
+    pub publisher: Vec<Value>,
+    pub contributor: Vec<Value>,
+    pub date: Vec<Value>,
+    pub r#type: Vec<Value>,
+    pub format: Vec<Value>,
+    pub identifier: Vec<Value>,
+    pub source: Vec<Value>,
+    pub language: Vec<Value>,
+    pub relation: Vec<Value>,
+    pub coverage: Vec<Value>,
+    pub rights: Vec<Value>,
+}
+
+impl DublinCore {
+    /// Parse an `oai_dc:dc` metadata fragment
+    ///
+    /// Elements outside the Dublin Core namespace are ignored.
+    ///
+    /// # Example
+    /// ```
+    /// use oai_pmh::formats::oai_dc::DublinCore;
+    ///
+    /// let xml = r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/"
+    ///                         xmlns:dc="http://purl.org/dc/elements/1.1/">
+    ///   <dc:title xml:lang="en">Minutes</dc:title>
+    ///   <dc:creator>Doe, Jane</dc:creator>
+    /// </oai_dc:dc>"#;
+    ///
+    /// let dc = DublinCore::parse(xml).unwrap();
+    /// assert_eq!(dc.title[0].value, "Minutes");
+    /// assert_eq!(dc.title[0].lang.as_deref(), Some("en"));
+    /// assert_eq!(dc.creator[0].value, "Doe, Jane");
+    /// ```
+    pub fn parse(xml: &str) -> Result<Self> {
+        let root = Element::parse(xml)?;
+        if root.local_name() != "dc" {
+            return Err(Error::Metadata(format!(
+                "expected oai_dc:dc root element, found {}",
+                root.name
+            )));
+        }
+
+        let mut dc = Self::default();
+        for element in root.elements() {
+            if element.namespace.as_deref() != Some(DC_NAMESPACE) {
+                continue;
+            }
+
+            let values = match element.local_name() {
+                "title" => &mut dc.title,
+                "creator" => &mut dc.creator,
+                "subject" => &mut dc.subject,
+                "description" => &mut dc.description,
+                "publisher" => &mut dc.publisher,
+                "contributor" => &mut dc.contributor,
+                "date" => &mut dc.date,
+                "type" => &mut dc.r#type,
+                "format" => &mut dc.format,
+                "identifier" => &mut dc.identifier,
+                "source" => &mut dc.source,
+                "language" => &mut dc.language,
+                "relation" => &mut dc.relation,
+                "coverage" => &mut dc.coverage,
+                "rights" => &mut dc.rights,
+                _ => continue,
+            };
+
+            values.push(Value {
+                value: element.text().trim().to_string(),
+                lang: element.attr("xml:lang").map(|s| s.to_string()),
+            });
+        }
+
+        Ok(dc)
+    }
+}
+
+impl MetadataFormat for DublinCore {
+    const PREFIX: &'static str = METADATA_PREFIX;
+    const NAMESPACE: &'static str = NAMESPACE;
+
+    fn from_metadata(xml: &str) -> Result<Self> {
+        Self::parse(xml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::response::ListRecordsResponse;
+
+    #[test]
+    fn test_parse_list_records_fixture() {
+        let xml = std::fs::read_to_string("tests/fixtures/list_records.xml")
+            .expect("Failed to load fixture");
+
+        let response = ListRecordsResponse::new(&xml).unwrap();
+        let records = response.payload.unwrap().record;
+
+        for record in &records {
+            let dc = record.parse_dc().unwrap();
+            assert_eq!(dc.title.len(), 1);
+            assert_eq!(dc.publisher.len(), 2);
+            assert_eq!(dc.identifier.len(), 2);
+        }
+
+        let dc = records[0].parse_dc().unwrap();
+        assert_eq!(dc.title[0].value, "Correspondence about art, 1974–2014");
+        assert_eq!(dc.title[0].lang, None);
+        assert_eq!(dc.date[0].value, "1974–2014");
+        assert_eq!(dc.subject[0].value, "Acting");
+        assert_eq!(
+            dc.rights[0].value,
+            "The collection is open for research. Medical documents are restricted."
+        );
+        assert_eq!(
+            dc.format[0].value,
+            "2.625 Linear Feet; 3 standard-size legal size document cases\n1 oversize flat case"
+        );
+        assert!(dc.creator.is_empty());
+
+        let dc = records[1].parse_dc().unwrap();
+        assert_eq!(
+            dc.creator
+                .iter()
+                .map(|v| v.value.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Saholt, Richard, (1924 - 2014)", "Bonesteel, Michael"]
+        );
+    }
+
+    #[test]
+    fn test_parse_keeps_lang_and_ignores_other_namespaces() {
+        let xml = r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/"
+            xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/">
+          <dc:title xml:lang="en">The Cat</dc:title>
+          <dc:title xml:lang="fr">Le Chat</dc:title>
+          <dc:type>Text</dc:type>
+          <dcterms:abstract>Ignored</dcterms:abstract>
+        </oai_dc:dc>"#;
+
+        let dc = DublinCore::parse(xml).unwrap();
+        assert_eq!(
+            dc.title,
+            vec![
+                Value {
+                    value: "The Cat".to_string(),
+                    lang: Some("en".to_string())
+                },
+                Value {
+                    value: "Le Chat".to_string(),
+                    lang: Some("fr".to_string())
+                },
+            ]
+        );
+        assert_eq!(dc.r#type[0].value, "Text");
+        assert!(dc.description.is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_other_formats() {
+        let xml = r#"<ead xmlns="urn:isbn:1-931666-22-9"><eadheader/></ead>"#;
+        assert!(matches!(DublinCore::parse(xml), Err(Error::Metadata(_))));
+        assert!(DublinCore::parse("").is_err());
+    }
+}
@@ -3,8 +3,18 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod client;
+#[cfg(feature = "crosswalk")]
+pub mod crosswalk;
 pub mod error;
+#[cfg(feature = "formats")]
+pub mod formats;
+#[cfg(feature = "provider")]
+pub mod provider;
+#[cfg(feature = "xml")]
+pub mod xml;
 
 pub use client::Client;
 pub use client::query::*;
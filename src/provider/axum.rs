@@ -0,0 +1,131 @@
+//! Serving a [`Provider`] with [axum](https://crates.io/crates/axum).
+//!
+//! [`router`] turns a provider into an axum [`Router`] (itself a `tower::Service`)
+//! that answers OAI-PMH requests at its root, so it can be nested at any path of an
+//! existing app. GET requests are read from the query string and POST requests from
+//! the `application/x-www-form-urlencoded` body; POSTs of any other content type get
+//! `415 Unsupported Media Type`. Responses are
+//! `text/xml; charset=utf-8` and gzip-compressed when the request accepts it.
+//! Repository calls run on the blocking thread pool, and
+//! [internal](crate::provider::ProviderError::internal) repository errors are answered
+//! with `500 Internal Server Error`.
+//!
+//! # Example
+//! ```no_run
+//! # use oai_pmh::provider::{Provider, Repository};
+//! # async fn serve(repository: impl Repository + Send + Sync + 'static) {
+//! use axum::Router;
+//! use oai_pmh::provider::axum::router;
+//!
+//! let app = Router::new().nest("/oai", router(Provider::new(repository)));
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+//! axum::serve(listener, app).await.unwrap();
+//! # }
+//! ```
+use std::sync::Arc;
+use std::time::Duration;
+
+use ::axum::Router;
+use ::axum::body::Bytes;
+use ::axum::extract::{RawQuery, State};
+use ::axum::http::{HeaderMap, StatusCode, header};
+use ::axum::response::{IntoResponse, Response};
+use ::axum::routing::get;
+use tower_http::compression::CompressionLayer;
+
+use crate::provider::{Provider, Repository};
+
+type LoadCheck = dyn Fn() -> Option<Duration> + Send + Sync;
+
+/// A [`Router`] serving `provider` at `/`
+pub fn router<R: Repository + Send + Sync + 'static>(provider: Provider<R>) -> Router {
+    Endpoint::new(provider).router()
+}
+
+/// An OAI-PMH endpoint with optional load shedding
+pub struct Endpoint<R> {
+    provider: Provider<R>,
+    overloaded: Option<Box<LoadCheck>>,
+}
+
+impl<R: Repository + Send + Sync + 'static> Endpoint<R> {
+    pub fn new(provider: Provider<R>) -> Self {
+        Self {
+            provider,
+            overloaded: None,
+        }
+    }
+
+    /// Check load before each request
+    ///
+    /// When `check` returns a duration the request is answered with
+    /// `503 Service Unavailable` and a `Retry-After` header in whole seconds,
+    /// which harvesters are expected to honour.
+    pub fn overloaded(
+        mut self,
+        check: impl Fn() -> Option<Duration> + Send + Sync + 'static,
+    ) -> Self {
+        self.overloaded = Some(Box::new(check));
+        self
+    }
+
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/", get(handle_get::<R>).post(handle_post::<R>))
+            .with_state(Arc::new(self))
+            .layer(CompressionLayer::new())
+    }
+
+    async fn respond(self: Arc<Self>, form: Vec<u8>) -> Response {
+        if let Some(retry_after) = self.overloaded.as_ref().and_then(|check| check()) {
+            let seconds = retry_after.as_secs().max(1).to_string();
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                [(header::RETRY_AFTER, seconds)],
+            )
+                .into_response();
+        }
+
+        let xml = tokio::task::spawn_blocking(move || {
+            let params: Vec<(String, String)> =
+                url::form_urlencoded::parse(&form).into_owned().collect();
+            self.provider.handle(&params)
+        })
+        .await;
+
+        match xml {
+            Ok(Ok(xml)) => {
+                ([(header::CONTENT_TYPE, "text/xml; charset=utf-8")], xml).into_response()
+            }
+            Ok(Err(_)) | Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        }
+    }
+}
+
+async fn handle_get<R: Repository + Send + Sync + 'static>(
+    State(endpoint): State<Arc<Endpoint<R>>>,
+    RawQuery(query): RawQuery,
+) -> Response {
+    endpoint
+        .respond(query.unwrap_or_default().into_bytes())
+        .await
+}
+
+async fn handle_post<R: Repository + Send + Sync + 'static>(
+    State(endpoint): State<Arc<Endpoint<R>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let form = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|mime| {
+            mime.trim()
+                .eq_ignore_ascii_case("application/x-www-form-urlencoded")
+        });
+    if !form {
+        return StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response();
+    }
+    endpoint.respond(body.to_vec()).await
+}
@@ -0,0 +1,118 @@
+//! Disseminating metadata formats derived from a repository's native ones.
+//!
+//! A [`Converter`] registered with [`Provider::converter`](super::Provider::converter)
+//! turns records in a source format into metadata in a target format on the fly, so a
+//! repository storing MARCXML can also serve the mandatory `oai_dc`. The provider
+//! advertises the target format wherever the source format is available, and answers
+//! `cannotDisseminateFormat` for items the converter refuses.
+//!
+//! # Example
+//! ```
+//! # #[cfg(feature = "formats")] {
+//! # use oai_pmh::client::response::Identify;
+//! # let identify = Identify {
+//! #     repository_name: "Catalog".to_string(),
+//! #     base_url: "https://example.org/oai".to_string(),
+//! #     protocol_version: "2.0".to_string(),
+//! #     admin_email: vec!["admin@example.org".to_string()],
+//! #     earliest_datestamp: "2024-01-01".to_string(),
+//! #     deleted_record: "no".to_string(),
+//! #     granularity: "YYYY-MM-DD".to_string(),
+//! #     compression: Vec::new(),
+//! #     description: Vec::new(),
+//! # };
+//! use oai_pmh::client::response::{Header, MetadataFormat, Record};
+//! use oai_pmh::provider::Provider;
+//! use oai_pmh::provider::memory::MemoryRepository;
+//!
+//! fn marc_to_dc(record: &Record) -> Option<String> {
+//!     let marc = record.parse_marc().ok()?;
+//!     let title = marc.data_fields.iter().find(|f| f.tag == "245")?;
+//!     Some(format!(
+//!         r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>{}</dc:title></oai_dc:dc>"#,
+//!         title.subfields[0].value
+//!     ))
+//! }
+//!
+//! let marc21 = MetadataFormat {
+//!     metadata_prefix: "marc21".to_string(),
+//!     metadata_namespace: "http://www.loc.gov/MARC21/slim".to_string(),
+//!     schema: "http://www.loc.gov/standards/marcxml/schema/MARC21slim.xsd".to_string(),
+//! };
+//! let oai_dc = MetadataFormat {
+//!     metadata_prefix: "oai_dc".to_string(),
+//!     metadata_namespace: "http://www.openarchives.org/OAI/2.0/oai_dc/".to_string(),
+//!     schema: "http://www.openarchives.org/OAI/2.0/oai_dc.xsd".to_string(),
+//! };
+//! let record = Record {
+//!     header: Header {
+//!         identifier: "oai:example.org:1".to_string(),
+//!         datestamp: "2024-01-01".to_string(),
+//!         status: None,
+//!         set_spec: Vec::new(),
+//!     },
+//!     metadata: r#"<record xmlns="http://www.loc.gov/MARC21/slim"><leader>00000nam a2200000 a 4500</leader><datafield tag="245" ind1="0" ind2="0"><subfield code="a">Harbor at dusk</subfield></datafield></record>"#.to_string(),
+//!     about: String::new(),
+//! };
+//!
+//! let repository = MemoryRepository::new(identify)
+//!     .format(marc21)
+//!     .records("marc21", [record]);
+//! let provider = Provider::new(repository).converter(oai_dc, "marc21", marc_to_dc);
+//!
+//! let xml = provider
+//!     .handle_query("verb=GetRecord&identifier=oai:example.org:1&metadataPrefix=oai_dc")
+//!     .unwrap();
+//! assert!(xml.contains("<dc:title>Harbor at dusk</dc:title>"));
+//! # }
+//! ```
+use crate::client::response::{ErrorCode, MetadataFormat, Record};
+use crate::provider::ProviderError;
+
+/// Derives the metadata of one format from a record in another
+pub trait Converter: Send + Sync {
+    /// The target metadata XML for `record`, or `None` if it can't be converted
+    ///
+    /// Deleted records have no metadata and are never passed to the converter.
+    fn convert(&self, record: &Record) -> Option<String>;
+}
+
+impl<F> Converter for F
+where
+    F: Fn(&Record) -> Option<String> + Send + Sync,
+{
+    fn convert(&self, record: &Record) -> Option<String> {
+        self(record)
+    }
+}
+
+// A target format served by converting records in the `source` format
+pub(super) struct Derived {
+    pub(super) format: MetadataFormat,
+    pub(super) source: String,
+    pub(super) converter: Box<dyn Converter>,
+}
+
+impl Derived {
+    pub(super) fn prefix(&self) -> &str {
+        &self.format.metadata_prefix
+    }
+
+    /// `record` with its metadata converted, keeping the header and `about`
+    pub(super) fn convert(&self, record: Record) -> Result<Record, ProviderError> {
+        if record.is_deleted() {
+            return Ok(record);
+        }
+        let metadata = self.converter.convert(&record).ok_or_else(|| {
+            ProviderError::new(
+                ErrorCode::CannotDisseminateFormat,
+                format!(
+                    "The record {} cannot be disseminated in {}",
+                    record.header.identifier,
+                    self.prefix()
+                ),
+            )
+        })?;
+        Ok(Record { metadata, ..record })
+    }
+}
@@ -0,0 +1,281 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Datestamp granularity supported by a repository
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Granularity {
+    /// `YYYY-MM-DD`
+    Day,
+    /// `YYYY-MM-DDThh:mm:ssZ`
+    Second,
+}
+
+impl Granularity {
+    /// Parse the `granularity` value of an `Identify` response
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "YYYY-MM-DD" => Some(Granularity::Day),
+            "YYYY-MM-DDThh:mm:ssZ" => Some(Granularity::Second),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Granularity::Day => "YYYY-MM-DD",
+            Granularity::Second => "YYYY-MM-DDThh:mm:ssZ",
+        }
+    }
+}
+
+impl fmt::Display for Granularity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A UTC datestamp, stored as seconds since the Unix epoch
+///
+/// OAI-PMH datestamps are always UTC, so there is no time zone handling.
+/// Parsing accepts exactly the two protocol formats, `YYYY-MM-DD` and
+/// `YYYY-MM-DDThh:mm:ssZ`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Datestamp {
+    seconds: i64,
+}
+
+impl Datestamp {
+    pub fn now() -> Self {
+        Self::from(SystemTime::now())
+    }
+
+    pub fn from_unix(seconds: i64) -> Self {
+        Self { seconds }
+    }
+
+    pub fn unix(&self) -> i64 {
+        self.seconds
+    }
+
+    /// Parse a datestamp, returning it with the granularity it was written in
+    pub fn parse(s: &str) -> Option<(Self, Granularity)> {
+        let bytes = s.as_bytes();
+        let (date, granularity) = match bytes.len() {
+            10 => (bytes, Granularity::Day),
+            20 if bytes[10] == b'T' && bytes[19] == b'Z' => (&bytes[..10], Granularity::Second),
+            _ => return None,
+        };
+
+        if date[4] != b'-' || date[7] != b'-' {
+            return None;
+        }
+        let year = digits(&date[0..4])?;
+        let month = digits(&date[5..7])?;
+        let day = digits(&date[8..10])?;
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+
+        let mut seconds = days_from_civil(year, month, day) * SECONDS_PER_DAY;
+        if granularity == Granularity::Second {
+            let time = &bytes[11..19];
+            if time[2] != b':' || time[5] != b':' {
+                return None;
+            }
+            let (hour, minute, second) = (
+                digits(&time[0..2])?,
+                digits(&time[3..5])?,
+                digits(&time[6..8])?,
+            );
+            if hour > 23 || minute > 59 || second > 59 {
+                return None;
+            }
+            seconds += hour * 3600 + minute * 60 + second;
+        }
+
+        Some((Self { seconds }, granularity))
+    }
+
+    /// Format at the given granularity, truncating the time for [`Granularity::Day`]
+    pub fn format(&self, granularity: Granularity) -> String {
+        let days = self.seconds.div_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+
+        match granularity {
+            Granularity::Day => format!("{year:04}-{month:02}-{day:02}"),
+            Granularity::Second => {
+                let time = self.seconds.rem_euclid(SECONDS_PER_DAY);
+                format!(
+                    "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+                    time / 3600,
+                    time % 3600 / 60,
+                    time % 60
+                )
+            }
+        }
+    }
+
+    /// The last second of the same day
+    pub fn end_of_day(&self) -> Self {
+        Self {
+            seconds: self.seconds.div_euclid(SECONDS_PER_DAY) * SECONDS_PER_DAY + SECONDS_PER_DAY
+                - 1,
+        }
+    }
+}
+
+impl From<SystemTime> for Datestamp {
+    fn from(time: SystemTime) -> Self {
+        let seconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs() as i64),
+        };
+        Self { seconds }
+    }
+}
+
+impl fmt::Display for Datestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(Granularity::Second))
+    }
+}
+
+/// Inclusive `from`/`until` bounds for selective harvesting
+///
+/// An `until` given at day granularity covers the whole day, so
+/// `until=2024-01-31` matches records stamped `2024-01-31T23:59:59Z`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: Option<Datestamp>,
+    pub until: Option<Datestamp>,
+}
+
+impl DateRange {
+    /// Parse request arguments, returning `None` if either is not a valid datestamp
+    pub fn parse(from: Option<&str>, until: Option<&str>) -> Option<Self> {
+        let from = match from {
+            Some(s) => Some(Datestamp::parse(s)?.0),
+            None => None,
+        };
+        let until = match until {
+            Some(s) => match Datestamp::parse(s)? {
+                (d, Granularity::Day) => Some(d.end_of_day()),
+                (d, Granularity::Second) => Some(d),
+            },
+            None => None,
+        };
+        Some(Self { from, until })
+    }
+
+    pub fn contains(&self, datestamp: Datestamp) -> bool {
+        self.from.is_none_or(|from| datestamp >= from)
+            && self.until.is_none_or(|until| datestamp <= until)
+    }
+}
+
+fn digits(bytes: &[u8]) -> Option<i64> {
+    bytes.iter().try_fold(0i64, |n, b| {
+        b.is_ascii_digit().then(|| n * 10 + i64::from(b - b'0'))
+    })
+}
+
+fn is_leap(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Days since 1970-01-01 in the proleptic Gregorian calendar (Howard Hinnant's algorithm)
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_both_granularities() {
+        let (day, g) = Datestamp::parse("1970-01-02").unwrap();
+        assert_eq!(g, Granularity::Day);
+        assert_eq!(day.unix(), 86_400);
+
+        let (second, g) = Datestamp::parse("2024-02-29T12:34:56Z").unwrap();
+        assert_eq!(g, Granularity::Second);
+        assert_eq!(second.unix(), 1_709_210_096);
+    }
+
+    #[test]
+    fn rejects_invalid_datestamps() {
+        for s in [
+            "2023-02-29",
+            "2024-13-01",
+            "2024-00-10",
+            "2024-1-01",
+            "2024-01-01T24:00:00Z",
+            "2024-01-01T12:00:00",
+            "2024-01-01T12:00:00+01:00",
+            "2024-01-01 12:00:00Z",
+            "20240101",
+            "",
+        ] {
+            assert!(Datestamp::parse(s).is_none(), "{s}");
+        }
+    }
+
+    #[test]
+    fn formats_round_trip() {
+        for s in [
+            "1899-12-31T23:59:59Z",
+            "2000-02-29T00:00:00Z",
+            "2038-01-19T03:14:08Z",
+        ] {
+            let (d, _) = Datestamp::parse(s).unwrap();
+            assert_eq!(d.to_string(), s);
+        }
+
+        let (d, _) = Datestamp::parse("2024-05-06T07:08:09Z").unwrap();
+        assert_eq!(d.format(Granularity::Day), "2024-05-06");
+    }
+
+    #[test]
+    fn day_until_covers_whole_day() {
+        let range = DateRange::parse(Some("2024-01-01"), Some("2024-01-31")).unwrap();
+        let (last, _) = Datestamp::parse("2024-01-31T23:59:59Z").unwrap();
+        let (after, _) = Datestamp::parse("2024-02-01T00:00:00Z").unwrap();
+        let (before, _) = Datestamp::parse("2023-12-31T23:59:59Z").unwrap();
+
+        assert!(range.contains(last));
+        assert!(!range.contains(after));
+        assert!(!range.contains(before));
+        assert!(DateRange::default().contains(before));
+    }
+}
@@ -0,0 +1,486 @@
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::client::query::ListRecordsArgs;
+use crate::client::response::{ErrorCode, Header, Identify, MetadataFormat, Record, Set};
+use crate::provider::datestamp::{Datestamp, Granularity};
+use crate::provider::memory::{date_range, selected};
+use crate::provider::{Page, ProviderError, Repository};
+
+/// A [`Repository`] serving XML files from a folder tree
+///
+/// Each file is one record in one format, named `<name>.<metadataPrefix>.xml`; files
+/// for other prefixes than the registered formats are ignored, as are hidden files
+/// and folders. Subfolders are sets, nested folders forming a hierarchy, so
+/// `theses/2024/smith.oai_dc.xml` is in set `theses:2024`, and so also in `theses`, and
+/// has the identifier `theses/2024/smith` after the
+/// [identifier prefix](DirectoryRepository::identifier_prefix).
+/// Datestamps are the file modification times.
+///
+/// The tree is read on every request, so changes are served immediately. Lists are
+/// ordered by identifier and resume after the last identifier served.
+///
+/// # Example
+/// ```
+/// # use oai_pmh::client::response::Identify;
+/// # let identify = Identify {
+/// #     repository_name: "Example".to_string(),
+/// #     base_url: "https://example.org/oai".to_string(),
+/// #     protocol_version: "2.0".to_string(),
+/// #     admin_email: vec!["admin@example.org".to_string()],
+/// #     earliest_datestamp: "2024-01-01".to_string(),
+/// #     deleted_record: "no".to_string(),
+/// #     granularity: "YYYY-MM-DD".to_string(),
+/// #     compression: Vec::new(),
+/// #     description: Vec::new(),
+/// # };
+/// use oai_pmh::client::response::MetadataFormat;
+/// use oai_pmh::provider::Provider;
+/// use oai_pmh::provider::directory::DirectoryRepository;
+///
+/// let root = std::env::temp_dir().join(format!("oai-pmh-directory-{}", std::process::id()));
+/// std::fs::create_dir_all(root.join("theses"))?;
+/// std::fs::write(
+///     root.join("theses/smith.oai_dc.xml"),
+///     r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Smith</dc:title></oai_dc:dc>"#,
+/// )?;
+///
+/// let oai_dc = MetadataFormat {
+///     metadata_prefix: "oai_dc".to_string(),
+///     metadata_namespace: "http://www.openarchives.org/OAI/2.0/oai_dc/".to_string(),
+///     schema: "http://www.openarchives.org/OAI/2.0/oai_dc.xsd".to_string(),
+/// };
+/// let repository = DirectoryRepository::open(&root, identify)?
+///     .identifier_prefix("oai:example.org:")
+///     .format(oai_dc);
+/// let provider = Provider::new(repository);
+///
+/// let xml = provider
+///     .handle_query("verb=ListIdentifiers&metadataPrefix=oai_dc&set=theses")
+///     .unwrap();
+/// assert!(xml.contains("<identifier>oai:example.org:theses/smith</identifier>"));
+/// # std::fs::remove_dir_all(&root)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct DirectoryRepository {
+    root: PathBuf,
+    identify: Identify,
+    identifier_prefix: String,
+    formats: Vec<MetadataFormat>,
+}
+
+// A record file found while walking the tree
+struct Entry {
+    header: Header,
+    path: PathBuf,
+}
+
+impl DirectoryRepository {
+    /// Serve the files below `root`, which must be a directory
+    pub fn open(root: impl Into<PathBuf>, identify: Identify) -> io::Result<Self> {
+        let root = root.into();
+        if !fs::metadata(&root)?.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotADirectory,
+                format!("{} is not a directory", root.display()),
+            ));
+        }
+        Ok(Self {
+            root,
+            identify,
+            identifier_prefix: String::new(),
+            formats: Vec::new(),
+        })
+    }
+
+    /// Prepended to the relative file paths to form identifiers, e.g. `oai:example.org:`
+    pub fn identifier_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.identifier_prefix = prefix.into();
+        self
+    }
+
+    /// Serve files with this format's prefix
+    pub fn format(mut self, format: MetadataFormat) -> Self {
+        self.formats.push(format);
+        self
+    }
+
+    fn granularity(&self) -> Granularity {
+        Granularity::parse(&self.identify.granularity).unwrap_or(Granularity::Second)
+    }
+
+    // The file holding `identifier` in `metadata_prefix`, if the identifier is well formed
+    // and names no hidden file or folder
+    fn path(&self, identifier: &str, metadata_prefix: &str) -> Option<PathBuf> {
+        let name = identifier.strip_prefix(&self.identifier_prefix)?;
+        let safe = !name.is_empty()
+            && Path::new(name).components().all(|c| match c {
+                Component::Normal(part) => !part.as_encoded_bytes().starts_with(b"."),
+                _ => false,
+            });
+        safe.then(|| self.root.join(format!("{name}.{metadata_prefix}.xml")))
+    }
+
+    fn header(&self, name: &str, modified: io::Result<std::time::SystemTime>) -> Header {
+        let datestamp = modified
+            .map(Datestamp::from)
+            .unwrap_or_else(|_| Datestamp::now());
+        Header {
+            identifier: format!("{}{name}", self.identifier_prefix),
+            datestamp: datestamp.format(self.granularity()),
+            status: None,
+            set_spec: name
+                .rsplit_once('/')
+                .map(|(folder, _)| vec![folder.replace('/', ":")])
+                .unwrap_or_default(),
+        }
+    }
+
+    fn record(&self, entry: Entry) -> io::Result<Record> {
+        let xml = fs::read_to_string(&entry.path)?;
+        Ok(Record {
+            header: entry.header,
+            metadata: strip_declaration(&xml).to_string(),
+            about: String::new(),
+        })
+    }
+
+    // Record files in `metadata_prefix` below `dir`, whose names start with `folder`
+    fn walk(&self, dir: &Path, folder: &str, metadata_prefix: &str, entries: &mut Vec<Entry>) {
+        let suffix = format!(".{metadata_prefix}.xml");
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let file_name = entry.file_name();
+            let Some(file_name) = file_name.to_str().filter(|n| !n.starts_with('.')) else {
+                continue;
+            };
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                let folder = format!("{folder}{file_name}/");
+                self.walk(&entry.path(), &folder, metadata_prefix, entries);
+            } else if let Some(stem) = file_name.strip_suffix(&suffix) {
+                let modified = entry.metadata().and_then(|m| m.modified());
+                entries.push(Entry {
+                    header: self.header(&format!("{folder}{stem}"), modified),
+                    path: entry.path(),
+                });
+            }
+        }
+    }
+
+    fn walk_sets(dir: &Path, parent: Option<&str>, sets: &mut Vec<Set>) {
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+            let file_name = entry.file_name();
+            let Some(name) = file_name.to_str().filter(|n| is_dir && !n.starts_with('.')) else {
+                continue;
+            };
+            let set_spec = match parent {
+                Some(parent) => format!("{parent}:{name}"),
+                None => name.to_string(),
+            };
+            sets.push(Set {
+                set_spec: set_spec.clone(),
+                set_name: name.to_string(),
+                set_description: Vec::new(),
+            });
+            Self::walk_sets(&entry.path(), Some(&set_spec), sets);
+        }
+    }
+}
+
+impl Repository for DirectoryRepository {
+    fn identify(&self) -> Identify {
+        self.identify.clone()
+    }
+
+    fn metadata_formats(
+        &self,
+        identifier: Option<&str>,
+    ) -> Result<Vec<MetadataFormat>, ProviderError> {
+        let Some(identifier) = identifier else {
+            return Ok(self.formats.clone());
+        };
+        let formats: Vec<MetadataFormat> = self
+            .formats
+            .iter()
+            .filter(|format| {
+                self.path(identifier, &format.metadata_prefix)
+                    .is_some_and(|path| path.is_file())
+            })
+            .cloned()
+            .collect();
+        if formats.is_empty() {
+            return Err(ErrorCode::IdDoesNotExist.into());
+        }
+        Ok(formats)
+    }
+
+    fn sets(&self) -> Result<Vec<Set>, ProviderError> {
+        let mut sets = Vec::new();
+        Self::walk_sets(&self.root, None, &mut sets);
+        sets.sort_by(|a, b| a.set_spec.cmp(&b.set_spec));
+        Ok(sets)
+    }
+
+    fn get_record(&self, identifier: &str, metadata_prefix: &str) -> Result<Record, ProviderError> {
+        let path = self
+            .path(identifier, metadata_prefix)
+            .ok_or(ErrorCode::IdDoesNotExist)?;
+        let name = &identifier[self.identifier_prefix.len()..];
+        let entry = Entry {
+            header: self.header(name, fs::metadata(&path).and_then(|m| m.modified())),
+            path,
+        };
+        self.record(entry).map_err(|_| {
+            if self.metadata_formats(Some(identifier)).is_ok() {
+                ErrorCode::CannotDisseminateFormat.into()
+            } else {
+                ErrorCode::IdDoesNotExist.into()
+            }
+        })
+    }
+
+    fn list_records(
+        &self,
+        args: &ListRecordsArgs,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<Page<Record>, ProviderError> {
+        let range = date_range(args)?;
+        let mut entries = Vec::new();
+        self.walk(&self.root, "", args.metadata_prefix(), &mut entries);
+        entries.retain(|entry| selected(&entry.header, args, &range));
+        entries.sort_by(|a, b| a.header.identifier.cmp(&b.header.identifier));
+
+        let size = entries.len();
+        let start = cursor.map_or(0, |after| {
+            entries.partition_point(|e| e.header.identifier.as_str() <= after)
+        });
+        let rest = entries.split_off(start);
+        let next =
+            (limit > 0 && rest.len() > limit).then(|| rest[limit - 1].header.identifier.clone());
+        // Files removed since the walk are skipped
+        let items: Vec<Record> = rest
+            .into_iter()
+            .take(limit)
+            .filter_map(|entry| self.record(entry).ok())
+            .collect();
+
+        let page = match next {
+            Some(next) => Page::new(items, next),
+            None => Page::last(items),
+        };
+        Ok(page.complete_list_size(size))
+    }
+}
+
+// Drop a BOM and XML declaration so the file can be embedded in a response
+fn strip_declaration(xml: &str) -> &str {
+    let xml = xml.trim_start_matches('\u{feff}').trim_start();
+    match xml
+        .strip_prefix("<?xml")
+        .and_then(|rest| rest.split_once("?>"))
+    {
+        Some((_, rest)) => rest.trim_start(),
+        None => xml,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn identify() -> Identify {
+        Identify {
+            repository_name: "Directory".to_string(),
+            base_url: "https://example.org/oai".to_string(),
+            protocol_version: "2.0".to_string(),
+            admin_email: Vec::new(),
+            earliest_datestamp: "2024-01-01".to_string(),
+            deleted_record: "no".to_string(),
+            granularity: "YYYY-MM-DD".to_string(),
+            compression: Vec::new(),
+            description: Vec::new(),
+        }
+    }
+
+    fn format(prefix: &str) -> MetadataFormat {
+        MetadataFormat {
+            metadata_prefix: prefix.to_string(),
+            metadata_namespace: format!("https://example.org/{prefix}"),
+            schema: format!("https://example.org/{prefix}.xsd"),
+        }
+    }
+
+    // Write `files` (path, day of January 2024) below a fresh temporary directory
+    fn tree(name: &str, files: &[(&str, u64)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("oai-pmh-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (path, day) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, "<?xml version=\"1.0\"?>\n<record/>").unwrap();
+            let modified = UNIX_EPOCH + Duration::from_secs(1_704_067_200 + (day - 1) * 86_400);
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        }
+        root
+    }
+
+    fn repository(root: &Path) -> DirectoryRepository {
+        DirectoryRepository::open(root, identify())
+            .unwrap()
+            .identifier_prefix("oai:example.org:")
+            .format(format("oai_dc"))
+            .format(format("marc21"))
+    }
+
+    #[test]
+    fn open_requires_a_directory() {
+        let root = tree("open", &[("a.oai_dc.xml", 1)]);
+        assert!(DirectoryRepository::open(root.join("a.oai_dc.xml"), identify()).is_err());
+        assert!(DirectoryRepository::open(root.join("missing"), identify()).is_err());
+    }
+
+    #[test]
+    fn folders_are_sets() {
+        let root = tree(
+            "sets",
+            &[
+                ("top.oai_dc.xml", 1),
+                ("theses/2024/smith.oai_dc.xml", 2),
+                ("articles/jones.oai_dc.xml", 3),
+                (".git/config.oai_dc.xml", 1),
+            ],
+        );
+        let repository = repository(&root);
+
+        let specs: Vec<String> = repository
+            .sets()
+            .unwrap()
+            .into_iter()
+            .map(|s| s.set_spec)
+            .collect();
+        assert_eq!(specs, ["articles", "theses", "theses:2024"]);
+
+        let record = repository
+            .get_record("oai:example.org:theses/2024/smith", "oai_dc")
+            .unwrap();
+        assert_eq!(record.header.set_spec, ["theses:2024"]);
+        assert_eq!(record.header.datestamp, "2024-01-02");
+        assert_eq!(record.metadata, "<record/>");
+
+        let args = ListRecordsArgs::new("oai_dc").set("theses");
+        let page = repository.list_records(&args, None, 10).unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(
+            page.items[0].header.identifier,
+            "oai:example.org:theses/2024/smith"
+        );
+    }
+
+    #[test]
+    fn formats_and_errors() {
+        let root = tree(
+            "formats",
+            &[
+                ("a.oai_dc.xml", 1),
+                ("a.marc21.xml", 1),
+                ("b.oai_dc.xml", 2),
+                (".git/config.oai_dc.xml", 2),
+            ],
+        );
+        let repository = repository(&root);
+
+        let formats = repository
+            .metadata_formats(Some("oai:example.org:a"))
+            .unwrap();
+        assert_eq!(formats.len(), 2);
+        let formats = repository
+            .metadata_formats(Some("oai:example.org:b"))
+            .unwrap();
+        assert_eq!(formats[0].metadata_prefix, "oai_dc");
+
+        let err = repository
+            .get_record("oai:example.org:b", "marc21")
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::CannotDisseminateFormat));
+        let err = repository
+            .get_record("oai:example.org:c", "oai_dc")
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::IdDoesNotExist));
+        let err = repository
+            .get_record("oai:example.org:../b", "oai_dc")
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::IdDoesNotExist));
+        let err = repository.get_record("other:a", "oai_dc").unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::IdDoesNotExist));
+
+        // Hidden files are not served, even when named directly
+        let err = repository
+            .get_record("oai:example.org:.git/config", "oai_dc")
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::IdDoesNotExist));
+        let err = repository
+            .metadata_formats(Some("oai:example.org:.git/config"))
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::IdDoesNotExist));
+    }
+
+    #[test]
+    fn list_records_resumes_after_last_identifier() {
+        let root = tree(
+            "list",
+            &[
+                ("a.oai_dc.xml", 1),
+                ("b.oai_dc.xml", 2),
+                ("c.oai_dc.xml", 3),
+                ("c.marc21.xml", 3),
+                ("notes.txt", 3),
+            ],
+        );
+        let repository = repository(&root);
+        let args = ListRecordsArgs::new("oai_dc");
+
+        let page = repository.list_records(&args, None, 2).unwrap();
+        let ids: Vec<&str> = page
+            .items
+            .iter()
+            .map(|r| r.header.identifier.as_str())
+            .collect();
+        assert_eq!(ids, ["oai:example.org:a", "oai:example.org:b"]);
+        assert_eq!(page.next.as_deref(), Some("oai:example.org:b"));
+        assert_eq!(page.complete_list_size, Some(3));
+
+        let page = repository
+            .list_records(&args, page.next.as_deref(), 2)
+            .unwrap();
+        assert_eq!(page.items[0].header.identifier, "oai:example.org:c");
+        assert_eq!(page.next, None);
+
+        let args = ListRecordsArgs::new("oai_dc")
+            .from("2024-01-02")
+            .until("2024-01-02");
+        let page = repository.list_records(&args, None, 10).unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].header.identifier, "oai:example.org:b");
+    }
+
+    #[test]
+    fn strips_xml_declaration() {
+        assert_eq!(
+            strip_declaration("\u{feff}<?xml version=\"1.0\"?>\n<a/>"),
+            "<a/>"
+        );
+        assert_eq!(strip_declaration("<a/>"), "<a/>");
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::client::response::{ErrorCode, ResponseError};
+
+/// An OAI-PMH error to return to the harvester, or an internal failure
+///
+/// Created from an [`ErrorCode`] alone it carries the standard message for that code;
+/// use [`ProviderError::new`] for a more specific one. A repository that fails for
+/// reasons of its own (a database or I/O error) returns [`ProviderError::internal`],
+/// which [`Provider::handle`](super::Provider::handle) passes on to the transport to
+/// answer as a server error instead of an OAI-PMH response.
+#[derive(Clone, Debug)]
+pub struct ProviderError {
+    code: Option<ErrorCode>,
+    message: String,
+    source: Option<Arc<dyn std::error::Error + Send + Sync>>,
+}
+
+impl ProviderError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code: Some(code),
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// An internal failure caused by `source`
+    pub fn internal(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        let source: Arc<dyn std::error::Error + Send + Sync> = Arc::from(source.into());
+        Self {
+            code: None,
+            message: source.to_string(),
+            source: Some(source),
+        }
+    }
+
+    /// The OAI-PMH error code, `None` for an internal failure
+    pub fn code(&self) -> Option<ErrorCode> {
+        self.code
+    }
+
+    pub fn is_internal(&self) -> bool {
+        self.code.is_none()
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub(crate) fn bad_argument(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::BadArgument, message)
+    }
+}
+
+impl From<ErrorCode> for ProviderError {
+    fn from(code: ErrorCode) -> Self {
+        let message = match code {
+            ErrorCode::BadArgument => "The request includes illegal arguments",
+            ErrorCode::BadResumptionToken => "The resumption token is invalid or expired",
+            ErrorCode::BadVerb => "Illegal OAI verb",
+            ErrorCode::CannotDisseminateFormat => {
+                "The metadata format is not supported by the item or repository"
+            }
+            ErrorCode::IdDoesNotExist => "The identifier is unknown or illegal in this repository",
+            ErrorCode::NoRecordsMatch => "The combination of arguments results in an empty list",
+            ErrorCode::NoMetadataFormats => "There are no metadata formats available for the item",
+            ErrorCode::NoSetHierarchy => "The repository does not support sets",
+        };
+        Self::new(code, message)
+    }
+}
+
+impl TryFrom<ProviderError> for ResponseError {
+    type Error = ProviderError;
+
+    /// Fails for internal errors, which have no OAI-PMH equivalent
+    fn try_from(error: ProviderError) -> Result<Self, ProviderError> {
+        match error.code {
+            Some(code) => Ok(ResponseError {
+                code,
+                message: error.message,
+            }),
+            None => Err(error),
+        }
+    }
+}
+
+impl PartialEq for ProviderError {
+    fn eq(&self, other: &Self) -> bool {
+        self.code == other.code && self.message == other.message
+    }
+}
+
+impl std::error::Error for ProviderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
+    }
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "{code}: {}", self.message),
+            None => write!(f, "internal error: {}", self.message),
+        }
+    }
+}
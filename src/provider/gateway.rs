@@ -0,0 +1,121 @@
+use std::fs;
+use std::path::Path;
+
+use quick_xml::escape::escape;
+
+use crate::client::query::ListRecordsArgs;
+use crate::client::response::{Identify, MetadataFormat, Record, Set};
+use crate::client::static_repository::StaticRepository;
+use crate::provider::memory::MemoryRepository;
+use crate::provider::{Page, ProviderError, Repository};
+
+pub const GATEWAY_NAMESPACE: &str = "http://www.openarchives.org/OAI/2.0/gateway/";
+
+const GATEWAY_DESCRIPTION: &str =
+    "http://www.openarchives.org/OAI/2.0/guidelines-static-repository.htm";
+
+/// A [`Repository`] serving an [OAI Static Repository](StaticRepository) document,
+/// as a static repository gateway does
+///
+/// The document is parsed once and served from memory. Static repositories have no
+/// sets, so `ListSets` answers `noSetHierarchy`. The `Identify` description gains a
+/// `gateway` description once the [source](StaticGateway::source) is set.
+///
+/// # Example
+/// ```
+/// use oai_pmh::provider::Provider;
+/// use oai_pmh::provider::gateway::StaticGateway;
+///
+/// let gateway = StaticGateway::open("tests/fixtures/static_repository.xml")?
+///     .source("http://an.oai.org/ma/mini.xml")
+///     .gateway_admin("gateway@example.org");
+/// let provider = Provider::new(gateway);
+/// # Ok::<(), oai_pmh::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct StaticGateway {
+    repository: MemoryRepository,
+    source: Option<String>,
+    admins: Vec<String>,
+}
+
+impl StaticGateway {
+    pub fn new(document: StaticRepository) -> Self {
+        let mut repository = MemoryRepository::new(document.identify);
+        for format in document.metadata_formats {
+            repository = repository.format(format);
+        }
+        for (prefix, records) in document.records {
+            repository = repository.records(prefix, records);
+        }
+        Self {
+            repository,
+            source: None,
+            admins: Vec::new(),
+        }
+    }
+
+    /// Read and parse the static repository document at `path`
+    pub fn open(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let xml = fs::read_to_string(path)?;
+        Ok(Self::new(StaticRepository::parse(&xml)?))
+    }
+
+    /// URL the static repository document is published at, for the gateway description
+    pub fn source(mut self, url: impl Into<String>) -> Self {
+        self.source = Some(url.into());
+        self
+    }
+
+    /// Add an administrator of the gateway to the gateway description
+    pub fn gateway_admin(mut self, email: impl Into<String>) -> Self {
+        self.admins.push(email.into());
+        self
+    }
+
+    fn gateway_description(&self, source: &str) -> String {
+        let mut xml = format!(
+            r#"<gateway xmlns="{GATEWAY_NAMESPACE}"><source>{}</source><gatewayDescription>{GATEWAY_DESCRIPTION}</gatewayDescription>"#,
+            escape(source)
+        );
+        for admin in &self.admins {
+            xml.push_str(&format!("<gatewayAdmin>{}</gatewayAdmin>", escape(admin)));
+        }
+        xml.push_str("</gateway>");
+        xml
+    }
+}
+
+impl Repository for StaticGateway {
+    fn identify(&self) -> Identify {
+        let mut identify = self.repository.identify();
+        if let Some(source) = &self.source {
+            identify.description.push(self.gateway_description(source));
+        }
+        identify
+    }
+
+    fn metadata_formats(
+        &self,
+        identifier: Option<&str>,
+    ) -> Result<Vec<MetadataFormat>, ProviderError> {
+        self.repository.metadata_formats(identifier)
+    }
+
+    fn sets(&self) -> Result<Vec<Set>, ProviderError> {
+        Ok(Vec::new())
+    }
+
+    fn get_record(&self, identifier: &str, metadata_prefix: &str) -> Result<Record, ProviderError> {
+        self.repository.get_record(identifier, metadata_prefix)
+    }
+
+    fn list_records(
+        &self,
+        args: &ListRecordsArgs,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<Page<Record>, ProviderError> {
+        self.repository.list_records(args, cursor, limit)
+    }
+}
@@ -0,0 +1,342 @@
+use crate::client::query::ListRecordsArgs;
+use crate::client::response::{ErrorCode, Header, Identify, MetadataFormat, Record, Set};
+use crate::client::sets::is_within;
+use crate::provider::datestamp::{DateRange, Datestamp};
+use crate::provider::{Page, ProviderError, Repository};
+
+/// A [`Repository`] serving records held in memory
+///
+/// Records are added per metadata prefix, so the same item can be served in several
+/// formats by adding a record with the same identifier under each prefix. Sets and
+/// deletions come from the record headers: `set_spec` places a record in sets, and
+/// their parent sets, and a `status` of `"deleted"` marks it deleted. Lists are
+/// returned in insertion order.
+///
+/// # Example
+/// ```
+/// # use oai_pmh::client::response::Identify;
+/// # let identify = Identify {
+/// #     repository_name: "Example".to_string(),
+/// #     base_url: "https://example.org/oai".to_string(),
+/// #     protocol_version: "2.0".to_string(),
+/// #     admin_email: vec!["admin@example.org".to_string()],
+/// #     earliest_datestamp: "2024-01-01".to_string(),
+/// #     deleted_record: "no".to_string(),
+/// #     granularity: "YYYY-MM-DD".to_string(),
+/// #     compression: Vec::new(),
+/// #     description: Vec::new(),
+/// # };
+/// use oai_pmh::client::response::{Header, MetadataFormat, Record};
+/// use oai_pmh::provider::Provider;
+/// use oai_pmh::provider::memory::MemoryRepository;
+///
+/// let oai_dc = MetadataFormat {
+///     metadata_prefix: "oai_dc".to_string(),
+///     metadata_namespace: "http://www.openarchives.org/OAI/2.0/oai_dc/".to_string(),
+///     schema: "http://www.openarchives.org/OAI/2.0/oai_dc.xsd".to_string(),
+/// };
+/// let record = Record {
+///     header: Header {
+///         identifier: "oai:example.org:1".to_string(),
+///         datestamp: "2024-01-01".to_string(),
+///         status: None,
+///         set_spec: Vec::new(),
+///     },
+///     metadata: r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>One</dc:title></oai_dc:dc>"#.to_string(),
+///     about: String::new(),
+/// };
+///
+/// let repository = MemoryRepository::new(identify)
+///     .format(oai_dc)
+///     .records("oai_dc", [record]);
+/// let provider = Provider::new(repository);
+///
+/// let xml = provider
+///     .handle_query("verb=GetRecord&identifier=oai:example.org:1&metadataPrefix=oai_dc")
+///     .unwrap();
+/// assert!(xml.contains("<dc:title>One</dc:title>"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MemoryRepository {
+    identify: Identify,
+    formats: Vec<MetadataFormat>,
+    sets: Vec<Set>,
+    records: Vec<(String, Record)>,
+}
+
+impl MemoryRepository {
+    pub fn new(identify: Identify) -> Self {
+        Self {
+            identify,
+            formats: Vec::new(),
+            sets: Vec::new(),
+            records: Vec::new(),
+        }
+    }
+
+    /// Advertise a metadata format
+    pub fn format(mut self, format: MetadataFormat) -> Self {
+        self.formats.push(format);
+        self
+    }
+
+    /// Advertise a set
+    pub fn set(mut self, set: Set) -> Self {
+        self.sets.push(set);
+        self
+    }
+
+    /// Add records in the format identified by `metadata_prefix`
+    pub fn records(
+        mut self,
+        metadata_prefix: impl Into<String>,
+        records: impl IntoIterator<Item = Record>,
+    ) -> Self {
+        let prefix = metadata_prefix.into();
+        self.records
+            .extend(records.into_iter().map(|record| (prefix.clone(), record)));
+        self
+    }
+
+    fn contains(&self, identifier: &str) -> bool {
+        self.records
+            .iter()
+            .any(|(_, record)| record.header.identifier == identifier)
+    }
+}
+
+impl Repository for MemoryRepository {
+    fn identify(&self) -> Identify {
+        self.identify.clone()
+    }
+
+    fn metadata_formats(
+        &self,
+        identifier: Option<&str>,
+    ) -> Result<Vec<MetadataFormat>, ProviderError> {
+        let Some(identifier) = identifier else {
+            return Ok(self.formats.clone());
+        };
+        if !self.contains(identifier) {
+            return Err(ErrorCode::IdDoesNotExist.into());
+        }
+        Ok(self
+            .formats
+            .iter()
+            .filter(|format| {
+                self.records.iter().any(|(prefix, record)| {
+                    *prefix == format.metadata_prefix && record.header.identifier == identifier
+                })
+            })
+            .cloned()
+            .collect())
+    }
+
+    fn sets(&self) -> Result<Vec<Set>, ProviderError> {
+        Ok(self.sets.clone())
+    }
+
+    fn get_record(&self, identifier: &str, metadata_prefix: &str) -> Result<Record, ProviderError> {
+        self.records
+            .iter()
+            .find(|(prefix, record)| {
+                prefix == metadata_prefix && record.header.identifier == identifier
+            })
+            .map(|(_, record)| record.clone())
+            .ok_or_else(|| {
+                if self.contains(identifier) {
+                    ErrorCode::CannotDisseminateFormat.into()
+                } else {
+                    ErrorCode::IdDoesNotExist.into()
+                }
+            })
+    }
+
+    fn list_records(
+        &self,
+        args: &ListRecordsArgs,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<Page<Record>, ProviderError> {
+        let range = date_range(args)?;
+        let matches: Vec<&Record> = self
+            .records
+            .iter()
+            .filter(|(prefix, record)| {
+                prefix == args.metadata_prefix() && selected(&record.header, args, &range)
+            })
+            .map(|(_, record)| record)
+            .collect();
+
+        let start: usize = match cursor {
+            Some(cursor) => cursor.parse().map_err(|_| ErrorCode::BadResumptionToken)?,
+            None => 0,
+        };
+        if start > matches.len() {
+            return Err(ErrorCode::BadResumptionToken.into());
+        }
+        let end = (start + limit).min(matches.len());
+        let items = matches[start..end].iter().map(|&r| r.clone()).collect();
+        let page = if end < matches.len() {
+            Page::new(items, end.to_string())
+        } else {
+            Page::last(items)
+        };
+        Ok(page.complete_list_size(matches.len()))
+    }
+}
+
+pub(super) fn date_range(args: &ListRecordsArgs) -> Result<DateRange, ProviderError> {
+    DateRange::parse(args.from_datestamp(), args.until_datestamp())
+        .ok_or_else(|| ErrorCode::BadArgument.into())
+}
+
+// Whether a header passes the `from`, `until` and `set` arguments, where a record
+// in a set is also in its parent sets
+pub(super) fn selected(header: &Header, args: &ListRecordsArgs, range: &DateRange) -> bool {
+    let in_range = Datestamp::parse(&header.datestamp).is_some_and(|(d, _)| range.contains(d));
+    let in_set = args
+        .set_spec()
+        .is_none_or(|set| header.set_spec.iter().any(|s| is_within(s, set)));
+    in_range && in_set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identify() -> Identify {
+        Identify {
+            repository_name: "Memory".to_string(),
+            base_url: "https://example.org/oai".to_string(),
+            protocol_version: "2.0".to_string(),
+            admin_email: Vec::new(),
+            earliest_datestamp: "2024-01-01".to_string(),
+            deleted_record: "persistent".to_string(),
+            granularity: "YYYY-MM-DD".to_string(),
+            compression: Vec::new(),
+            description: Vec::new(),
+        }
+    }
+
+    fn format(prefix: &str) -> MetadataFormat {
+        MetadataFormat {
+            metadata_prefix: prefix.to_string(),
+            metadata_namespace: format!("https://example.org/{prefix}"),
+            schema: format!("https://example.org/{prefix}.xsd"),
+        }
+    }
+
+    fn record(id: usize, sets: &[&str]) -> Record {
+        Record {
+            header: Header {
+                identifier: format!("oai:example.org:{id}"),
+                datestamp: format!("2024-01-0{id}"),
+                status: None,
+                set_spec: sets.iter().map(|s| s.to_string()).collect(),
+            },
+            metadata: format!("<record>{id}</record>"),
+            about: String::new(),
+        }
+    }
+
+    fn repository() -> MemoryRepository {
+        MemoryRepository::new(identify())
+            .format(format("oai_dc"))
+            .format(format("marc21"))
+            .records(
+                "oai_dc",
+                [record(1, &["a"]), record(2, &[]), record(3, &["a", "b"])],
+            )
+            .records("marc21", [record(1, &["a"])])
+    }
+
+    #[test]
+    fn formats_per_item() {
+        let repository = repository();
+
+        let prefixes = |id| -> Vec<String> {
+            repository
+                .metadata_formats(id)
+                .unwrap()
+                .into_iter()
+                .map(|f| f.metadata_prefix)
+                .collect()
+        };
+        assert_eq!(prefixes(None), ["oai_dc", "marc21"]);
+        assert_eq!(prefixes(Some("oai:example.org:1")), ["oai_dc", "marc21"]);
+        assert_eq!(prefixes(Some("oai:example.org:2")), ["oai_dc"]);
+
+        let err = repository
+            .metadata_formats(Some("oai:example.org:9"))
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::IdDoesNotExist));
+    }
+
+    #[test]
+    fn get_record_errors() {
+        let repository = repository();
+
+        assert!(repository.get_record("oai:example.org:1", "marc21").is_ok());
+        let err = repository
+            .get_record("oai:example.org:2", "marc21")
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::CannotDisseminateFormat));
+        let err = repository
+            .get_record("oai:example.org:9", "oai_dc")
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::IdDoesNotExist));
+    }
+
+    #[test]
+    fn list_records_filters_and_pages() {
+        let repository = repository();
+        let args = ListRecordsArgs::new("oai_dc").set("a");
+
+        let page = repository.list_records(&args, None, 1).unwrap();
+        assert_eq!(page.items[0].header.identifier, "oai:example.org:1");
+        assert_eq!(page.complete_list_size, Some(2));
+
+        let page = repository
+            .list_records(&args, page.next.as_deref(), 1)
+            .unwrap();
+        assert_eq!(page.items[0].header.identifier, "oai:example.org:3");
+        assert_eq!(page.next, None);
+
+        let args = ListRecordsArgs::new("oai_dc")
+            .from("2024-01-02")
+            .until("2024-01-02");
+        let page = repository.list_records(&args, None, 10).unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].header.identifier, "oai:example.org:2");
+
+        let err = repository.list_records(&args, Some("x"), 10).unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::BadResumptionToken));
+    }
+
+    #[test]
+    fn parent_sets_include_subsets() {
+        let repository = MemoryRepository::new(identify())
+            .format(format("oai_dc"))
+            .records(
+                "oai_dc",
+                [
+                    record(1, &["a:b"]),
+                    record(2, &["a:b:c"]),
+                    record(3, &["ab"]),
+                ],
+            );
+        let ids = |set: &str| -> Vec<String> {
+            let args = ListRecordsArgs::new("oai_dc").set(set);
+            let page = repository.list_records(&args, None, 10).unwrap();
+            page.items
+                .into_iter()
+                .map(|r| r.header.identifier)
+                .collect()
+        };
+
+        assert_eq!(ids("a"), ["oai:example.org:1", "oai:example.org:2"]);
+        assert_eq!(ids("a:b:c"), ["oai:example.org:2"]);
+        assert!(ids("a:b:c:d").is_empty());
+    }
+}
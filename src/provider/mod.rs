@@ -0,0 +1,593 @@
+//! Serving OAI-PMH: a [`Repository`] trait and a framework-independent verb dispatcher.
+//!
+//! A repository describes itself and its records with the same types the client
+//! parses ([`Identify`], [`MetadataFormat`], [`Set`], [`Record`], [`Header`]).
+//! [`Provider`] takes the raw request parameters, validates them against the
+//! protocol (see [`request`]), calls the repository and returns the complete OAI-PMH XML response,
+//! including error responses. Serving it over HTTP is left to the caller.
+//!
+//! [`memory::MemoryRepository`] and [`directory::DirectoryRepository`] are ready-made
+//! repositories for trying things out and for tests. With the `xml` feature,
+//! `gateway::StaticGateway` serves an OAI Static Repository document.
+//!
+//! # Example
+//! ```
+//! use oai_pmh::ListRecordsArgs;
+//! use oai_pmh::client::response::{ErrorCode, Identify, MetadataFormat, Record, Set};
+//! use oai_pmh::provider::{Page, Provider, ProviderError, Repository};
+//!
+//! struct Empty;
+//!
+//! impl Repository for Empty {
+//!     fn identify(&self) -> Identify {
+//!         Identify {
+//!             repository_name: "Empty".to_string(),
+//!             base_url: "https://example.org/oai".to_string(),
+//!             protocol_version: "2.0".to_string(),
+//!             admin_email: vec!["admin@example.org".to_string()],
+//!             earliest_datestamp: "2024-01-01".to_string(),
+//!             deleted_record: "no".to_string(),
+//!             granularity: "YYYY-MM-DD".to_string(),
+//!             compression: Vec::new(),
+//!             description: Vec::new(),
+//!         }
+//!     }
+//!
+//!     fn metadata_formats(&self, _: Option<&str>) -> Result<Vec<MetadataFormat>, ProviderError> {
+//!         Ok(Vec::new())
+//!     }
+//!
+//!     fn sets(&self) -> Result<Vec<Set>, ProviderError> {
+//!         Ok(Vec::new())
+//!     }
+//!
+//!     fn get_record(&self, _: &str, _: &str) -> Result<Record, ProviderError> {
+//!         Err(ErrorCode::IdDoesNotExist.into())
+//!     }
+//!
+//!     fn list_records(
+//!         &self,
+//!         _: &ListRecordsArgs,
+//!         _: Option<&str>,
+//!         _: usize,
+//!     ) -> Result<Page<Record>, ProviderError> {
+//!         Ok(Page::last(Vec::new()))
+//!     }
+//! }
+//!
+//! let provider = Provider::new(Empty);
+//! let xml = provider.handle_query("verb=Identify").unwrap();
+//! assert!(xml.contains("<repositoryName>Empty</repositoryName>"));
+//!
+//! let xml = provider.handle_query("verb=ListSets").unwrap();
+//! assert!(xml.contains(r#"<error code="noSetHierarchy">"#));
+//! ```
+#[cfg(feature = "axum")]
+pub mod axum;
+pub mod convert;
+pub mod datestamp;
+pub mod directory;
+mod error;
+#[cfg(feature = "xml")]
+pub mod gateway;
+pub mod memory;
+pub mod request;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod token;
+pub mod writer;
+
+pub use error::ProviderError;
+
+use crate::Verb;
+use crate::client::query::{ListIdentifiersArgs, ListRecordsArgs};
+use crate::client::response::{
+    ErrorCode, Header, Identify, MetadataFormat, Record, ResumptionToken, Set,
+};
+use convert::{Converter, Derived};
+use datestamp::Granularity;
+use request::{ListRequest, Request, Validator};
+use token::{ResumptionTokenCodec, TokenCodec, TokenState};
+use writer::ResponseWriter;
+
+// Source pages read for one page of a converted list before answering with an empty one
+const CONVERTED_PAGES: usize = 3;
+
+/// Records and metadata served by a [`Provider`]
+///
+/// Errors are returned to the harvester as OAI-PMH errors; an [`ErrorCode`] converts
+/// into a [`ProviderError`] with the standard message. Failures of the repository
+/// itself are [`ProviderError::internal`] and become server errors. The provider validates
+/// arguments before calling the repository: prefixes are checked against
+/// `metadata_formats(None)`, `set` is rejected when there are no sets, and
+/// `from`/`until` are valid datestamps at the advertised granularity.
+pub trait Repository {
+    /// The `Identify` description, whose `base_url` is echoed in every response
+    fn identify(&self) -> Identify;
+
+    /// Formats for the whole repository, or for one item when `identifier` is given
+    ///
+    /// Return [`ErrorCode::IdDoesNotExist`] for unknown items.
+    fn metadata_formats(
+        &self,
+        identifier: Option<&str>,
+    ) -> Result<Vec<MetadataFormat>, ProviderError>;
+
+    /// All sets, or none if the repository has no set hierarchy
+    fn sets(&self) -> Result<Vec<Set>, ProviderError>;
+
+    /// A single record
+    ///
+    /// Return [`ErrorCode::IdDoesNotExist`] for unknown items and
+    /// [`ErrorCode::CannotDisseminateFormat`] when the item is not available in the format.
+    fn get_record(&self, identifier: &str, metadata_prefix: &str) -> Result<Record, ProviderError>;
+
+    /// Up to `limit` records matching `args`, starting at `cursor`
+    ///
+    /// `cursor` is `None` for the first page, then whatever the previous page returned
+    /// in [`Page::next`]. `from` and `until` are inclusive; an `until` at day granularity
+    /// covers the whole day (see [`DateRange`](datestamp::DateRange)).
+    fn list_records(
+        &self,
+        args: &ListRecordsArgs,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<Page<Record>, ProviderError>;
+
+    /// Up to `limit` headers matching `args`, starting at `cursor`
+    ///
+    /// The default takes the headers from [`list_records`](Repository::list_records).
+    /// Override it when headers are cheaper to load than full records.
+    fn list_identifiers(
+        &self,
+        args: &ListIdentifiersArgs,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<Page<Header>, ProviderError> {
+        let page = self.list_records(&args.clone().into(), cursor, limit)?;
+        Ok(Page {
+            items: page.items.into_iter().map(|record| record.header).collect(),
+            next: page.next,
+            complete_list_size: page.complete_list_size,
+        })
+    }
+}
+
+/// One page of a list response
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Repository cursor for the next page, `None` on the last page
+    pub next: Option<String>,
+    /// Number of items across all pages, if known
+    pub complete_list_size: Option<usize>,
+}
+
+impl<T> Page<T> {
+    /// A page followed by another starting at `next`
+    pub fn new(items: Vec<T>, next: impl Into<String>) -> Self {
+        Self {
+            items,
+            next: Some(next.into()),
+            complete_list_size: None,
+        }
+    }
+
+    /// The final page of a list
+    pub fn last(items: Vec<T>) -> Self {
+        Self {
+            items,
+            next: None,
+            complete_list_size: None,
+        }
+    }
+
+    pub fn complete_list_size(mut self, size: usize) -> Self {
+        self.complete_list_size = Some(size);
+        self
+    }
+}
+
+/// Answers OAI-PMH requests from a [`Repository`]
+pub struct Provider<R> {
+    repository: R,
+    page_size: usize,
+    codec: Box<dyn ResumptionTokenCodec>,
+    derived: Vec<Derived>,
+}
+
+impl<R: Repository> Provider<R> {
+    pub fn new(repository: R) -> Self {
+        Self {
+            repository,
+            page_size: 100,
+            codec: Box::new(TokenCodec::new()),
+            derived: Vec::new(),
+        }
+    }
+
+    /// Maximum number of records or headers per list response (default 100)
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Encode resumption tokens with `codec` (default: an unsigned [`TokenCodec`])
+    ///
+    /// The default tokens are forgeable; see [`TokenCodec`]'s security notes and give
+    /// it a secret for a public endpoint.
+    pub fn token_codec(mut self, codec: impl ResumptionTokenCodec + 'static) -> Self {
+        self.codec = Box::new(codec);
+        self
+    }
+
+    /// Serve `format` by converting records in the `source_prefix` format
+    ///
+    /// The format is advertised alongside the repository's own, and for each item
+    /// available in `source_prefix` that `converter` accepts. It takes over the prefix
+    /// if the repository serves it too. Lists in a converted format skip refused records
+    /// and have no `completeListSize`; a page read from a run of refused records may
+    /// be empty, with a resumption token to carry on.
+    pub fn converter(
+        mut self,
+        format: MetadataFormat,
+        source_prefix: impl Into<String>,
+        converter: impl Converter + 'static,
+    ) -> Self {
+        self.derived
+            .retain(|d| d.prefix() != format.metadata_prefix);
+        self.derived.push(Derived {
+            format,
+            source: source_prefix.into(),
+            converter: Box::new(converter),
+        });
+        self
+    }
+
+    pub fn repository(&self) -> &R {
+        &self.repository
+    }
+
+    /// Handle a request given as decoded name/value pairs, returning the XML response
+    ///
+    /// Pairs are taken as-is, so repeated arguments are detected. For a GET request
+    /// these come from the query string, for a POST from the form-encoded body.
+    ///
+    /// OAI-PMH errors are part of the response. An [internal](ProviderError::internal)
+    /// repository error is returned as `Err`, for the transport to answer as a server
+    /// error such as HTTP `500`.
+    pub fn handle<K: AsRef<str>, V: AsRef<str>>(
+        &self,
+        params: &[(K, V)],
+    ) -> Result<String, ProviderError> {
+        let params: Vec<(&str, &str)> = params
+            .iter()
+            .map(|(k, v)| (k.as_ref(), v.as_ref()))
+            .collect();
+        let identify = self.repository.identify();
+        let writer = ResponseWriter::new(&identify.base_url);
+
+        match self.dispatch(&params, &identify) {
+            Ok(reply) => Ok(reply.write(&writer.request(params))),
+            Err(error) => match error.code() {
+                None => Err(error),
+                // The request element must not echo arguments that failed validation
+                Some(code @ (ErrorCode::BadVerb | ErrorCode::BadArgument)) => {
+                    Ok(writer.error(code, error.message()))
+                }
+                Some(code) => Ok(writer.request(params).error(code, error.message())),
+            },
+        }
+    }
+
+    /// Handle a request given as a urlencoded query string (without the leading `?`)
+    pub fn handle_query(&self, query: &str) -> Result<String, ProviderError> {
+        let params: Vec<(String, String)> = url::form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect();
+        self.handle(&params)
+    }
+
+    fn dispatch(
+        &self,
+        params: &[(&str, &str)],
+        identify: &Identify,
+    ) -> Result<Reply, ProviderError> {
+        let granularity = Granularity::parse(&identify.granularity).unwrap_or(Granularity::Second);
+        let validator = Validator::new().granularity(granularity);
+        let request = validator.validate(params)?;
+
+        match request {
+            Request::Identify => Ok(Reply::Identify(identify.clone())),
+            Request::ListMetadataFormats(args) => {
+                let identifier = args.as_ref().map(|args| args.identifier());
+                let formats = self.metadata_formats(identifier)?;
+                if formats.is_empty() {
+                    return Err(ErrorCode::NoMetadataFormats.into());
+                }
+                Ok(Reply::MetadataFormats(formats))
+            }
+            Request::ListSets(Some(_)) => Err(ErrorCode::BadResumptionToken.into()),
+            Request::ListSets(None) => {
+                let sets = self.repository.sets()?;
+                if sets.is_empty() {
+                    return Err(ErrorCode::NoSetHierarchy.into());
+                }
+                Ok(Reply::Sets(sets))
+            }
+            Request::GetRecord(args) => {
+                self.check_prefix(args.metadata_prefix())?;
+                let record = match self.derived(args.metadata_prefix()) {
+                    Some(derived) => derived.convert(
+                        self.repository
+                            .get_record(args.identifier(), &derived.source)?,
+                    )?,
+                    None => self
+                        .repository
+                        .get_record(args.identifier(), args.metadata_prefix())?,
+                };
+                Ok(Reply::Record(record))
+            }
+            Request::ListIdentifiers(list) => {
+                self.list(Verb::ListIdentifiers, list.map(Into::into), &validator)
+            }
+            Request::ListRecords(list) => self.list(Verb::ListRecords, list, &validator),
+        }
+    }
+
+    fn list(
+        &self,
+        verb: Verb,
+        list: ListRequest<ListRecordsArgs>,
+        validator: &Validator,
+    ) -> Result<Reply, ProviderError> {
+        let (query, cursor, position) = match list {
+            ListRequest::Resume(args) => {
+                let state = self.codec.decode(args.resumption_token())?;
+                if state.verb != verb {
+                    return Err(ProviderError::new(
+                        ErrorCode::BadResumptionToken,
+                        format!("The resumption token was issued for {}", state.verb),
+                    ));
+                }
+                // An unsigned token can be edited, so its arguments get the same checks
+                // as a new request's
+                self.check_resumed(verb, &state.args, validator)
+                    .map_err(|error| match error.code() {
+                        None => error,
+                        Some(_) => ProviderError::new(
+                            ErrorCode::BadResumptionToken,
+                            format!(
+                                "The resumption token carries invalid arguments: {}",
+                                error.message()
+                            ),
+                        ),
+                    })?;
+                (state.args, Some(state.cursor), state.position)
+            }
+            ListRequest::Initial(query) => {
+                self.check_list(&query)?;
+                (query, None, 0)
+            }
+        };
+
+        let (body, next, size) = match self.derived(query.metadata_prefix()) {
+            Some(derived) => {
+                let page = self.converted(derived, &query, cursor)?;
+                let body = if verb == Verb::ListRecords {
+                    Body::Records(page.items)
+                } else {
+                    Body::Headers(page.items.into_iter().map(|r| r.header).collect())
+                };
+                (body, page.next, None)
+            }
+            None if verb == Verb::ListRecords => {
+                let page =
+                    self.repository
+                        .list_records(&query, cursor.as_deref(), self.page_size)?;
+                (
+                    Body::Records(page.items),
+                    page.next,
+                    page.complete_list_size,
+                )
+            }
+            None => {
+                let page = self.repository.list_identifiers(
+                    &query.clone().into(),
+                    cursor.as_deref(),
+                    self.page_size,
+                )?;
+                (
+                    Body::Headers(page.items),
+                    page.next,
+                    page.complete_list_size,
+                )
+            }
+        };
+        let count = body.len();
+
+        if count == 0 && position == 0 && next.is_none() {
+            return Err(ErrorCode::NoRecordsMatch.into());
+        }
+
+        // Only incomplete lists, and the last page of one, carry a resumptionToken
+        let resumption = match (next, position) {
+            (None, 0) => None,
+            (next, _) => {
+                let next =
+                    next.map(|cursor| TokenState::new(verb, query, cursor, position + count));
+                Some(self.codec.resumption_token(next, position, size))
+            }
+        };
+
+        Ok(match body {
+            Body::Records(records) => Reply::Records(records, resumption),
+            Body::Headers(headers) => Reply::Headers(headers, resumption),
+        })
+    }
+
+    /// The next page of records converted by `derived`
+    ///
+    /// Pages of the source format are read until one has a record the converter
+    /// accepts, up to `CONVERTED_PAGES` of them, so a page may be empty before the
+    /// end of the list.
+    fn converted(
+        &self,
+        derived: &Derived,
+        query: &ListRecordsArgs,
+        mut cursor: Option<String>,
+    ) -> Result<Page<Record>, ProviderError> {
+        let source = query.with_metadata_prefix(&derived.source);
+        let mut items = Vec::new();
+        for _ in 0..CONVERTED_PAGES {
+            let page = self
+                .repository
+                .list_records(&source, cursor.as_deref(), self.page_size)?;
+            for record in page.items {
+                match derived.convert(record) {
+                    Ok(record) => items.push(record),
+                    Err(error) if error.is_internal() => return Err(error),
+                    Err(_) => {}
+                }
+            }
+            cursor = page.next;
+            if !items.is_empty() || cursor.is_none() {
+                break;
+            }
+        }
+        Ok(Page {
+            items,
+            next: cursor,
+            complete_list_size: None,
+        })
+    }
+
+    fn derived(&self, prefix: &str) -> Option<&Derived> {
+        self.derived.iter().find(|d| d.prefix() == prefix)
+    }
+
+    // The repository's formats, with derived formats in place of any it serves itself
+    fn metadata_formats(
+        &self,
+        identifier: Option<&str>,
+    ) -> Result<Vec<MetadataFormat>, ProviderError> {
+        let native = self.repository.metadata_formats(identifier)?;
+        let mut formats: Vec<MetadataFormat> = native
+            .iter()
+            .filter(|f| self.derived(&f.metadata_prefix).is_none())
+            .cloned()
+            .collect();
+        for derived in &self.derived {
+            if !native.iter().any(|f| f.metadata_prefix == derived.source) {
+                continue;
+            }
+            let accepted = match identifier {
+                Some(identifier) => match self
+                    .repository
+                    .get_record(identifier, &derived.source)
+                    .and_then(|record| derived.convert(record))
+                {
+                    Err(error) if error.is_internal() => return Err(error),
+                    result => result.is_ok(),
+                },
+                None => true,
+            };
+            if accepted {
+                formats.push(derived.format.clone());
+            }
+        }
+        Ok(formats)
+    }
+
+    // The format and set of a list request are served by the repository
+    fn check_list(&self, query: &ListRecordsArgs) -> Result<(), ProviderError> {
+        self.check_prefix(query.metadata_prefix())?;
+        if query.set_spec().is_some() && self.repository.sets()?.is_empty() {
+            return Err(ErrorCode::NoSetHierarchy.into());
+        }
+        Ok(())
+    }
+
+    // Validate arguments decoded from a resumption token as if they were sent afresh
+    fn check_resumed(
+        &self,
+        verb: Verb,
+        args: &ListRecordsArgs,
+        validator: &Validator,
+    ) -> Result<(), ProviderError> {
+        let mut params = vec![
+            ("verb", verb.to_string()),
+            ("metadataPrefix", args.metadata_prefix().to_string()),
+        ];
+        let optional = [
+            ("from", args.from_datestamp()),
+            ("until", args.until_datestamp()),
+            ("set", args.set_spec()),
+        ];
+        for (name, value) in optional {
+            if let Some(value) = value {
+                params.push((name, value.to_string()));
+            }
+        }
+        validator.validate(&params)?;
+        self.check_list(args)
+    }
+
+    fn check_prefix(&self, prefix: &str) -> Result<(), ProviderError> {
+        let formats = self.metadata_formats(None)?;
+        if formats.iter().any(|f| f.metadata_prefix == prefix) {
+            Ok(())
+        } else {
+            Err(ProviderError::new(
+                ErrorCode::CannotDisseminateFormat,
+                format!("The metadata format {prefix} is not supported by the repository"),
+            ))
+        }
+    }
+}
+
+impl<T> ListRequest<T> {
+    fn map<U>(self, f: impl FnOnce(T) -> U) -> ListRequest<U> {
+        match self {
+            ListRequest::Initial(args) => ListRequest::Initial(f(args)),
+            ListRequest::Resume(token) => ListRequest::Resume(token),
+        }
+    }
+}
+
+enum Body {
+    Records(Vec<Record>),
+    Headers(Vec<Header>),
+}
+
+impl Body {
+    fn len(&self) -> usize {
+        match self {
+            Body::Records(records) => records.len(),
+            Body::Headers(headers) => headers.len(),
+        }
+    }
+}
+
+// A successful response, written once the request arguments are known to be valid
+enum Reply {
+    Identify(Identify),
+    MetadataFormats(Vec<MetadataFormat>),
+    Sets(Vec<Set>),
+    Record(Record),
+    Headers(Vec<Header>, Option<ResumptionToken>),
+    Records(Vec<Record>, Option<ResumptionToken>),
+}
+
+impl Reply {
+    fn write(&self, writer: &ResponseWriter) -> String {
+        match self {
+            Reply::Identify(identify) => writer.identify(identify),
+            Reply::MetadataFormats(formats) => writer.list_metadata_formats(formats),
+            Reply::Sets(sets) => writer.list_sets(sets, None),
+            Reply::Record(record) => writer.get_record(record),
+            Reply::Headers(headers, token) => writer.list_identifiers(headers, token.as_ref()),
+            Reply::Records(records, token) => writer.list_records(records, token.as_ref()),
+        }
+    }
+}
@@ -0,0 +1,576 @@
+//! Validating raw request arguments into typed requests.
+//!
+//! The rules follow the OAI-PMH 2.0 specification: each verb has required, optional
+//! and exclusive arguments, `resumptionToken` may only appear alongside `verb`, and
+//! arguments may not be repeated. A missing, repeated or unknown verb is `badVerb`;
+//! every other violation is `badArgument`.
+//!
+//! # Example
+//! ```
+//! use oai_pmh::client::response::ErrorCode;
+//! use oai_pmh::provider::request::{ListRequest, Request};
+//!
+//! let request = Request::parse(&[("verb", "ListRecords"), ("metadataPrefix", "oai_dc")]).unwrap();
+//! let Request::ListRecords(ListRequest::Initial(args)) = request else {
+//!     panic!("expected a new ListRecords request");
+//! };
+//! assert_eq!(args.metadata_prefix(), "oai_dc");
+//!
+//! let error = Request::parse(&[("verb", "ListSets"), ("set", "maps")]).unwrap_err();
+//! assert_eq!(error.code(), Some(ErrorCode::BadArgument));
+//! ```
+use crate::Verb;
+use crate::client::query::{
+    GetRecordArgs, ListIdentifiersArgs, ListMetadataFormatsArgs, ListRecordsArgs, ResumableArgs,
+};
+use crate::client::response::ErrorCode;
+use crate::provider::ProviderError;
+use crate::provider::datestamp::{Datestamp, Granularity};
+
+/// A validated OAI-PMH request
+#[derive(Clone, Debug, PartialEq)]
+pub enum Request {
+    GetRecord(GetRecordArgs),
+    Identify,
+    ListIdentifiers(ListRequest<ListIdentifiersArgs>),
+    ListMetadataFormats(Option<ListMetadataFormatsArgs>),
+    ListRecords(ListRequest<ListRecordsArgs>),
+    ListSets(Option<ResumableArgs>),
+}
+
+/// The first request of a list, or the continuation of one
+#[derive(Clone, Debug, PartialEq)]
+pub enum ListRequest<T> {
+    Initial(T),
+    Resume(ResumableArgs),
+}
+
+impl Request {
+    /// Validate with the default [`Validator`]
+    pub fn parse<K: AsRef<str>, V: AsRef<str>>(params: &[(K, V)]) -> Result<Self, ProviderError> {
+        Validator::new().validate(params)
+    }
+
+    pub fn verb(&self) -> Verb {
+        match self {
+            Request::GetRecord(_) => Verb::GetRecord,
+            Request::Identify => Verb::Identify,
+            Request::ListIdentifiers(_) => Verb::ListIdentifiers,
+            Request::ListMetadataFormats(_) => Verb::ListMetadataFormats,
+            Request::ListRecords(_) => Verb::ListRecords,
+            Request::ListSets(_) => Verb::ListSets,
+        }
+    }
+}
+
+/// Checks request arguments against the protocol
+///
+/// Besides the per-verb argument rules, `from` and `until` must be valid datestamps,
+/// no finer than the repository's granularity, of the same granularity as each other,
+/// and `from` may not be later than `until`.
+#[derive(Clone, Debug)]
+pub struct Validator {
+    granularity: Granularity,
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self {
+            granularity: Granularity::Second,
+        }
+    }
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The repository's datestamp granularity (default [`Granularity::Second`])
+    pub fn granularity(mut self, granularity: Granularity) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
+    /// Turn decoded name/value pairs into a [`Request`]
+    pub fn validate<K: AsRef<str>, V: AsRef<str>>(
+        &self,
+        params: &[(K, V)],
+    ) -> Result<Request, ProviderError> {
+        let params: Vec<(&str, &str)> = params
+            .iter()
+            .map(|(k, v)| (k.as_ref(), v.as_ref()))
+            .collect();
+
+        let verb = parse_verb(&params)?;
+        let args = Arguments::parse(verb, &params)?;
+
+        Ok(match verb {
+            Verb::GetRecord => Request::GetRecord(GetRecordArgs::new(
+                args.required("identifier"),
+                args.required("metadataPrefix"),
+            )),
+            Verb::Identify => Request::Identify,
+            Verb::ListIdentifiers => Request::ListIdentifiers(match args.resumption_token() {
+                Some(token) => ListRequest::Resume(token),
+                None => ListRequest::Initial(self.selective(&args)?.into()),
+            }),
+            Verb::ListMetadataFormats => Request::ListMetadataFormats(
+                args.get("identifier").map(ListMetadataFormatsArgs::new),
+            ),
+            Verb::ListRecords => Request::ListRecords(match args.resumption_token() {
+                Some(token) => ListRequest::Resume(token),
+                None => ListRequest::Initial(self.selective(&args)?),
+            }),
+            Verb::ListSets => Request::ListSets(args.resumption_token()),
+        })
+    }
+
+    // Arguments for selective harvesting, shared by ListIdentifiers and ListRecords
+    fn selective(&self, args: &Arguments) -> Result<ListRecordsArgs, ProviderError> {
+        let mut bounds = Vec::new();
+        for name in ["from", "until"] {
+            if let Some(value) = args.get(name) {
+                let (datestamp, granularity) = Datestamp::parse(value).ok_or_else(|| {
+                    ProviderError::bad_argument(format!("Invalid {name} datestamp: {value}"))
+                })?;
+                if granularity > self.granularity {
+                    return Err(ProviderError::bad_argument(format!(
+                        "The {name} datestamp is finer than the repository granularity ({})",
+                        self.granularity
+                    )));
+                }
+                bounds.push((datestamp, granularity));
+            }
+        }
+        if let [(from, from_granularity), (until, until_granularity)] = bounds[..] {
+            if from_granularity != until_granularity {
+                return Err(ProviderError::bad_argument(
+                    "The from and until datestamps have different granularities",
+                ));
+            }
+            if from > until {
+                return Err(ProviderError::bad_argument(
+                    "The from datestamp is later than the until datestamp",
+                ));
+            }
+        }
+
+        let mut query = ListRecordsArgs::new(args.required("metadataPrefix"));
+        if let Some(from) = args.get("from") {
+            query = query.from(from);
+        }
+        if let Some(until) = args.get("until") {
+            query = query.until(until);
+        }
+        if let Some(set) = args.get("set") {
+            query = query.set(set);
+        }
+        Ok(query)
+    }
+}
+
+pub(crate) fn verb_from_str(verb: &str) -> Option<Verb> {
+    Some(match verb {
+        "GetRecord" => Verb::GetRecord,
+        "Identify" => Verb::Identify,
+        "ListIdentifiers" => Verb::ListIdentifiers,
+        "ListMetadataFormats" => Verb::ListMetadataFormats,
+        "ListRecords" => Verb::ListRecords,
+        "ListSets" => Verb::ListSets,
+        _ => return None,
+    })
+}
+
+fn parse_verb(params: &[(&str, &str)]) -> Result<Verb, ProviderError> {
+    let verbs: Vec<&str> = params
+        .iter()
+        .filter(|(name, _)| *name == "verb")
+        .map(|(_, value)| *value)
+        .collect();
+
+    match verbs[..] {
+        [] => Err(ProviderError::new(
+            ErrorCode::BadVerb,
+            "Missing verb argument",
+        )),
+        [verb] => verb_from_str(verb)
+            .ok_or_else(|| ProviderError::new(ErrorCode::BadVerb, format!("Illegal verb: {verb}"))),
+        _ => Err(ProviderError::new(
+            ErrorCode::BadVerb,
+            "Repeated verb argument",
+        )),
+    }
+}
+
+// Request arguments other than `verb`, checked against the verb's allowed arguments
+struct Arguments<'a> {
+    args: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Arguments<'a> {
+    fn parse(verb: Verb, params: &[(&'a str, &'a str)]) -> Result<Self, ProviderError> {
+        let (required, optional): (&[&str], &[&str]) = match verb {
+            Verb::GetRecord => (&["identifier", "metadataPrefix"], &[]),
+            Verb::Identify => (&[], &[]),
+            Verb::ListIdentifiers | Verb::ListRecords => {
+                (&["metadataPrefix"], &["from", "until", "set"])
+            }
+            Verb::ListMetadataFormats => (&[], &["identifier"]),
+            Verb::ListSets => (&[], &[]),
+        };
+        let exclusive = matches!(
+            verb,
+            Verb::ListIdentifiers | Verb::ListRecords | Verb::ListSets
+        );
+
+        let mut args: Vec<(&str, &str)> = Vec::new();
+        for &(name, value) in params.iter().filter(|(name, _)| *name != "verb") {
+            let allowed = required.contains(&name)
+                || optional.contains(&name)
+                || (exclusive && name == "resumptionToken");
+            if !allowed {
+                return Err(ProviderError::bad_argument(format!(
+                    "Illegal argument for {verb}: {name}"
+                )));
+            }
+            if args.iter().any(|(n, _)| *n == name) {
+                return Err(ProviderError::bad_argument(format!(
+                    "Repeated argument: {name}"
+                )));
+            }
+            if value.is_empty() {
+                return Err(ProviderError::bad_argument(format!(
+                    "Empty argument: {name}"
+                )));
+            }
+            args.push((name, value));
+        }
+
+        // resumptionToken is exclusive and replaces the required arguments
+        if args.iter().any(|(name, _)| *name == "resumptionToken") {
+            if args.len() > 1 {
+                return Err(ProviderError::bad_argument(
+                    "resumptionToken cannot be combined with other arguments",
+                ));
+            }
+        } else if let Some(missing) = required
+            .iter()
+            .find(|r| !args.iter().any(|(name, _)| name == *r))
+        {
+            return Err(ProviderError::bad_argument(format!(
+                "Missing required argument for {verb}: {missing}"
+            )));
+        }
+
+        Ok(Self { args })
+    }
+
+    fn get(&self, name: &str) -> Option<&'a str> {
+        self.args
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, value)| *value)
+    }
+
+    // Only called for arguments `parse` has checked are present
+    fn required(&self, name: &str) -> &'a str {
+        self.get(name).unwrap_or_default()
+    }
+
+    fn resumption_token(&self) -> Option<ResumableArgs> {
+        self.get("resumptionToken").map(ResumableArgs::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(params: &[(&str, &str)]) -> ErrorCode {
+        Request::parse(params).unwrap_err().code().unwrap()
+    }
+
+    const ALL_ARGUMENTS: [&str; 6] = [
+        "identifier",
+        "metadataPrefix",
+        "from",
+        "until",
+        "set",
+        "resumptionToken",
+    ];
+
+    #[test]
+    fn missing_or_illegal_verb() {
+        assert_eq!(code(&[]), ErrorCode::BadVerb);
+        assert_eq!(code(&[("metadataPrefix", "oai_dc")]), ErrorCode::BadVerb);
+        assert_eq!(code(&[("verb", "")]), ErrorCode::BadVerb);
+        assert_eq!(code(&[("verb", "Nope")]), ErrorCode::BadVerb);
+        assert_eq!(code(&[("verb", "identify")]), ErrorCode::BadVerb);
+        assert_eq!(code(&[("verb", " Identify")]), ErrorCode::BadVerb);
+        assert_eq!(code(&[("Verb", "Identify")]), ErrorCode::BadVerb);
+    }
+
+    #[test]
+    fn repeated_verb() {
+        assert_eq!(
+            code(&[("verb", "Identify"), ("verb", "Identify")]),
+            ErrorCode::BadVerb
+        );
+        assert_eq!(
+            code(&[("verb", "Identify"), ("verb", "ListSets")]),
+            ErrorCode::BadVerb
+        );
+    }
+
+    #[test]
+    fn verb_is_checked_before_arguments() {
+        assert_eq!(
+            code(&[("verb", "Nope"), ("bogus", "x"), ("bogus", "y")]),
+            ErrorCode::BadVerb
+        );
+    }
+
+    #[test]
+    fn parses_every_verb() {
+        let cases: [(&[(&str, &str)], Request); 10] = [
+            (
+                &[
+                    ("verb", "GetRecord"),
+                    ("identifier", "id"),
+                    ("metadataPrefix", "oai_dc"),
+                ],
+                Request::GetRecord(GetRecordArgs::new("id", "oai_dc")),
+            ),
+            (&[("verb", "Identify")], Request::Identify),
+            (
+                &[("verb", "ListIdentifiers"), ("metadataPrefix", "oai_dc")],
+                Request::ListIdentifiers(ListRequest::Initial(ListIdentifiersArgs::new("oai_dc"))),
+            ),
+            (
+                &[("verb", "ListIdentifiers"), ("resumptionToken", "t")],
+                Request::ListIdentifiers(ListRequest::Resume(ResumableArgs::new("t"))),
+            ),
+            (
+                &[("verb", "ListMetadataFormats")],
+                Request::ListMetadataFormats(None),
+            ),
+            (
+                &[("verb", "ListMetadataFormats"), ("identifier", "id")],
+                Request::ListMetadataFormats(Some(ListMetadataFormatsArgs::new("id"))),
+            ),
+            (
+                &[
+                    ("verb", "ListRecords"),
+                    ("set", "maps"),
+                    ("until", "2024-02-01"),
+                    ("from", "2024-01-01"),
+                    ("metadataPrefix", "oai_dc"),
+                ],
+                Request::ListRecords(ListRequest::Initial(
+                    ListRecordsArgs::new("oai_dc")
+                        .from("2024-01-01")
+                        .until("2024-02-01")
+                        .set("maps"),
+                )),
+            ),
+            (
+                &[("verb", "ListRecords"), ("resumptionToken", "t")],
+                Request::ListRecords(ListRequest::Resume(ResumableArgs::new("t"))),
+            ),
+            (&[("verb", "ListSets")], Request::ListSets(None)),
+            (
+                &[("verb", "ListSets"), ("resumptionToken", "t")],
+                Request::ListSets(Some(ResumableArgs::new("t"))),
+            ),
+        ];
+
+        for (params, expected) in cases {
+            let request = Request::parse(params).unwrap();
+            assert_eq!(request.verb(), expected.verb());
+            assert_eq!(request, expected, "{params:?}");
+        }
+    }
+
+    #[test]
+    fn illegal_arguments_per_verb() {
+        let allowed: [(&str, &[&str]); 6] = [
+            ("GetRecord", &["identifier", "metadataPrefix"]),
+            ("Identify", &[]),
+            (
+                "ListIdentifiers",
+                &["metadataPrefix", "from", "until", "set", "resumptionToken"],
+            ),
+            ("ListMetadataFormats", &["identifier"]),
+            (
+                "ListRecords",
+                &["metadataPrefix", "from", "until", "set", "resumptionToken"],
+            ),
+            ("ListSets", &["resumptionToken"]),
+        ];
+
+        for (verb, allowed) in allowed {
+            for argument in ALL_ARGUMENTS.iter().chain(&["bogus", "Identifier"]) {
+                if allowed.contains(argument) {
+                    continue;
+                }
+                assert_eq!(
+                    code(&[("verb", verb), (argument, "x")]),
+                    ErrorCode::BadArgument,
+                    "{verb} {argument}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn missing_required_arguments() {
+        for params in [
+            &[("verb", "GetRecord")][..],
+            &[("verb", "GetRecord"), ("identifier", "id")],
+            &[("verb", "GetRecord"), ("metadataPrefix", "oai_dc")],
+            &[("verb", "ListIdentifiers")],
+            &[("verb", "ListIdentifiers"), ("set", "maps")],
+            &[("verb", "ListRecords")],
+            &[("verb", "ListRecords"), ("from", "2024-01-01")],
+        ] {
+            assert_eq!(code(params), ErrorCode::BadArgument, "{params:?}");
+        }
+    }
+
+    #[test]
+    fn repeated_arguments() {
+        for params in [
+            &[
+                ("verb", "GetRecord"),
+                ("identifier", "a"),
+                ("identifier", "a"),
+                ("metadataPrefix", "oai_dc"),
+            ][..],
+            &[
+                ("verb", "ListRecords"),
+                ("metadataPrefix", "oai_dc"),
+                ("metadataPrefix", "marc21"),
+            ],
+            &[
+                ("verb", "ListRecords"),
+                ("metadataPrefix", "oai_dc"),
+                ("set", "a"),
+                ("set", "b"),
+            ],
+            &[
+                ("verb", "ListSets"),
+                ("resumptionToken", "t"),
+                ("resumptionToken", "t"),
+            ],
+            &[
+                ("verb", "ListMetadataFormats"),
+                ("identifier", "a"),
+                ("identifier", "b"),
+            ],
+        ] {
+            assert_eq!(code(params), ErrorCode::BadArgument, "{params:?}");
+        }
+    }
+
+    #[test]
+    fn empty_arguments() {
+        assert_eq!(
+            code(&[("verb", "ListRecords"), ("metadataPrefix", "")]),
+            ErrorCode::BadArgument
+        );
+        assert_eq!(
+            code(&[("verb", "ListSets"), ("resumptionToken", "")]),
+            ErrorCode::BadArgument
+        );
+    }
+
+    #[test]
+    fn resumption_token_is_exclusive() {
+        for other in ["metadataPrefix", "from", "until", "set"] {
+            for verb in ["ListIdentifiers", "ListRecords"] {
+                assert_eq!(
+                    code(&[
+                        ("verb", verb),
+                        ("resumptionToken", "t"),
+                        (other, "2024-01-01")
+                    ]),
+                    ErrorCode::BadArgument,
+                    "{verb} {other}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn datestamp_arguments() {
+        let list = |from: &str, until: &str| {
+            let mut params = vec![("verb", "ListRecords"), ("metadataPrefix", "oai_dc")];
+            if !from.is_empty() {
+                params.push(("from", from));
+            }
+            if !until.is_empty() {
+                params.push(("until", until));
+            }
+            Request::parse(&params).map_err(|e| e.code())
+        };
+
+        assert!(list("2024-01-01", "").is_ok());
+        assert!(list("", "2024-01-01T00:00:00Z").is_ok());
+        assert!(list("2024-01-01", "2024-01-01").is_ok());
+        assert!(list("2024-01-01T00:00:00Z", "2024-01-01T23:59:59Z").is_ok());
+
+        for (from, until) in [
+            ("yesterday", ""),
+            ("", "2024-02-30"),
+            ("2024-01-01T00:00:00", ""),
+            ("2024-01-01", "2024-01-02T00:00:00Z"),
+            ("2024-01-02", "2024-01-01"),
+        ] {
+            assert_eq!(
+                list(from, until),
+                Err(Some(ErrorCode::BadArgument)),
+                "{from} {until}"
+            );
+        }
+    }
+
+    #[test]
+    fn granularity_is_enforced() {
+        let params = [
+            ("verb", "ListIdentifiers"),
+            ("metadataPrefix", "oai_dc"),
+            ("from", "2024-01-01T00:00:00Z"),
+        ];
+        assert!(Validator::new().validate(&params).is_ok());
+
+        let day = Validator::new().granularity(Granularity::Day);
+        assert_eq!(
+            day.validate(&params).unwrap_err().code(),
+            Some(ErrorCode::BadArgument)
+        );
+        assert!(
+            day.validate(&[
+                ("verb", "ListIdentifiers"),
+                ("metadataPrefix", "oai_dc"),
+                ("from", "2024-01-01"),
+            ])
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn argument_order_does_not_matter() {
+        let a = Request::parse(&[
+            ("verb", "GetRecord"),
+            ("identifier", "id"),
+            ("metadataPrefix", "p"),
+        ]);
+        let b = Request::parse(&[
+            ("metadataPrefix", "p"),
+            ("identifier", "id"),
+            ("verb", "GetRecord"),
+        ]);
+        assert_eq!(a.unwrap(), b.unwrap());
+    }
+}
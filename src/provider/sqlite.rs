@@ -0,0 +1,885 @@
+//! A [`Repository`] backed by SQLite, enabled by the `sqlite` feature.
+//!
+//! [`SqliteRepository`] serves records from a database with the schema in [`SCHEMA`],
+//! which it creates if missing:
+//!
+//! - `formats`: the metadata formats, one row per prefix.
+//! - `sets`: the set hierarchy, with an optional `setDescription` XML fragment.
+//! - `records`: one row per item and format, holding the metadata XML. Datestamps
+//!   are Unix seconds in UTC and are formatted at the granularity in `Identify`.
+//! - `record_sets`: set membership per item, shared by all its formats. Rows are kept
+//!   while an item has deletions, so deleted headers keep their `setSpec`s. Selecting
+//!   a set also selects items in its subsets.
+//! - `deletions`: deleted records, per item and format.
+//!
+//! Lists are ordered by datestamp and identifier and resume after the last pair
+//! served, so paging stays correct while records change and each page is an index
+//! range scan. `completeListSize` is counted on the first page and carried in the
+//! cursor, which is opaque to the provider and works with any
+//! [`ResumptionTokenCodec`](crate::provider::token::ResumptionTokenCodec).
+//!
+//! Deletions follow the `deletedRecord` policy advertised in `Identify`: with `no`
+//! they are not recorded or served, with `persistent` they are kept, and with
+//! `transient` they are kept until [`SqliteRepository::purge_deletions`].
+//!
+//! OAI-PMH has no error code for server failures, so database errors while answering
+//! a request are returned as [internal errors](ProviderError::internal), which
+//! [`Provider::handle`](crate::provider::Provider::handle) passes to the transport and
+//! the [axum endpoint](crate::provider::axum) answers with a 500.
+//!
+//! # Example
+//! ```
+//! use oai_pmh::client::response::{Header, Identify, MetadataFormat, Record};
+//! use oai_pmh::provider::Provider;
+//! use oai_pmh::provider::sqlite::SqliteRepository;
+//!
+//! # fn main() -> rusqlite::Result<()> {
+//! # let identify = Identify {
+//! #     repository_name: "SQLite".to_string(),
+//! #     base_url: "https://example.org/oai".to_string(),
+//! #     protocol_version: "2.0".to_string(),
+//! #     admin_email: vec!["admin@example.org".to_string()],
+//! #     earliest_datestamp: "2024-01-01".to_string(),
+//! #     deleted_record: "persistent".to_string(),
+//! #     granularity: "YYYY-MM-DD".to_string(),
+//! #     compression: Vec::new(),
+//! #     description: Vec::new(),
+//! # };
+//! let repository = SqliteRepository::open_in_memory(identify)?;
+//! repository.add_format(&MetadataFormat {
+//!     metadata_prefix: "oai_dc".to_string(),
+//!     metadata_namespace: "http://www.openarchives.org/OAI/2.0/oai_dc/".to_string(),
+//!     schema: "http://www.openarchives.org/OAI/2.0/oai_dc.xsd".to_string(),
+//! })?;
+//! repository.put_record("oai_dc", &Record {
+//!     header: Header {
+//!         identifier: "oai:example.org:1".to_string(),
+//!         datestamp: "2024-01-01".to_string(),
+//!         status: None,
+//!         set_spec: Vec::new(),
+//!     },
+//!     metadata: "<oai_dc:dc xmlns:oai_dc=\"http://www.openarchives.org/OAI/2.0/oai_dc/\"/>".to_string(),
+//!     about: String::new(),
+//! })?;
+//!
+//! let provider = Provider::new(repository);
+//! let xml = provider.handle_query("verb=ListIdentifiers&metadataPrefix=oai_dc").unwrap();
+//! assert!(xml.contains("<identifier>oai:example.org:1</identifier>"));
+//! # Ok(())
+//! # }
+//! ```
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use rusqlite::types::Value;
+use rusqlite::{Connection, OptionalExtension, Row, params, params_from_iter};
+
+use crate::client::query::{ListIdentifiersArgs, ListRecordsArgs};
+use crate::client::response::{ErrorCode, Header, Identify, MetadataFormat, Record, Set};
+use crate::provider::datestamp::{DateRange, Datestamp, Granularity};
+use crate::provider::{Page, ProviderError, Repository};
+
+/// The database schema, applied by [`SqliteRepository::new`]
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS formats (
+    prefix    TEXT PRIMARY KEY,
+    namespace TEXT NOT NULL,
+    schema    TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS sets (
+    spec        TEXT PRIMARY KEY,
+    name        TEXT NOT NULL,
+    description TEXT
+);
+
+CREATE TABLE IF NOT EXISTS records (
+    identifier TEXT NOT NULL,
+    prefix     TEXT NOT NULL REFERENCES formats (prefix),
+    datestamp  INTEGER NOT NULL,
+    metadata   TEXT NOT NULL,
+    about      TEXT,
+    PRIMARY KEY (identifier, prefix)
+);
+CREATE INDEX IF NOT EXISTS records_by_datestamp ON records (prefix, datestamp, identifier);
+
+CREATE TABLE IF NOT EXISTS record_sets (
+    identifier TEXT NOT NULL,
+    set_spec   TEXT NOT NULL,
+    PRIMARY KEY (identifier, set_spec)
+);
+CREATE INDEX IF NOT EXISTS record_sets_by_set ON record_sets (set_spec, identifier);
+
+CREATE TABLE IF NOT EXISTS deletions (
+    identifier TEXT NOT NULL,
+    prefix     TEXT NOT NULL,
+    datestamp  INTEGER NOT NULL,
+    PRIMARY KEY (identifier, prefix)
+);
+CREATE INDEX IF NOT EXISTS deletions_by_datestamp ON deletions (prefix, datestamp, identifier);
+";
+
+// Records and deletions matching `filter`, with set specs joined by spaces
+//
+// `?1` includes deletions. With a `limit`, each side is ordered and limited before
+// the two are merged, so a page reads no more than `limit` rows from either table.
+fn items(metadata: bool, filter: &str, limit: Option<usize>) -> String {
+    let columns = if metadata { ", metadata, about" } else { "" };
+    let limit = limit
+        .map(|limit| format!(" ORDER BY datestamp, identifier LIMIT {limit}"))
+        .unwrap_or_default();
+    format!(
+        "SELECT identifier, datestamp, deleted{columns}, (
+            SELECT group_concat(set_spec, ' ') FROM record_sets s
+            WHERE s.identifier = items.identifier
+        ) AS sets
+        FROM (
+            SELECT * FROM (
+                SELECT identifier, datestamp, 0 AS deleted, metadata, about FROM records
+                WHERE {filter}{limit}
+            )
+            UNION ALL
+            SELECT * FROM (
+                SELECT identifier, datestamp, 1, NULL, NULL FROM deletions
+                WHERE ?1 AND {filter}{limit}
+            )
+        ) AS items{limit}"
+    )
+}
+
+/// A [`Repository`] serving records from a SQLite database
+pub struct SqliteRepository {
+    connection: Mutex<Connection>,
+    identify: Identify,
+}
+
+impl SqliteRepository {
+    /// Serve from `connection`, creating the schema if missing
+    pub fn new(connection: Connection, identify: Identify) -> rusqlite::Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+            identify,
+        })
+    }
+
+    pub fn open(path: impl AsRef<Path>, identify: Identify) -> rusqlite::Result<Self> {
+        Self::new(Connection::open(path)?, identify)
+    }
+
+    pub fn open_in_memory(identify: Identify) -> rusqlite::Result<Self> {
+        Self::new(Connection::open_in_memory()?, identify)
+    }
+
+    /// Add or replace a metadata format
+    pub fn add_format(&self, format: &MetadataFormat) -> rusqlite::Result<()> {
+        self.connection().execute(
+            "INSERT OR REPLACE INTO formats (prefix, namespace, schema) VALUES (?1, ?2, ?3)",
+            params![
+                format.metadata_prefix,
+                format.metadata_namespace,
+                format.schema
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Add or replace a set; only the first `setDescription` is stored
+    pub fn add_set(&self, set: &Set) -> rusqlite::Result<()> {
+        self.connection().execute(
+            "INSERT OR REPLACE INTO sets (spec, name, description) VALUES (?1, ?2, ?3)",
+            params![set.set_spec, set.set_name, set.set_description.first()],
+        )?;
+        Ok(())
+    }
+
+    /// Add or replace a record in the format identified by `metadata_prefix`
+    ///
+    /// The header's `setSpec`s replace the item's set membership. A record whose
+    /// header status is `deleted` is stored as a deletion of that format.
+    pub fn put_record(&self, metadata_prefix: &str, record: &Record) -> rusqlite::Result<()> {
+        self.put_records(metadata_prefix, std::slice::from_ref(record))
+    }
+
+    /// Like [`put_record`](Self::put_record) for many records, in one transaction
+    pub fn put_records<'a>(
+        &self,
+        metadata_prefix: &str,
+        records: impl IntoIterator<Item = &'a Record>,
+    ) -> rusqlite::Result<()> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+        for record in records {
+            let header = &record.header;
+            let datestamp = parse_datestamp(&header.datestamp)?;
+            if header.status.as_deref() == Some("deleted") {
+                self.delete(
+                    &transaction,
+                    &header.identifier,
+                    Some(metadata_prefix),
+                    datestamp,
+                )?;
+                if !self.tracks_deletions() {
+                    continue;
+                }
+            } else {
+                transaction.execute(
+                    "INSERT OR REPLACE INTO records (identifier, prefix, datestamp, metadata, about)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        header.identifier,
+                        metadata_prefix,
+                        datestamp.unix(),
+                        record.metadata,
+                        Some(&record.about).filter(|about| !about.is_empty()),
+                    ],
+                )?;
+                transaction.execute(
+                    "DELETE FROM deletions WHERE identifier = ?1 AND prefix = ?2",
+                    params![header.identifier, metadata_prefix],
+                )?;
+            }
+
+            transaction.execute(
+                "DELETE FROM record_sets WHERE identifier = ?1",
+                params![header.identifier],
+            )?;
+            for set_spec in &header.set_spec {
+                transaction.execute(
+                    "INSERT OR IGNORE INTO record_sets (identifier, set_spec) VALUES (?1, ?2)",
+                    params![header.identifier, set_spec],
+                )?;
+            }
+        }
+        transaction.commit()
+    }
+
+    /// Delete an item in all its formats at `datestamp`
+    ///
+    /// Unless the `deletedRecord` policy is `no`, the deletion is recorded and
+    /// harvesters see the item with a `deleted` status.
+    pub fn delete_record(&self, identifier: &str, datestamp: Datestamp) -> rusqlite::Result<()> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+        self.delete(&transaction, identifier, None, datestamp)?;
+        transaction.commit()
+    }
+
+    /// Forget deletions stamped before `before`, returning how many were removed
+    ///
+    /// Only allowed by the `transient` policy; with `persistent` this does nothing.
+    pub fn purge_deletions(&self, before: Datestamp) -> rusqlite::Result<usize> {
+        if self.identify.deleted_record == "persistent" {
+            return Ok(0);
+        }
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+        let purged = transaction.execute(
+            "DELETE FROM deletions WHERE datestamp < ?1",
+            params![before.unix()],
+        )?;
+        transaction.execute(
+            "DELETE FROM record_sets WHERE identifier NOT IN (
+                SELECT identifier FROM records UNION SELECT identifier FROM deletions
+            )",
+            [],
+        )?;
+        transaction.commit()?;
+        Ok(purged)
+    }
+
+    // A panic while holding the lock leaves no transaction open, so the connection
+    // is still usable
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn tracks_deletions(&self) -> bool {
+        self.identify.deleted_record != "no"
+    }
+
+    fn granularity(&self) -> Granularity {
+        Granularity::parse(&self.identify.granularity).unwrap_or(Granularity::Second)
+    }
+
+    // Delete `identifier` in one or all formats, recording it as the policy requires
+    fn delete(
+        &self,
+        connection: &Connection,
+        identifier: &str,
+        metadata_prefix: Option<&str>,
+        datestamp: Datestamp,
+    ) -> rusqlite::Result<()> {
+        if self.tracks_deletions() {
+            connection.execute(
+                "INSERT OR REPLACE INTO deletions (identifier, prefix, datestamp)
+                 SELECT identifier, prefix, ?3 FROM records
+                 WHERE identifier = ?1 AND (?2 IS NULL OR prefix = ?2)
+                 UNION SELECT ?1, ?2, ?3 WHERE ?2 IS NOT NULL",
+                params![identifier, metadata_prefix, datestamp.unix()],
+            )?;
+        }
+        connection.execute(
+            "DELETE FROM records WHERE identifier = ?1 AND (?2 IS NULL OR prefix = ?2)",
+            params![identifier, metadata_prefix],
+        )?;
+        if !self.tracks_deletions() {
+            connection.execute(
+                "DELETE FROM record_sets WHERE identifier = ?1
+                 AND NOT EXISTS (SELECT 1 FROM records WHERE identifier = ?1)",
+                params![identifier],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn record(&self, row: &Row, metadata: bool) -> rusqlite::Result<Record> {
+        let sets: Option<String> = row.get("sets")?;
+        let header = Header {
+            identifier: row.get("identifier")?,
+            datestamp: Datestamp::from_unix(row.get("datestamp")?).format(self.granularity()),
+            status: row
+                .get::<_, bool>("deleted")?
+                .then(|| "deleted".to_string()),
+            set_spec: sets
+                .map(|sets| sets.split(' ').map(str::to_string).collect())
+                .unwrap_or_default(),
+        };
+        if !metadata {
+            return Ok(Record {
+                header,
+                metadata: String::new(),
+                about: String::new(),
+            });
+        }
+        Ok(Record {
+            header,
+            metadata: row
+                .get::<_, Option<String>>("metadata")?
+                .unwrap_or_default(),
+            about: row.get::<_, Option<String>>("about")?.unwrap_or_default(),
+        })
+    }
+
+    // One page of items matching `args`, in (datestamp, identifier) order
+    fn list(
+        &self,
+        args: &ListRecordsArgs,
+        cursor: Option<&str>,
+        limit: usize,
+        metadata: bool,
+    ) -> Result<Page<Record>, ProviderError> {
+        let range = DateRange::parse(args.from_datestamp(), args.until_datestamp())
+            .ok_or(ErrorCode::BadArgument)?;
+
+        let mut filter = String::from("prefix = ?2");
+        let mut values = vec![
+            Value::from(self.tracks_deletions()),
+            Value::from(args.metadata_prefix().to_string()),
+        ];
+        let mut bind = |condition: &str, value: Value| {
+            values.push(value);
+            filter.push_str(&condition.replace('?', &format!("?{}", values.len())));
+        };
+        if let Some(from) = range.from {
+            bind(" AND datestamp >= ?", Value::from(from.unix()));
+        }
+        if let Some(until) = range.until {
+            bind(" AND datestamp <= ?", Value::from(until.unix()));
+        }
+        if let Some(set) = args.set_spec() {
+            // The set and its subsets, as an index range: ';' follows ':'
+            bind(
+                " AND identifier IN (SELECT identifier FROM record_sets \
+                 WHERE set_spec = ? OR (set_spec > ? || ':' AND set_spec < ? || ';'))",
+                Value::from(set.to_string()),
+            );
+        }
+        let connection = self.connection();
+        // The list size is counted once, on the first page, and carried in the cursor
+        let complete_list_size = match cursor {
+            Some(cursor) => {
+                let cursor = Cursor::parse(cursor).ok_or(ErrorCode::BadResumptionToken)?;
+                values.push(Value::from(cursor.datestamp));
+                values.push(Value::from(cursor.identifier.to_string()));
+                filter.push_str(&format!(
+                    " AND (datestamp, identifier) > (?{}, ?{})",
+                    values.len() - 1,
+                    values.len()
+                ));
+                cursor.size
+            }
+            None => connection
+                .query_row(
+                    &format!("SELECT count(*) FROM ({})", items(false, &filter, None)),
+                    params_from_iter(&values),
+                    |row| row.get::<_, i64>(0),
+                )
+                .map_err(ProviderError::internal)? as usize,
+        };
+
+        // One more than a page tells whether another follows
+        let sql = items(metadata, &filter, Some(limit + 1));
+        let mut records = connection
+            .prepare(&sql)
+            .and_then(|mut statement| {
+                statement
+                    .query_map(params_from_iter(&values), |row| {
+                        Ok((row.get::<_, i64>("datestamp")?, self.record(row, metadata)?))
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            })
+            .map_err(ProviderError::internal)?;
+
+        let next = (records.len() > limit && limit > 0).then(|| {
+            let (datestamp, record) = &records[limit - 1];
+            format!(
+                "{complete_list_size} {datestamp} {}",
+                record.header.identifier
+            )
+        });
+        records.truncate(limit);
+        let items = records.into_iter().map(|(_, record)| record).collect();
+        let page = match next {
+            Some(next) => Page::new(items, next),
+            None => Page::last(items),
+        };
+        Ok(page.complete_list_size(complete_list_size))
+    }
+}
+
+impl Repository for SqliteRepository {
+    fn identify(&self) -> Identify {
+        self.identify.clone()
+    }
+
+    fn metadata_formats(
+        &self,
+        identifier: Option<&str>,
+    ) -> Result<Vec<MetadataFormat>, ProviderError> {
+        let connection = self.connection();
+        let formats = connection
+            .prepare(
+                "SELECT prefix, namespace, schema FROM formats
+                 WHERE ?1 IS NULL OR prefix IN (
+                     SELECT prefix FROM records WHERE identifier = ?1
+                     UNION SELECT prefix FROM deletions WHERE identifier = ?1 AND ?2
+                 )
+                 ORDER BY prefix",
+            )
+            .and_then(|mut statement| {
+                statement
+                    .query_map(params![identifier, self.tracks_deletions()], |row| {
+                        Ok(MetadataFormat {
+                            metadata_prefix: row.get(0)?,
+                            metadata_namespace: row.get(1)?,
+                            schema: row.get(2)?,
+                        })
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            })
+            .map_err(ProviderError::internal)?;
+
+        if identifier.is_some() && formats.is_empty() {
+            return Err(ErrorCode::IdDoesNotExist.into());
+        }
+        Ok(formats)
+    }
+
+    fn sets(&self) -> Result<Vec<Set>, ProviderError> {
+        let connection = self.connection();
+        connection
+            .prepare("SELECT spec, name, description FROM sets ORDER BY spec")
+            .and_then(|mut statement| {
+                statement
+                    .query_map([], |row| {
+                        Ok(Set {
+                            set_spec: row.get(0)?,
+                            set_name: row.get(1)?,
+                            set_description: row.get::<_, Option<String>>(2)?.into_iter().collect(),
+                        })
+                    })?
+                    .collect()
+            })
+            .map_err(ProviderError::internal)
+    }
+
+    fn get_record(&self, identifier: &str, metadata_prefix: &str) -> Result<Record, ProviderError> {
+        let record = {
+            let connection = self.connection();
+            connection
+                .query_row(
+                    &items(true, "identifier = ?2 AND prefix = ?3", None),
+                    params![self.tracks_deletions(), identifier, metadata_prefix],
+                    |row| self.record(row, true),
+                )
+                .optional()
+                .map_err(ProviderError::internal)?
+        };
+        match record {
+            Some(record) => Ok(record),
+            // Fails with idDoesNotExist for unknown items
+            None => self
+                .metadata_formats(Some(identifier))
+                .and(Err(ErrorCode::CannotDisseminateFormat.into())),
+        }
+    }
+
+    fn list_records(
+        &self,
+        args: &ListRecordsArgs,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<Page<Record>, ProviderError> {
+        self.list(args, cursor, limit, true)
+    }
+
+    fn list_identifiers(
+        &self,
+        args: &ListIdentifiersArgs,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<Page<Header>, ProviderError> {
+        // Headers only, without loading metadata
+        let page = self.list(&args.clone().into(), cursor, limit, false)?;
+        Ok(Page {
+            items: page.items.into_iter().map(|record| record.header).collect(),
+            next: page.next,
+            complete_list_size: page.complete_list_size,
+        })
+    }
+}
+
+fn parse_datestamp(datestamp: &str) -> rusqlite::Result<Datestamp> {
+    Datestamp::parse(datestamp)
+        .map(|(datestamp, _)| datestamp)
+        .ok_or_else(|| {
+            rusqlite::Error::ToSqlConversionFailure(
+                format!("invalid datestamp: {datestamp}").into(),
+            )
+        })
+}
+
+// The list size, then the datestamp and identifier of the last item served
+struct Cursor<'a> {
+    size: usize,
+    datestamp: i64,
+    identifier: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn parse(cursor: &'a str) -> Option<Self> {
+        let mut parts = cursor.splitn(3, ' ');
+        Some(Self {
+            size: parts.next()?.parse().ok()?,
+            datestamp: parts.next()?.parse().ok()?,
+            identifier: parts.next()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repository(deleted_record: &str) -> SqliteRepository {
+        let identify = Identify {
+            repository_name: "SQLite".to_string(),
+            base_url: "https://example.org/oai".to_string(),
+            protocol_version: "2.0".to_string(),
+            admin_email: Vec::new(),
+            earliest_datestamp: "2024-01-01T00:00:00Z".to_string(),
+            deleted_record: deleted_record.to_string(),
+            granularity: "YYYY-MM-DDThh:mm:ssZ".to_string(),
+            compression: Vec::new(),
+            description: Vec::new(),
+        };
+        let repository = SqliteRepository::open_in_memory(identify).unwrap();
+        for prefix in ["oai_dc", "marc21"] {
+            repository
+                .add_format(&MetadataFormat {
+                    metadata_prefix: prefix.to_string(),
+                    metadata_namespace: format!("https://example.org/{prefix}"),
+                    schema: format!("https://example.org/{prefix}.xsd"),
+                })
+                .unwrap();
+        }
+        repository
+    }
+
+    fn record(id: usize, day: usize, sets: &[&str]) -> Record {
+        Record {
+            header: Header {
+                identifier: format!("oai:example.org:{id}"),
+                datestamp: format!("2024-01-{day:02}T12:00:00Z"),
+                status: None,
+                set_spec: sets.iter().map(|s| s.to_string()).collect(),
+            },
+            metadata: format!("<record>{id}</record>"),
+            about: String::new(),
+        }
+    }
+
+    fn identifiers(page: &Page<Record>) -> Vec<&str> {
+        page.items
+            .iter()
+            .map(|r| r.header.identifier.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn keyset_pages_in_datestamp_order() {
+        let repository = repository("no");
+        let records = [
+            record(1, 3, &[]),
+            record(2, 1, &[]),
+            record(3, 2, &[]),
+            record(4, 1, &[]),
+        ];
+        repository.put_records("oai_dc", &records).unwrap();
+        let args = ListRecordsArgs::new("oai_dc");
+
+        let page = repository.list_records(&args, None, 3).unwrap();
+        assert_eq!(
+            identifiers(&page),
+            [
+                "oai:example.org:2",
+                "oai:example.org:4",
+                "oai:example.org:3"
+            ]
+        );
+        assert_eq!(page.complete_list_size, Some(4));
+        assert_eq!(page.items[0].header.datestamp, "2024-01-01T12:00:00Z");
+        assert_eq!(page.items[0].metadata, "<record>2</record>");
+
+        // An earlier record changing between pages moves behind the cursor
+        repository.put_record("oai_dc", &record(2, 4, &[])).unwrap();
+        let page = repository
+            .list_records(&args, page.next.as_deref(), 3)
+            .unwrap();
+        assert_eq!(
+            identifiers(&page),
+            ["oai:example.org:1", "oai:example.org:2"]
+        );
+        assert_eq!(page.next, None);
+
+        let err = repository.list_records(&args, Some("x"), 3).unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::BadResumptionToken));
+    }
+
+    #[test]
+    fn counts_the_list_on_the_first_page() {
+        let repository = repository("no");
+        let records = [record(1, 1, &[]), record(2, 2, &[]), record(3, 3, &[])];
+        repository.put_records("oai_dc", &records).unwrap();
+        let args = ListRecordsArgs::new("oai_dc");
+
+        let page = repository.list_records(&args, None, 2).unwrap();
+        assert_eq!(page.complete_list_size, Some(3));
+
+        // Later pages report the size counted at the start of the list
+        repository.put_record("oai_dc", &record(4, 4, &[])).unwrap();
+        let page = repository
+            .list_records(&args, page.next.as_deref(), 2)
+            .unwrap();
+        assert_eq!(
+            identifiers(&page),
+            ["oai:example.org:3", "oai:example.org:4"]
+        );
+        assert_eq!(page.complete_list_size, Some(3));
+    }
+
+    #[test]
+    fn database_errors_are_internal() {
+        let path = std::env::temp_dir().join(format!("oai-pmh-sqlite-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let repository = SqliteRepository::open(&path, repository("no").identify).unwrap();
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch("DROP TABLE sets; DROP TABLE record_sets;")
+            .unwrap();
+
+        assert!(repository.sets().unwrap_err().is_internal());
+        let args = ListRecordsArgs::new("oai_dc");
+        assert!(
+            repository
+                .list_records(&args, None, 10)
+                .unwrap_err()
+                .is_internal()
+        );
+        let err = repository
+            .get_record("oai:example.org:1", "oai_dc")
+            .unwrap_err();
+        assert!(err.is_internal());
+        // The connection is still usable after a failed request
+        assert!(repository.metadata_formats(None).is_ok());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn filters_by_datestamp_and_set() {
+        let repository = repository("no");
+        let records = [
+            record(1, 1, &["a"]),
+            record(2, 2, &["a", "b"]),
+            record(3, 3, &[]),
+            record(4, 4, &["a:x"]),
+            record(5, 5, &["ab"]),
+        ];
+        repository.put_records("oai_dc", &records).unwrap();
+
+        let args = ListRecordsArgs::new("oai_dc")
+            .from("2024-01-02")
+            .until("2024-01-03");
+        let page = repository.list_records(&args, None, 10).unwrap();
+        assert_eq!(
+            identifiers(&page),
+            ["oai:example.org:2", "oai:example.org:3"]
+        );
+
+        // Subsets are included, but not sets that merely share the prefix
+        let args = ListRecordsArgs::new("oai_dc").set("a");
+        let page = repository.list_records(&args, None, 10).unwrap();
+        assert_eq!(
+            identifiers(&page),
+            [
+                "oai:example.org:1",
+                "oai:example.org:2",
+                "oai:example.org:4"
+            ]
+        );
+        assert_eq!(page.items[1].header.set_spec, ["a", "b"]);
+        assert_eq!(page.items[2].header.set_spec, ["a:x"]);
+
+        let args = ListIdentifiersArgs::new("oai_dc").set("b");
+        let page = repository.list_identifiers(&args, None, 10).unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].identifier, "oai:example.org:2");
+
+        let page = repository
+            .list_records(&ListRecordsArgs::new("marc21"), None, 10)
+            .unwrap();
+        assert!(page.items.is_empty());
+    }
+
+    #[test]
+    fn formats_and_get_record_errors() {
+        let repository = repository("no");
+        repository.put_record("oai_dc", &record(1, 1, &[])).unwrap();
+
+        let formats = repository
+            .metadata_formats(Some("oai:example.org:1"))
+            .unwrap();
+        assert_eq!(formats.len(), 1);
+        assert_eq!(repository.metadata_formats(None).unwrap().len(), 2);
+        let err = repository
+            .metadata_formats(Some("oai:example.org:9"))
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::IdDoesNotExist));
+
+        let err = repository
+            .get_record("oai:example.org:1", "marc21")
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::CannotDisseminateFormat));
+        let err = repository
+            .get_record("oai:example.org:9", "oai_dc")
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::IdDoesNotExist));
+    }
+
+    #[test]
+    fn persistent_deletions_are_served() {
+        let repository = repository("persistent");
+        repository
+            .put_record("oai_dc", &record(1, 1, &["a"]))
+            .unwrap();
+        repository
+            .put_record("marc21", &record(1, 1, &["a"]))
+            .unwrap();
+        repository.put_record("oai_dc", &record(2, 1, &[])).unwrap();
+
+        let deleted = Datestamp::parse("2024-02-01T00:00:00Z").unwrap().0;
+        repository
+            .delete_record("oai:example.org:1", deleted)
+            .unwrap();
+
+        let marc = repository
+            .get_record("oai:example.org:1", "marc21")
+            .unwrap();
+        assert_eq!(marc.header.status.as_deref(), Some("deleted"));
+        assert_eq!(marc.header.datestamp, "2024-02-01T00:00:00Z");
+        assert_eq!(marc.header.set_spec, ["a"]);
+        assert!(marc.metadata.is_empty());
+
+        let args = ListRecordsArgs::new("oai_dc").set("a");
+        let page = repository.list_records(&args, None, 10).unwrap();
+        assert_eq!(identifiers(&page), ["oai:example.org:1"]);
+        assert!(page.items[0].header.status.is_some());
+
+        assert_eq!(repository.purge_deletions(Datestamp::now()).unwrap(), 0);
+
+        // Re-adding a record replaces its deletion
+        repository.put_record("oai_dc", &record(1, 5, &[])).unwrap();
+        let dc = repository
+            .get_record("oai:example.org:1", "oai_dc")
+            .unwrap();
+        assert_eq!(dc.header.status, None);
+    }
+
+    #[test]
+    fn harvested_deletions_are_stored() {
+        let repository = repository("transient");
+        let mut deleted = record(1, 2, &["a"]);
+        deleted.header.status = Some("deleted".to_string());
+        deleted.metadata = String::new();
+        repository.put_record("oai_dc", &deleted).unwrap();
+
+        let record = repository
+            .get_record("oai:example.org:1", "oai_dc")
+            .unwrap();
+        assert_eq!(record.header.status.as_deref(), Some("deleted"));
+        assert_eq!(record.header.set_spec, ["a"]);
+
+        let before = Datestamp::parse("2024-01-02").unwrap().0;
+        assert_eq!(repository.purge_deletions(before).unwrap(), 0);
+        assert_eq!(repository.purge_deletions(Datestamp::now()).unwrap(), 1);
+        let err = repository
+            .get_record("oai:example.org:1", "oai_dc")
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::IdDoesNotExist));
+    }
+
+    #[test]
+    fn no_deletions_policy_forgets_records() {
+        let repository = repository("no");
+        repository
+            .put_record("oai_dc", &record(1, 1, &["a"]))
+            .unwrap();
+        repository
+            .delete_record("oai:example.org:1", Datestamp::now())
+            .unwrap();
+
+        let err = repository
+            .get_record("oai:example.org:1", "oai_dc")
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::IdDoesNotExist));
+        let page = repository
+            .list_records(&ListRecordsArgs::new("oai_dc"), None, 10)
+            .unwrap();
+        assert!(page.items.is_empty());
+    }
+
+    #[test]
+    fn sets_round_trip() {
+        let repository = repository("no");
+        let set = Set {
+            set_spec: "a".to_string(),
+            set_name: "Set A".to_string(),
+            set_description: vec!["<oai_dc:dc/>".to_string()],
+        };
+        repository.add_set(&set).unwrap();
+        assert_eq!(repository.sets().unwrap(), [set]);
+    }
+}
@@ -0,0 +1,428 @@
+//! Stateless resumption tokens.
+//!
+//! A [`ResumptionTokenCodec`] carries everything needed to continue a list request in
+//! the token itself, so a provider keeps no session state between pages. The default
+//! [`TokenCodec`] serializes the [`TokenState`] as compact form-urlencoded pairs,
+//! base64url-encodes them, and can sign the result with HMAC-SHA256 and give tokens
+//! an expiry. Tampered, malformed and expired tokens are rejected with
+//! `badResumptionToken`.
+//!
+//! # Example
+//! ```
+//! use std::time::Duration;
+//! use oai_pmh::{ListRecordsArgs, Verb};
+//! use oai_pmh::client::response::ErrorCode;
+//! use oai_pmh::provider::token::{ResumptionTokenCodec, TokenCodec, TokenState};
+//!
+//! let codec = TokenCodec::new()
+//!     .secret("change me")
+//!     .ttl(Duration::from_secs(3600));
+//!
+//! let state = TokenState::new(Verb::ListRecords, ListRecordsArgs::new("oai_dc"), "42", 100);
+//! let token = codec.resumption_token(Some(state.clone()), 0, Some(250));
+//! assert_eq!(token.cursor, Some(0));
+//! assert!(token.expiration_date.is_some());
+//!
+//! let decoded = codec.decode(&token.token).unwrap();
+//! assert_eq!(decoded.cursor, "42");
+//!
+//! let tampered = token.token.replacen('.', "x.", 1);
+//! assert_eq!(codec.decode(&tampered).unwrap_err().code(), Some(ErrorCode::BadResumptionToken));
+//! ```
+use std::time::Duration;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::Verb;
+use crate::client::query::ListRecordsArgs;
+use crate::client::response::{ErrorCode, ResumptionToken};
+use crate::provider::ProviderError;
+use crate::provider::datestamp::Datestamp;
+use crate::provider::request::verb_from_str;
+
+/// State carried between pages of a list request
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenState {
+    pub verb: Verb,
+    /// Arguments of the original request
+    pub args: ListRecordsArgs,
+    /// The repository's cursor or last-seen key for the next page
+    pub cursor: String,
+    /// Number of items returned before the next page
+    pub position: usize,
+    /// When the token stops being accepted
+    pub expires: Option<Datestamp>,
+}
+
+impl TokenState {
+    pub fn new(
+        verb: Verb,
+        args: ListRecordsArgs,
+        cursor: impl Into<String>,
+        position: usize,
+    ) -> Self {
+        Self {
+            verb,
+            args,
+            cursor: cursor.into(),
+            position,
+            expires: None,
+        }
+    }
+}
+
+/// Turns [`TokenState`] into resumption tokens and back
+pub trait ResumptionTokenCodec: Send + Sync {
+    /// Encode `state`, including its expiry if set
+    fn encode(&self, state: &TokenState) -> String;
+
+    /// Decode a token, failing with `badResumptionToken` if it is malformed,
+    /// tampered with or expired
+    fn decode(&self, token: &str) -> Result<TokenState, ProviderError>;
+
+    /// How long issued tokens remain valid, or `None` if they don't expire
+    fn ttl(&self) -> Option<Duration> {
+        None
+    }
+
+    /// The `resumptionToken` element for a page of an incomplete list
+    ///
+    /// `next` is the state for the following page, or `None` on the last page,
+    /// which gets an empty token. `cursor` is the number of items returned before
+    /// this page.
+    fn resumption_token(
+        &self,
+        next: Option<TokenState>,
+        cursor: usize,
+        complete_list_size: Option<usize>,
+    ) -> ResumptionToken {
+        let (token, expiration_date) = match next {
+            Some(mut state) => {
+                state.expires = self.ttl().map(|ttl| {
+                    let ttl = i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX);
+                    Datestamp::from_unix(Datestamp::now().unix().saturating_add(ttl))
+                });
+                (self.encode(&state), state.expires.map(|d| d.to_string()))
+            }
+            None => (String::new(), None),
+        };
+
+        ResumptionToken {
+            token,
+            expiration_date,
+            complete_list_size: complete_list_size.map(|size| size as u64),
+            cursor: Some(cursor as u64),
+        }
+    }
+}
+
+/// The default codec: base64url-encoded state, optionally signed and expiring
+///
+/// With a secret, each token carries an HMAC-SHA256 signature after a `.`.
+///
+/// # Security
+///
+/// **Without a [`secret`](Self::secret), tokens are forgeable.** They are only encoded,
+/// so a harvester can decode one, change its arguments, cursor or expiry and send it
+/// back. [`Provider`](super::Provider) validates the arguments of a resumed request
+/// again, but the cursor is passed to the repository unchecked, so repositories must
+/// treat it as untrusted input. Set a secret for any public endpoint.
+#[derive(Clone, Debug, Default)]
+pub struct TokenCodec {
+    secret: Option<Vec<u8>>,
+    ttl: Option<Duration>,
+}
+
+impl TokenCodec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sign tokens with HMAC-SHA256 using `secret`
+    pub fn secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.secret = Some(secret.as_ref().to_vec());
+        self
+    }
+
+    /// Expire tokens `ttl` after they are issued
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    fn mac(&self, secret: &[u8], payload: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+        mac.update(payload.as_bytes());
+        mac
+    }
+}
+
+impl ResumptionTokenCodec for TokenCodec {
+    fn encode(&self, state: &TokenState) -> String {
+        let payload = URL_SAFE_NO_PAD.encode(serialize(state));
+        match &self.secret {
+            Some(secret) => {
+                let signature = self.mac(secret, &payload).finalize().into_bytes();
+                format!("{payload}.{}", URL_SAFE_NO_PAD.encode(signature))
+            }
+            None => payload,
+        }
+    }
+
+    fn decode(&self, token: &str) -> Result<TokenState, ProviderError> {
+        let payload = match &self.secret {
+            Some(secret) => {
+                let (payload, signature) = token.split_once('.').ok_or_else(bad_token)?;
+                let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| bad_token())?;
+                self.mac(secret, payload)
+                    .verify_slice(&signature)
+                    .map_err(|_| bad_token())?;
+                payload
+            }
+            None => token,
+        };
+
+        let bytes = URL_SAFE_NO_PAD.decode(payload).map_err(|_| bad_token())?;
+        let state = deserialize(&bytes).ok_or_else(bad_token)?;
+
+        if state
+            .expires
+            .is_some_and(|expires| expires < Datestamp::now())
+        {
+            return Err(ProviderError::new(
+                ErrorCode::BadResumptionToken,
+                "The resumption token has expired",
+            ));
+        }
+        Ok(state)
+    }
+
+    fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+}
+
+fn bad_token() -> ProviderError {
+    ProviderError::new(
+        ErrorCode::BadResumptionToken,
+        "The resumption token is invalid",
+    )
+}
+
+// Single-letter keys keep tokens short
+fn serialize(state: &TokenState) -> String {
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    serializer
+        .append_pair("v", &state.verb.to_string())
+        .append_pair("m", state.args.metadata_prefix())
+        .append_pair("c", &state.cursor)
+        .append_pair("p", &state.position.to_string());
+    let optional = [
+        ("f", state.args.from_datestamp()),
+        ("u", state.args.until_datestamp()),
+        ("s", state.args.set_spec()),
+    ];
+    for (key, value) in optional {
+        if let Some(value) = value {
+            serializer.append_pair(key, value);
+        }
+    }
+    if let Some(expires) = state.expires {
+        serializer.append_pair("e", &expires.unix().to_string());
+    }
+    serializer.finish()
+}
+
+fn deserialize(bytes: &[u8]) -> Option<TokenState> {
+    let (mut verb, mut prefix, mut cursor, mut position, mut expires) =
+        (None, None, None, None, None);
+    let (mut from, mut until, mut set) = (None, None, None);
+
+    for (key, value) in url::form_urlencoded::parse(bytes) {
+        let slot = match key.as_ref() {
+            "v" => &mut verb,
+            "m" => &mut prefix,
+            "c" => &mut cursor,
+            "p" => &mut position,
+            "e" => &mut expires,
+            "f" => &mut from,
+            "u" => &mut until,
+            "s" => &mut set,
+            _ => return None,
+        };
+        if slot.replace(value.into_owned()).is_some() {
+            return None;
+        }
+    }
+
+    let mut args = ListRecordsArgs::new(prefix?);
+    if let Some(from) = from {
+        args = args.from(from);
+    }
+    if let Some(until) = until {
+        args = args.until(until);
+    }
+    if let Some(set) = set {
+        args = args.set(set);
+    }
+
+    let expires = match expires {
+        Some(expires) => Some(Datestamp::from_unix(expires.parse().ok()?)),
+        None => None,
+    };
+    Some(TokenState {
+        verb: verb_from_str(&verb?)?,
+        args,
+        cursor: cursor?,
+        position: position?.parse().ok()?,
+        expires,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> TokenState {
+        let args = ListRecordsArgs::new("oai_dc")
+            .from("2024-01-01")
+            .set("a:b&c=d");
+        TokenState::new(Verb::ListIdentifiers, args, "last=oai:x:9", 100)
+    }
+
+    fn code(codec: &TokenCodec, token: &str) -> ErrorCode {
+        codec.decode(token).unwrap_err().code().unwrap()
+    }
+
+    #[test]
+    fn round_trips_unsigned() {
+        let codec = TokenCodec::new();
+        let token = codec.encode(&state());
+
+        assert!(
+            token
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        );
+        assert_eq!(codec.decode(&token).unwrap(), state());
+    }
+
+    #[test]
+    fn round_trips_signed() {
+        let codec = TokenCodec::new().secret("s3cret");
+        let token = codec.encode(&state());
+
+        assert_eq!(token.matches('.').count(), 1);
+        assert_eq!(codec.decode(&token).unwrap(), state());
+    }
+
+    #[test]
+    fn rejects_tampered_tokens() {
+        let codec = TokenCodec::new().secret("s3cret");
+        let token = codec.encode(&state());
+        let (payload, signature) = token.split_once('.').unwrap();
+
+        // Re-encode the state with a different cursor but keep the old signature
+        let mut forged = state();
+        forged.cursor = "last=oai:x:0".to_string();
+        let forged_payload = URL_SAFE_NO_PAD.encode(serialize(&forged));
+        assert_eq!(
+            code(&codec, &format!("{forged_payload}.{signature}")),
+            ErrorCode::BadResumptionToken
+        );
+
+        assert_eq!(code(&codec, payload), ErrorCode::BadResumptionToken);
+        assert_eq!(
+            code(&codec, &format!("{payload}.AAAA")),
+            ErrorCode::BadResumptionToken
+        );
+
+        let other = TokenCodec::new().secret("other");
+        assert_eq!(code(&other, &token), ErrorCode::BadResumptionToken);
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        let codec = TokenCodec::new();
+        for token in [
+            "",
+            "not base64!",
+            &URL_SAFE_NO_PAD.encode("v=ListRecords&m=oai_dc&c=1&p=x"),
+            &URL_SAFE_NO_PAD.encode("v=Nope&m=oai_dc&c=1&p=0"),
+            &URL_SAFE_NO_PAD.encode("v=ListRecords&m=oai_dc&p=0"),
+            &URL_SAFE_NO_PAD.encode("v=ListRecords&m=oai_dc&c=1&c=2&p=0"),
+            &URL_SAFE_NO_PAD.encode("v=ListRecords&m=oai_dc&c=1&p=0&z=1"),
+        ] {
+            assert_eq!(
+                code(&codec, token),
+                ErrorCode::BadResumptionToken,
+                "{token}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_repeated_keys() {
+        let codec = TokenCodec::new();
+        let mut expired = state();
+        expired.expires = Some(Datestamp::from_unix(Datestamp::now().unix() - 1));
+        let later = Datestamp::now().unix() + 60;
+
+        // A second expiry, position or verb doesn't override the first
+        for payload in [
+            format!("{}&e={later}", serialize(&expired)),
+            format!("{}&p=0", serialize(&state())),
+            format!("{}&v=ListRecords", serialize(&state())),
+        ] {
+            let token = URL_SAFE_NO_PAD.encode(&payload);
+            assert_eq!(
+                code(&codec, &token),
+                ErrorCode::BadResumptionToken,
+                "{payload}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_expired_tokens() {
+        let codec = TokenCodec::new().secret("s3cret");
+        let mut expired = state();
+        expired.expires = Some(Datestamp::from_unix(Datestamp::now().unix() - 1));
+        assert_eq!(
+            code(&codec, &codec.encode(&expired)),
+            ErrorCode::BadResumptionToken
+        );
+
+        let mut valid = state();
+        valid.expires = Some(Datestamp::from_unix(Datestamp::now().unix() + 60));
+        assert!(codec.decode(&codec.encode(&valid)).is_ok());
+    }
+
+    #[test]
+    fn saturates_long_ttls() {
+        let codec = TokenCodec::new().ttl(Duration::MAX);
+        let token = codec.resumption_token(Some(state()), 0, None);
+        let expires = codec.decode(&token.token).unwrap().expires.unwrap();
+        assert_eq!(expires.unix(), i64::MAX);
+    }
+
+    #[test]
+    fn builds_resumption_token_element() {
+        let codec = TokenCodec::new().ttl(Duration::from_secs(600));
+
+        let token = codec.resumption_token(Some(state()), 50, Some(120));
+        assert_eq!(token.cursor, Some(50));
+        assert_eq!(token.complete_list_size, Some(120));
+        let (expires, _) = Datestamp::parse(token.expiration_date.as_deref().unwrap()).unwrap();
+        assert_eq!(codec.decode(&token.token).unwrap().expires, Some(expires));
+
+        // The last page has an empty token and no expiry
+        let last = codec.resumption_token(None, 100, Some(120));
+        assert!(last.token.is_empty());
+        assert!(last.expiration_date.is_none());
+        assert_eq!(last.cursor, Some(100));
+    }
+}
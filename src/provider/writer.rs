@@ -0,0 +1,317 @@
+//! Writing OAI-PMH 2.0 responses from the client's response types.
+//!
+//! [`ResponseWriter`] produces the complete document: the `OAI-PMH` root with its
+//! `xsi:schemaLocation`, `responseDate`, the `request` element echoing the
+//! arguments, and the verb or error element. Text and attribute values are
+//! escaped; metadata, `about` and description fragments are embedded verbatim
+//! and must be well-formed XML.
+//!
+//! # Example
+//! ```
+//! use oai_pmh::client::response::{Header, ListIdentifiersResponse};
+//! use oai_pmh::provider::writer::ResponseWriter;
+//!
+//! let header = Header {
+//!     identifier: "oai:example.org:1".to_string(),
+//!     datestamp: "2024-01-01".to_string(),
+//!     status: None,
+//!     set_spec: vec!["maps".to_string()],
+//! };
+//!
+//! let xml = ResponseWriter::new("https://example.org/oai")
+//!     .request([("verb", "ListIdentifiers"), ("metadataPrefix", "oai_dc")])
+//!     .list_identifiers(&[header.clone()], None);
+//!
+//! let response = ListIdentifiersResponse::new(&xml).unwrap();
+//! assert_eq!(response.payload.unwrap().header, vec![header]);
+//! ```
+use quick_xml::escape::escape;
+
+use crate::client::response::{
+    ErrorCode, Header, Identify, MetadataFormat, Record, ResponseError, ResumptionToken, Set,
+};
+use crate::provider::datestamp::Datestamp;
+
+pub const NAMESPACE: &str = "http://www.openarchives.org/OAI/2.0/";
+pub const SCHEMA: &str = "http://www.openarchives.org/OAI/2.0/OAI-PMH.xsd";
+
+/// Writes OAI-PMH response documents for one request
+#[derive(Clone, Debug)]
+pub struct ResponseWriter {
+    base_url: String,
+    response_date: Datestamp,
+    request: Vec<(String, String)>,
+}
+
+impl ResponseWriter {
+    /// A writer for responses from `base_url`, dated now
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            response_date: Datestamp::now(),
+            request: Vec::new(),
+        }
+    }
+
+    pub fn response_date(mut self, response_date: Datestamp) -> Self {
+        self.response_date = response_date;
+        self
+    }
+
+    /// Arguments echoed as attributes of the `request` element, including `verb`
+    ///
+    /// Leave these out for `badVerb` and `badArgument` errors, as the spec requires.
+    pub fn request<K: Into<String>, V: Into<String>>(
+        mut self,
+        args: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.request = args
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        self
+    }
+
+    pub fn identify(&self, identify: &Identify) -> String {
+        let mut xml = String::from("<Identify>\n");
+        element(&mut xml, "repositoryName", &identify.repository_name);
+        element(&mut xml, "baseURL", &identify.base_url);
+        element(&mut xml, "protocolVersion", &identify.protocol_version);
+        for email in &identify.admin_email {
+            element(&mut xml, "adminEmail", email);
+        }
+        element(&mut xml, "earliestDatestamp", &identify.earliest_datestamp);
+        element(&mut xml, "deletedRecord", &identify.deleted_record);
+        element(&mut xml, "granularity", &identify.granularity);
+        for compression in &identify.compression {
+            element(&mut xml, "compression", compression);
+        }
+        for description in &identify.description {
+            verbatim(&mut xml, "description", description);
+        }
+        xml.push_str("</Identify>\n");
+        self.document(&xml)
+    }
+
+    pub fn list_metadata_formats(&self, formats: &[MetadataFormat]) -> String {
+        let mut xml = String::from("<ListMetadataFormats>\n");
+        for format in formats {
+            xml.push_str("<metadataFormat>\n");
+            element(&mut xml, "metadataPrefix", &format.metadata_prefix);
+            element(&mut xml, "schema", &format.schema);
+            element(&mut xml, "metadataNamespace", &format.metadata_namespace);
+            xml.push_str("</metadataFormat>\n");
+        }
+        xml.push_str("</ListMetadataFormats>\n");
+        self.document(&xml)
+    }
+
+    pub fn list_sets(&self, sets: &[Set], resumption_token: Option<&ResumptionToken>) -> String {
+        let mut xml = String::from("<ListSets>\n");
+        for set in sets {
+            xml.push_str("<set>\n");
+            element(&mut xml, "setSpec", &set.set_spec);
+            element(&mut xml, "setName", &set.set_name);
+            for description in &set.set_description {
+                verbatim(&mut xml, "setDescription", description);
+            }
+            xml.push_str("</set>\n");
+        }
+        write_resumption_token(&mut xml, resumption_token);
+        xml.push_str("</ListSets>\n");
+        self.document(&xml)
+    }
+
+    pub fn get_record(&self, record: &Record) -> String {
+        let mut xml = String::from("<GetRecord>\n");
+        write_record(&mut xml, record);
+        xml.push_str("</GetRecord>\n");
+        self.document(&xml)
+    }
+
+    pub fn list_identifiers(
+        &self,
+        headers: &[Header],
+        resumption_token: Option<&ResumptionToken>,
+    ) -> String {
+        let mut xml = String::from("<ListIdentifiers>\n");
+        for header in headers {
+            write_header(&mut xml, header);
+        }
+        write_resumption_token(&mut xml, resumption_token);
+        xml.push_str("</ListIdentifiers>\n");
+        self.document(&xml)
+    }
+
+    pub fn list_records(
+        &self,
+        records: &[Record],
+        resumption_token: Option<&ResumptionToken>,
+    ) -> String {
+        let mut xml = String::from("<ListRecords>\n");
+        for record in records {
+            write_record(&mut xml, record);
+        }
+        write_resumption_token(&mut xml, resumption_token);
+        xml.push_str("</ListRecords>\n");
+        self.document(&xml)
+    }
+
+    pub fn error(&self, code: ErrorCode, message: &str) -> String {
+        self.errors(&[ResponseError {
+            code,
+            message: message.to_string(),
+        }])
+    }
+
+    /// An error response; the protocol allows several errors in one response
+    pub fn errors(&self, errors: &[ResponseError]) -> String {
+        let mut xml = String::new();
+        for error in errors {
+            xml.push_str(&format!(
+                "<error code=\"{}\">{}</error>\n",
+                error.code,
+                escape(&error.message)
+            ));
+        }
+        self.document(&xml)
+    }
+
+    fn document(&self, body: &str) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<OAI-PMH xmlns=\"{NAMESPACE}\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:schemaLocation=\"{NAMESPACE} {SCHEMA}\">\n"
+        ));
+        element(&mut xml, "responseDate", &self.response_date.to_string());
+
+        xml.push_str("<request");
+        for (name, value) in &self.request {
+            attribute(&mut xml, name, value);
+        }
+        xml.push('>');
+        xml.push_str(&escape(&self.base_url));
+        xml.push_str("</request>\n");
+
+        xml.push_str(body);
+        xml.push_str("</OAI-PMH>\n");
+        xml
+    }
+}
+
+fn write_record(xml: &mut String, record: &Record) {
+    xml.push_str("<record>\n");
+    write_header(xml, &record.header);
+    // Deleted records carry no metadata
+    if !record.is_deleted() {
+        verbatim(xml, "metadata", &record.metadata);
+    }
+    if !record.about.is_empty() {
+        verbatim(xml, "about", &record.about);
+    }
+    xml.push_str("</record>\n");
+}
+
+fn write_header(xml: &mut String, header: &Header) {
+    xml.push_str("<header");
+    if let Some(status) = &header.status {
+        attribute(xml, "status", status);
+    }
+    xml.push_str(">\n");
+    element(xml, "identifier", &header.identifier);
+    element(xml, "datestamp", &header.datestamp);
+    for spec in &header.set_spec {
+        element(xml, "setSpec", spec);
+    }
+    xml.push_str("</header>\n");
+}
+
+fn write_resumption_token(xml: &mut String, token: Option<&ResumptionToken>) {
+    let Some(token) = token else {
+        return;
+    };
+    xml.push_str("<resumptionToken");
+    if let Some(expiration_date) = &token.expiration_date {
+        attribute(xml, "expirationDate", expiration_date);
+    }
+    if let Some(size) = token.complete_list_size {
+        attribute(xml, "completeListSize", &size.to_string());
+    }
+    if let Some(cursor) = token.cursor {
+        attribute(xml, "cursor", &cursor.to_string());
+    }
+    xml.push('>');
+    xml.push_str(&escape(&token.token));
+    xml.push_str("</resumptionToken>\n");
+}
+
+fn element(xml: &mut String, name: &str, text: &str) {
+    xml.push_str(&format!("<{name}>{}</{name}>\n", escape(text)));
+}
+
+// Wrap an XML fragment without escaping it
+fn verbatim(xml: &mut String, name: &str, fragment: &str) {
+    xml.push_str(&format!("<{name}>{fragment}</{name}>\n"));
+}
+
+fn attribute(xml: &mut String, name: &str, value: &str) {
+    xml.push_str(&format!(" {name}=\"{}\"", escape(value)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn writer() -> ResponseWriter {
+        let (date, _) = Datestamp::parse("2024-01-02T03:04:05Z").unwrap();
+        ResponseWriter::new("https://example.org/oai?a=1&b=2").response_date(date)
+    }
+
+    #[test]
+    fn writes_envelope() {
+        let xml = writer()
+            .request([("verb", "GetRecord"), ("identifier", "a\"b")])
+            .error(ErrorCode::IdDoesNotExist, "no <such> item");
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<OAI-PMH xmlns=\"http://www.openarchives.org/OAI/2.0/\""));
+        assert!(xml.contains("xsi:schemaLocation=\"http://www.openarchives.org/OAI/2.0/ http://www.openarchives.org/OAI/2.0/OAI-PMH.xsd\""));
+        assert!(xml.contains("<responseDate>2024-01-02T03:04:05Z</responseDate>"));
+        assert!(xml.contains(
+            "<request verb=\"GetRecord\" identifier=\"a&quot;b\">https://example.org/oai?a=1&amp;b=2</request>"
+        ));
+        assert!(xml.contains("<error code=\"idDoesNotExist\">no &lt;such&gt; item</error>"));
+    }
+
+    #[test]
+    fn deleted_records_have_no_metadata() {
+        let record = Record {
+            header: Header {
+                identifier: "oai:example.org:1".to_string(),
+                datestamp: "2024-01-01".to_string(),
+                status: Some("deleted".to_string()),
+                set_spec: Vec::new(),
+            },
+            metadata: "<stale/>".to_string(),
+            about: String::new(),
+        };
+
+        let xml = writer().get_record(&record);
+        assert!(xml.contains("<header status=\"deleted\">"));
+        assert!(!xml.contains("<metadata>"));
+        assert!(!xml.contains("<about>"));
+    }
+
+    #[test]
+    fn resumption_token_attributes() {
+        let token = ResumptionToken {
+            token: "a&b".to_string(),
+            expiration_date: Some("2024-01-03T00:00:00Z".to_string()),
+            complete_list_size: Some(10),
+            cursor: Some(0),
+        };
+
+        let xml = writer().list_sets(&[], Some(&token));
+        assert!(xml.contains(
+            "<resumptionToken expirationDate=\"2024-01-03T00:00:00Z\" completeListSize=\"10\" cursor=\"0\">a&amp;b</resumptionToken>"
+        ));
+    }
+}
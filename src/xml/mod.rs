@@ -0,0 +1,399 @@
+//! A lightweight, owned XML tree for pulling a few values out of record metadata.
+//!
+//! [`Element`] keeps namespace URIs and declarations, and supports a small path query
+//! language (see [`Path`]) for when a full format model would be overkill.
+//!
+//! # Example
+//! ```
+//! use oai_pmh::xml::{Element, Namespaces};
+//!
+//! let xml = r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/"
+//!                         xmlns:dc="http://purl.org/dc/elements/1.1/">
+//!   <dc:identifier>urn:isbn:0-395-36341-1</dc:identifier>
+//!   <dc:identifier>https://hdl.handle.net/2027/abc</dc:identifier>
+//!   <dc:rights>CC BY 4.0</dc:rights>
+//! </oai_dc:dc>"#;
+//!
+//! let tree = Element::parse(xml).unwrap();
+//! let ns = Namespaces::common();
+//!
+//! assert_eq!(tree.value("dc:rights", &ns).unwrap().as_deref(), Some("CC BY 4.0"));
+//! assert_eq!(tree.values("//dc:identifier", &ns).unwrap().len(), 2);
+//! ```
+mod path;
+
+pub use path::{Namespaces, Path};
+
+use quick_xml::NsReader;
+use quick_xml::escape::{escape, partial_escape, resolve_xml_entity};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::ResolveResult;
+
+use crate::error::{Error, Result};
+
+/// An owned XML element with resolved namespaces
+#[derive(Clone, Debug, PartialEq)]
+pub struct Element {
+    /// Qualified name as written, e.g. `dc:title`
+    pub name: String,
+    /// Resolved namespace URI, if any
+    pub namespace: Option<String>,
+    pub attributes: Vec<Attribute>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attribute {
+    /// Qualified name as written, e.g. `xml:lang`
+    pub name: String,
+    /// Resolved namespace URI, for prefixed attributes
+    pub namespace: Option<String>,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    /// Parse a document or fragment, returning its root element
+    pub fn parse(xml: &str) -> Result<Self> {
+        let mut reader = NsReader::from_str(xml);
+        let mut stack: Vec<Element> = Vec::new();
+
+        loop {
+            let (ns, event) = reader.read_resolved_event()?;
+            let namespace = match ns {
+                ResolveResult::Bound(ns) => Some(String::from_utf8_lossy(ns.as_ref()).into_owned()),
+                _ => None,
+            };
+
+            match event {
+                Event::Start(e) => stack.push(Self::from_start(&reader, &e, namespace)?),
+                Event::Empty(e) => {
+                    let element = Self::from_start(&reader, &e, namespace)?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(Node::Element(element)),
+                        None => return Ok(element),
+                    }
+                }
+                Event::End(_) => {
+                    let element = stack.pop().expect("reader checks end tags");
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(Node::Element(element)),
+                        None => return Ok(element),
+                    }
+                }
+                Event::Text(e) => {
+                    if let Some(parent) = stack.last_mut() {
+                        parent.push_text(&e.xml_content().map_err(xml_err)?);
+                    }
+                }
+                Event::CData(e) => {
+                    if let Some(parent) = stack.last_mut() {
+                        parent.push_text(&e.xml_content().map_err(xml_err)?);
+                    }
+                }
+                Event::GeneralRef(e) => {
+                    if let Some(parent) = stack.last_mut() {
+                        let text = match e.resolve_char_ref()? {
+                            Some(c) => c.to_string(),
+                            None => {
+                                let name = e.decode().map_err(xml_err)?;
+                                match resolve_xml_entity(&name) {
+                                    Some(s) => s.to_string(),
+                                    None => format!("&{name};"),
+                                }
+                            }
+                        };
+                        parent.push_text(&text);
+                    }
+                }
+                Event::Eof => {
+                    return Err(Error::Metadata("no root element found".to_string()));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn from_start(
+        reader: &NsReader<&[u8]>,
+        e: &BytesStart,
+        namespace: Option<String>,
+    ) -> Result<Self> {
+        let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+        let mut attributes = Vec::new();
+        for attr in e.attributes() {
+            let attr = attr.map_err(xml_err)?;
+            let namespace = match reader.resolver().resolve_attribute(attr.key).0 {
+                ResolveResult::Bound(ns) => Some(String::from_utf8_lossy(ns.as_ref()).into_owned()),
+                _ => None,
+            };
+            attributes.push(Attribute {
+                name: String::from_utf8_lossy(attr.key.as_ref()).into_owned(),
+                namespace,
+                value: attr.unescape_value()?.into_owned(),
+            });
+        }
+
+        Ok(Self {
+            name,
+            namespace,
+            attributes,
+            children: Vec::new(),
+        })
+    }
+
+    // Merge adjacent text so entity references don't split text nodes
+    fn push_text(&mut self, text: &str) {
+        match self.children.last_mut() {
+            Some(Node::Text(last)) => last.push_str(text),
+            _ => self.children.push(Node::Text(text.to_string())),
+        }
+    }
+
+    /// The name without its namespace prefix
+    pub fn local_name(&self) -> &str {
+        self.name.rsplit(':').next().unwrap_or_default()
+    }
+
+    /// Returns the value of the attribute with the given qualified name
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|a| a.name == name)
+            .map(|a| a.value.as_str())
+    }
+
+    /// Returns the value of the attribute with the given namespace URI and local name,
+    /// whatever prefix the document binds to the namespace
+    pub fn attr_ns(&self, namespace: &str, local_name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|a| {
+                a.namespace.as_deref() == Some(namespace)
+                    && a.name.rsplit(':').next() == Some(local_name)
+            })
+            .map(|a| a.value.as_str())
+    }
+
+    /// Iterates over child elements
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// Iterates over child elements with the given local name, in any namespace
+    pub fn elements_named<'a>(&'a self, local_name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.elements()
+            .filter(move |e| e.local_name() == local_name)
+    }
+
+    /// The first child element with the given local name
+    pub fn child(&self, local_name: &str) -> Option<&Element> {
+        self.elements().find(|e| e.local_name() == local_name)
+    }
+
+    /// Trimmed text of the first child element with the given local name
+    pub fn child_text(&self, local_name: &str) -> Option<String> {
+        self.child(local_name).map(Element::trimmed_text)
+    }
+
+    /// Trimmed text of each child element with the given local name
+    pub fn child_texts(&self, local_name: &str) -> Vec<String> {
+        self.elements_named(local_name)
+            .map(Element::trimmed_text)
+            .collect()
+    }
+
+    /// All descendant text, concatenated in document order
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        self.collect_text(&mut text);
+        text
+    }
+
+    /// [`text`](Self::text) without leading and trailing whitespace
+    pub fn trimmed_text(&self) -> String {
+        self.text().trim().to_string()
+    }
+
+    /// Elements matching a path query, see [`Path`]
+    pub fn select(&self, path: &str, namespaces: &Namespaces) -> Result<Vec<&Element>> {
+        Ok(Path::parse(path, namespaces)?.select(self))
+    }
+
+    /// Trimmed text of matching elements, or values of a trailing `@attribute` step
+    pub fn values(&self, path: &str, namespaces: &Namespaces) -> Result<Vec<String>> {
+        Ok(Path::parse(path, namespaces)?.values(self))
+    }
+
+    /// The first of [`values`](Self::values)
+    pub fn value(&self, path: &str, namespaces: &Namespaces) -> Result<Option<String>> {
+        Ok(self.values(path, namespaces)?.into_iter().next())
+    }
+
+    /// Namespace declarations made on this element, as `(prefix, uri)` pairs.
+    /// The default namespace has an empty prefix.
+    pub fn namespace_declarations(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attributes.iter().filter_map(|a| {
+            if a.name == "xmlns" {
+                Some(("", a.value.as_str()))
+            } else {
+                a.name
+                    .strip_prefix("xmlns:")
+                    .map(|prefix| (prefix, a.value.as_str()))
+            }
+        })
+    }
+
+    /// The namespace declarations in scope at this element: `inherited` ones from its
+    /// ancestors, overridden by those made on the element itself
+    pub fn scope(&self, inherited: &[(String, String)]) -> Vec<(String, String)> {
+        let mut scope = inherited.to_vec();
+        for (prefix, uri) in self.namespace_declarations() {
+            scope.retain(|(p, _)| p != prefix);
+            scope.push((prefix.to_string(), uri.to_string()));
+        }
+        scope
+    }
+
+    /// Serialize the element, adding any `inherited` declarations it doesn't
+    /// redeclare so the result is a standalone document.
+    pub fn to_xml_in_scope(&self, inherited: &[(String, String)]) -> String {
+        let mut xml = String::new();
+        let declared: Vec<&str> = self.namespace_declarations().map(|(p, _)| p).collect();
+        let extra: Vec<Attribute> = inherited
+            .iter()
+            .filter(|(prefix, _)| !declared.contains(&prefix.as_str()))
+            .map(|(prefix, uri)| Attribute {
+                name: match prefix.as_str() {
+                    "" => "xmlns".to_string(),
+                    p => format!("xmlns:{p}"),
+                },
+                namespace: None,
+                value: uri.clone(),
+            })
+            .collect();
+
+        self.write_xml(&mut xml, &extra);
+        xml
+    }
+
+    fn write_xml(&self, xml: &mut String, extra: &[Attribute]) {
+        xml.push('<');
+        xml.push_str(&self.name);
+        for attr in extra.iter().chain(&self.attributes) {
+            xml.push_str(&format!(" {}=\"{}\"", attr.name, escape(&attr.value)));
+        }
+
+        if self.children.is_empty() {
+            xml.push_str("/>");
+            return;
+        }
+
+        xml.push('>');
+        for node in &self.children {
+            match node {
+                Node::Element(e) => e.write_xml(xml, &[]),
+                Node::Text(t) => xml.push_str(&partial_escape(t)),
+            }
+        }
+        xml.push_str(&format!("</{}>", self.name));
+    }
+
+    fn collect_text(&self, text: &mut String) {
+        for node in &self.children {
+            match node {
+                Node::Element(e) => e.collect_text(text),
+                Node::Text(t) => text.push_str(t),
+            }
+        }
+    }
+}
+
+fn xml_err(err: impl Into<quick_xml::Error>) -> Error {
+    Error::Xml(err.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_namespaced_tree() {
+        let xml = r#"<oai_dc:dc xmlns:oai_dc="urn:oai_dc" xmlns:dc="urn:dc">
+            <dc:title xml:lang="en">Tom &amp; Jerry&#33;</dc:title>
+            <dc:subject><![CDATA[<cats>]]></dc:subject>
+            <empty/>
+        </oai_dc:dc>"#;
+
+        let root = Element::parse(xml).unwrap();
+        assert_eq!(root.namespace.as_deref(), Some("urn:oai_dc"));
+        assert_eq!(root.local_name(), "dc");
+
+        let children: Vec<&Element> = root.elements().collect();
+        assert_eq!(children.len(), 3);
+        assert_eq!(children[0].namespace.as_deref(), Some("urn:dc"));
+        assert_eq!(children[0].text(), "Tom & Jerry!");
+        assert_eq!(children[0].attr("xml:lang"), Some("en"));
+        assert_eq!(
+            children[0].attributes[0].namespace.as_deref(),
+            Some("http://www.w3.org/XML/1998/namespace")
+        );
+        assert_eq!(children[1].text(), "<cats>");
+        assert_eq!(children[2].local_name(), "empty");
+        assert_eq!(children[2].namespace, None);
+    }
+
+    #[test]
+    fn child_accessors() {
+        let xml =
+            r#"<record xmlns:a="urn:a"><a:title> One </a:title><note/><title>Two</title></record>"#;
+        let root = Element::parse(xml).unwrap();
+
+        assert_eq!(root.elements_named("title").count(), 2);
+        assert_eq!(root.child("note").unwrap().name, "note");
+        assert!(root.child("missing").is_none());
+        assert_eq!(root.child_text("title").as_deref(), Some("One"));
+        assert_eq!(root.child_texts("title"), ["One", "Two"]);
+    }
+
+    #[test]
+    fn serializes_with_inherited_namespaces() {
+        let xml = r#"<mods xmlns="urn:mods" xmlns:xlink="urn:xlink"><note xlink:href="a&amp;b">x &lt; y</note><empty/></mods>"#;
+        let root = Element::parse(xml).unwrap();
+        assert_eq!(
+            root.namespace_declarations().collect::<Vec<_>>(),
+            vec![("", "urn:mods"), ("xlink", "urn:xlink")]
+        );
+
+        let inherited = root.scope(&[("xlink".to_string(), "urn:other".to_string())]);
+        assert_eq!(
+            inherited,
+            [
+                ("".to_string(), "urn:mods".to_string()),
+                ("xlink".to_string(), "urn:xlink".to_string())
+            ]
+        );
+        let note = root.elements().next().unwrap();
+        assert_eq!(
+            note.to_xml_in_scope(&inherited),
+            r#"<note xmlns="urn:mods" xmlns:xlink="urn:xlink" xlink:href="a&amp;b">x &lt; y</note>"#
+        );
+
+        // Round trip
+        assert_eq!(Element::parse(&root.to_xml_in_scope(&[])).unwrap(), root);
+    }
+
+    #[test]
+    fn rejects_malformed_xml() {
+        assert!(Element::parse("<a><b></a>").is_err());
+        assert!(Element::parse("").is_err());
+    }
+}
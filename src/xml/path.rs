@@ -0,0 +1,497 @@
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+use crate::xml::Element;
+
+/// Prefix bindings used to resolve names in a [`Path`]
+///
+/// Prefixes in a query are independent of those used in the document, so
+/// `dc:title` matches a Dublin Core title however the record declares it.
+#[derive(Clone, Debug, Default)]
+pub struct Namespaces {
+    bindings: HashMap<String, String>,
+}
+
+impl Namespaces {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bindings for the formats common in OAI-PMH
+    ///
+    /// `oai`, `oai_dc`, `dc`, `dcterms`, `marc`, `mods`, `ead`, `ead3`, `datacite`,
+    /// `oai_datacite`, `xlink`, `xsi` and `xml`.
+    pub fn common() -> Self {
+        Self::new()
+            .bind("oai", "http://www.openarchives.org/OAI/2.0/")
+            .bind("oai_dc", "http://www.openarchives.org/OAI/2.0/oai_dc/")
+            .bind("dc", "http://purl.org/dc/elements/1.1/")
+            .bind("dcterms", "http://purl.org/dc/terms/")
+            .bind("marc", "http://www.loc.gov/MARC21/slim")
+            .bind("mods", "http://www.loc.gov/mods/v3")
+            .bind("ead", "urn:isbn:1-931666-22-9")
+            .bind("ead3", "http://ead3.archivists.org/schema/")
+            .bind("datacite", "http://datacite.org/schema/kernel-4")
+            .bind("oai_datacite", "http://schema.datacite.org/oai/oai-1.1/")
+            .bind("xlink", "http://www.w3.org/1999/xlink")
+            .bind("xsi", "http://www.w3.org/2001/XMLSchema-instance")
+            .bind("xml", "http://www.w3.org/XML/1998/namespace")
+    }
+
+    /// Bind `prefix` to `uri`, replacing any existing binding
+    pub fn bind(mut self, prefix: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.bindings.insert(prefix.into(), uri.into());
+        self
+    }
+
+    fn resolve(&self, prefix: &str) -> Result<&str> {
+        self.bindings
+            .get(prefix)
+            .map(|uri| uri.as_str())
+            .ok_or_else(|| Error::InvalidPath(format!("unbound namespace prefix {prefix}")))
+    }
+}
+
+/// A compiled path query
+///
+/// The syntax is a small subset of XPath:
+///
+/// - `a/b` selects `b` children of `a` children of the context element
+/// - `/a/b` starts at the context element itself, which must match `a`
+/// - `//b` selects `b` anywhere in the tree (including the context element),
+///   and `a//b` selects `b` anywhere below `a`
+/// - `*` matches any element and `prefix:*` any element in a namespace
+/// - `[@attr]`, `[@attr='value']` and `[n]` filter a step; positions are 1-based
+///   and count matching siblings, so `//b[1]` is the first `b` of each parent
+/// - a final `@attr` step selects attribute values
+///
+/// A prefixed name matches by namespace URI, bound through [`Namespaces`]. An
+/// unprefixed name matches the local name in any namespace.
+///
+/// # Example
+/// ```
+/// use oai_pmh::xml::{Element, Namespaces, Path};
+///
+/// let xml = r#"<mods xmlns="http://www.loc.gov/mods/v3" xmlns:xlink="http://www.w3.org/1999/xlink">
+///   <titleInfo><title>Harbor at dusk</title></titleInfo>
+///   <titleInfo type="alternative"><title>Gloucester harbor</title></titleInfo>
+///   <accessCondition xlink:href="http://rightsstatements.org/vocab/NoC-US/1.0/"/>
+/// </mods>"#;
+///
+/// let tree = Element::parse(xml).unwrap();
+/// let ns = Namespaces::common();
+///
+/// let path = Path::parse("mods:titleInfo[@type='alternative']/mods:title", &ns).unwrap();
+/// assert_eq!(path.values(&tree), vec!["Gloucester harbor"]);
+///
+/// let license = tree.value("mods:accessCondition/@xlink:href", &ns).unwrap();
+/// assert_eq!(license.as_deref(), Some("http://rightsstatements.org/vocab/NoC-US/1.0/"));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    absolute: bool,
+    steps: Vec<Step>,
+    attribute: Option<Name>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Step {
+    descendant: bool,
+    name: Name,
+    predicates: Vec<Predicate>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Name {
+    /// `None` matches any namespace
+    namespace: Option<String>,
+    /// `None` matches any local name
+    local: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Predicate {
+    Position(usize),
+    Attribute { name: Name, value: Option<String> },
+}
+
+/// Where a step starts: above the root, or at an element
+#[derive(Clone, Copy)]
+enum Context<'a> {
+    Document(&'a Element),
+    Element(&'a Element),
+}
+
+impl Path {
+    pub fn parse(path: &str, namespaces: &Namespaces) -> Result<Self> {
+        let invalid = |msg: &str| Error::InvalidPath(format!("{msg} in {path:?}"));
+
+        let mut rest = path.trim();
+        let absolute = rest.starts_with('/');
+        let mut descendant = false;
+        if let Some(r) = rest.strip_prefix("//") {
+            descendant = true;
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix('/') {
+            rest = r;
+        }
+
+        let mut steps = Vec::new();
+        let mut attribute = None;
+        loop {
+            let end = step_end(rest).ok_or_else(|| invalid("unclosed predicate"))?;
+            let (step, remainder) = rest.split_at(end);
+            if step.is_empty() {
+                return Err(invalid("empty step"));
+            }
+            if attribute.is_some() {
+                return Err(invalid("attribute must be the last step"));
+            }
+
+            if let Some(name) = step.strip_prefix('@') {
+                if descendant {
+                    return Err(invalid("attribute after //"));
+                }
+                attribute = Some(Name::parse(name, namespaces, &invalid)?);
+            } else {
+                steps.push(Step::parse(step, descendant, namespaces, &invalid)?);
+            }
+
+            if let Some(r) = remainder.strip_prefix("//") {
+                descendant = true;
+                rest = r;
+            } else if let Some(r) = remainder.strip_prefix('/') {
+                descendant = false;
+                rest = r;
+            } else {
+                break;
+            }
+        }
+
+        if steps.is_empty() && absolute {
+            return Err(invalid("no element step"));
+        }
+
+        Ok(Self {
+            absolute,
+            steps,
+            attribute,
+        })
+    }
+
+    /// Elements selected by the path, in document order per step
+    ///
+    /// A trailing `@attr` step keeps the elements that have the attribute.
+    pub fn select<'a>(&self, element: &'a Element) -> Vec<&'a Element> {
+        let mut contexts = vec![if self.absolute {
+            Context::Document(element)
+        } else {
+            Context::Element(element)
+        }];
+
+        for step in &self.steps {
+            let mut selected: Vec<&Element> = Vec::new();
+            for context in contexts {
+                for e in step.apply(context) {
+                    if !selected.iter().any(|s| std::ptr::eq(*s, e)) {
+                        selected.push(e);
+                    }
+                }
+            }
+            contexts = selected.into_iter().map(Context::Element).collect();
+        }
+
+        contexts
+            .into_iter()
+            .filter_map(|context| match context {
+                Context::Element(e) => Some(e),
+                Context::Document(_) => None,
+            })
+            .filter(|e| match &self.attribute {
+                Some(name) => name.attribute(e).is_some(),
+                None => true,
+            })
+            .collect()
+    }
+
+    /// Trimmed text of selected elements, or the values of a trailing `@attr` step
+    pub fn values(&self, element: &Element) -> Vec<String> {
+        self.select(element)
+            .into_iter()
+            .filter_map(|e| match &self.attribute {
+                Some(name) => name.attribute(e).map(|v| v.to_string()),
+                None => Some(e.text().trim().to_string()),
+            })
+            .collect()
+    }
+}
+
+impl Step {
+    fn parse(
+        step: &str,
+        descendant: bool,
+        namespaces: &Namespaces,
+        invalid: &dyn Fn(&str) -> Error,
+    ) -> Result<Self> {
+        let (name, mut rest) = step.split_at(step.find('[').unwrap_or(step.len()));
+        let name = Name::parse(name, namespaces, invalid)?;
+
+        let mut predicates = Vec::new();
+        while let Some(r) = rest.strip_prefix('[') {
+            let end = predicate_end(r).ok_or_else(|| invalid("unclosed predicate"))?;
+            predicates.push(Predicate::parse(r[..end].trim(), namespaces, invalid)?);
+            rest = &r[end + 1..];
+        }
+        if !rest.is_empty() {
+            return Err(invalid("unexpected text after predicate"));
+        }
+
+        Ok(Self {
+            descendant,
+            name,
+            predicates,
+        })
+    }
+
+    fn apply<'a>(&self, context: Context<'a>) -> Vec<&'a Element> {
+        let mut selected = Vec::new();
+        match (context, self.descendant) {
+            (Context::Document(root), false) => selected.extend(self.filter(vec![root])),
+            (Context::Document(root), true) => {
+                selected.extend(self.filter(vec![root]));
+                self.descendants(root, &mut selected);
+            }
+            (Context::Element(e), false) => selected.extend(self.filter(e.elements().collect())),
+            (Context::Element(e), true) => self.descendants(e, &mut selected),
+        }
+        selected
+    }
+
+    // Matches below `element` in document order, with positions counted among siblings
+    fn descendants<'a>(&self, element: &'a Element, out: &mut Vec<&'a Element>) {
+        let matched = self.filter(element.elements().collect());
+        for child in element.elements() {
+            if matched.iter().any(|m| std::ptr::eq(*m, child)) {
+                out.push(child);
+            }
+            self.descendants(child, out);
+        }
+    }
+
+    // The `siblings` matching the name and predicates; `[n]` counts within the set
+    fn filter<'a>(&self, siblings: Vec<&'a Element>) -> Vec<&'a Element> {
+        let mut matched: Vec<&Element> = siblings
+            .into_iter()
+            .filter(|e| self.name.element(e))
+            .collect();
+        for predicate in &self.predicates {
+            matched = match predicate {
+                Predicate::Position(n) => matched.get(n - 1).into_iter().copied().collect(),
+                Predicate::Attribute { name, value } => matched
+                    .into_iter()
+                    .filter(|e| match (name.attribute(e), value) {
+                        (Some(actual), Some(expected)) => actual == expected,
+                        (found, None) => found.is_some(),
+                        (None, _) => false,
+                    })
+                    .collect(),
+            };
+        }
+        matched
+    }
+}
+
+impl Name {
+    fn parse(name: &str, namespaces: &Namespaces, invalid: &dyn Fn(&str) -> Error) -> Result<Self> {
+        let (namespace, local) = match name.split_once(':') {
+            Some((prefix, local)) => (Some(namespaces.resolve(prefix)?.to_string()), local),
+            None => (None, name),
+        };
+
+        let valid = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '.');
+        let local = match local {
+            "*" => None,
+            l if !l.is_empty() && l.chars().all(valid) => Some(l.to_string()),
+            _ => return Err(invalid(&format!("invalid name {name:?}"))),
+        };
+
+        Ok(Self { namespace, local })
+    }
+
+    fn element(&self, element: &Element) -> bool {
+        self.matches(element.namespace.as_deref(), element.local_name())
+    }
+
+    fn attribute<'a>(&self, element: &'a Element) -> Option<&'a str> {
+        element
+            .attributes
+            .iter()
+            .find(|a| {
+                let local = a.name.rsplit(':').next().unwrap_or_default();
+                match &self.namespace {
+                    // Unprefixed attribute names only match unprefixed attributes
+                    None => a.namespace.is_none() && self.matches(None, local),
+                    Some(_) => self.matches(a.namespace.as_deref(), local),
+                }
+            })
+            .map(|a| a.value.as_str())
+    }
+
+    fn matches(&self, namespace: Option<&str>, local: &str) -> bool {
+        let namespace_matches = match &self.namespace {
+            Some(expected) => namespace == Some(expected.as_str()),
+            None => true,
+        };
+        let local_matches = match &self.local {
+            Some(expected) => local == expected,
+            None => true,
+        };
+        namespace_matches && local_matches
+    }
+}
+
+impl Predicate {
+    fn parse(
+        predicate: &str,
+        namespaces: &Namespaces,
+        invalid: &dyn Fn(&str) -> Error,
+    ) -> Result<Self> {
+        if let Ok(n) = predicate.parse::<usize>() {
+            return match n {
+                0 => Err(invalid("positions start at 1")),
+                n => Ok(Predicate::Position(n)),
+            };
+        }
+
+        let attribute = predicate
+            .strip_prefix('@')
+            .ok_or_else(|| invalid("unsupported predicate"))?;
+        let (name, value) = match attribute.split_once('=') {
+            Some((name, value)) => {
+                let value = value.trim();
+                let unquoted = value
+                    .strip_prefix('\'')
+                    .and_then(|v| v.strip_suffix('\''))
+                    .or_else(|| value.strip_prefix('"').and_then(|v| v.strip_suffix('"')))
+                    .ok_or_else(|| invalid("predicate value must be quoted"))?;
+                (name.trim(), Some(unquoted.to_string()))
+            }
+            None => (attribute, None),
+        };
+
+        Ok(Predicate::Attribute {
+            name: Name::parse(name, namespaces, invalid)?,
+            value,
+        })
+    }
+}
+
+/// Byte offset of the `]` closing a predicate, skipping quoted values
+fn predicate_end(predicate: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in predicate.char_indices() {
+        match (c, quote) {
+            ('\'' | '"', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            (']', None) => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Byte offset of the next `/` outside a predicate, or the end
+fn step_end(path: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut depth = 0;
+    for (i, c) in path.char_indices() {
+        match (c, quote) {
+            ('\'' | '"', None) if depth > 0 => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            (_, Some(_)) => {}
+            ('[', None) => depth += 1,
+            (']', None) => depth -= 1,
+            ('/', None) if depth == 0 => return Some(i),
+            _ => {}
+        }
+    }
+    (depth == 0 && quote.is_none()).then_some(path.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"<root xmlns="urn:a" xmlns:b="urn:b" xmlns:xlink="http://www.w3.org/1999/xlink">
+        <item id="1"><b:name>one</b:name><b:name>uno</b:name></item>
+        <item id="2" xlink:href="http://example.org/2"><b:name>two</b:name>
+            <item id="3" label="[draft]"><b:name> three </b:name></item>
+        </item>
+        <other><b:name>four</b:name></other>
+    </root>"#;
+
+    fn ns() -> Namespaces {
+        Namespaces::common().bind("a", "urn:a").bind("x", "urn:b")
+    }
+
+    fn values(path: &str) -> Vec<String> {
+        Element::parse(XML).unwrap().values(path, &ns()).unwrap()
+    }
+
+    #[test]
+    fn selects_children_and_descendants() {
+        assert_eq!(values("a:item/x:name"), vec!["one", "uno", "two"]);
+        assert_eq!(
+            values("//x:name"),
+            vec!["one", "uno", "two", "three", "four"]
+        );
+        assert_eq!(values("a:item//x:name"), vec!["one", "uno", "two", "three"]);
+        assert_eq!(values("/a:root/a:other/x:name"), vec!["four"]);
+        assert_eq!(values("*/x:name").len(), 4);
+        assert_eq!(values("item/name").len(), 3);
+        assert!(values("/a:other").is_empty());
+    }
+
+    #[test]
+    fn matches_by_namespace_uri_not_prefix() {
+        // The document uses b: for urn:b; the query binds it to x:
+        assert_eq!(values("a:item[1]/x:*"), vec!["one", "uno"]);
+        assert!(values("a:item/a:name").is_empty());
+    }
+
+    #[test]
+    fn filters_with_predicates() {
+        assert_eq!(values("a:item[@id='2']/x:name"), vec!["two"]);
+        assert_eq!(values("a:item[@id=\"1\"]/x:name[2]"), vec!["uno"]);
+        assert_eq!(values("//a:item[@xlink:href]/@id"), vec!["2"]);
+        assert_eq!(values("a:item/x:name[1]"), vec!["one", "two"]);
+        assert_eq!(values("//a:item[1]/@id"), vec!["1", "3"]);
+        assert_eq!(values("//a:item[@id][2]/@id"), vec!["2"]);
+        assert!(values("//a:item[3]").is_empty());
+        assert_eq!(values("//a:item[@label='[draft]']/@id"), vec!["3"]);
+        assert!(values("//a:item[@label=\"a]b\"]/@id").is_empty());
+        assert_eq!(values("a:item/@xlink:href"), vec!["http://example.org/2"]);
+        assert!(values("a:item/@href").is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_paths() {
+        let ns = ns();
+        for path in [
+            "",
+            "a:item//",
+            "a:item/@id/x:name",
+            "a:item[@id='1'",
+            "a:item[0]",
+            "a:item[@id=1]",
+            "a:item[text()]",
+            "unbound:item",
+            "a:item//@id",
+        ] {
+            assert!(
+                matches!(Path::parse(path, &ns), Err(Error::InvalidPath(_))),
+                "{path}"
+            );
+        }
+    }
+}
@@ -0,0 +1,238 @@
+#![cfg(all(feature = "axum", feature = "reqwest"))]
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::time::Duration;
+
+    use axum::Router;
+    use oai_pmh::client::response::{ErrorCode, Header, Identify, MetadataFormat, Record, Set};
+    use oai_pmh::provider::axum::{Endpoint, router};
+    use oai_pmh::provider::{Page, Provider, ProviderError, Repository};
+    use oai_pmh::{Client, ListRecordsArgs};
+
+    // Five Dublin Core records, served two per page
+    struct TestRepository;
+
+    fn record(n: usize) -> Record {
+        Record {
+            header: Header {
+                identifier: format!("oai:example.org:{n}"),
+                datestamp: format!("2024-01-0{n}"),
+                status: None,
+                set_spec: Vec::new(),
+            },
+            metadata: format!(
+                r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Record {n}</dc:title></oai_dc:dc>"#
+            ),
+            about: String::new(),
+        }
+    }
+
+    impl Repository for TestRepository {
+        fn identify(&self) -> Identify {
+            Identify {
+                repository_name: "Axum Test".to_string(),
+                base_url: "http://localhost/oai".to_string(),
+                protocol_version: "2.0".to_string(),
+                admin_email: vec!["admin@example.org".to_string()],
+                earliest_datestamp: "2024-01-01".to_string(),
+                deleted_record: "no".to_string(),
+                granularity: "YYYY-MM-DD".to_string(),
+                compression: vec!["gzip".to_string()],
+                description: Vec::new(),
+            }
+        }
+
+        fn metadata_formats(&self, _: Option<&str>) -> Result<Vec<MetadataFormat>, ProviderError> {
+            Ok(vec![MetadataFormat {
+                metadata_prefix: "oai_dc".to_string(),
+                metadata_namespace: "http://www.openarchives.org/OAI/2.0/oai_dc/".to_string(),
+                schema: "http://www.openarchives.org/OAI/2.0/oai_dc.xsd".to_string(),
+            }])
+        }
+
+        fn sets(&self) -> Result<Vec<Set>, ProviderError> {
+            Ok(Vec::new())
+        }
+
+        fn get_record(&self, identifier: &str, _: &str) -> Result<Record, ProviderError> {
+            if identifier == "oai:example.org:broken" {
+                return Err(ProviderError::internal("storage unavailable"));
+            }
+            (1..=5)
+                .map(record)
+                .find(|r| r.header.identifier == identifier)
+                .ok_or_else(|| ErrorCode::IdDoesNotExist.into())
+        }
+
+        fn list_records(
+            &self,
+            _: &ListRecordsArgs,
+            cursor: Option<&str>,
+            limit: usize,
+        ) -> Result<Page<Record>, ProviderError> {
+            let start: usize = cursor
+                .map_or(Ok(0), str::parse)
+                .map_err(|_| ErrorCode::BadResumptionToken)?;
+            let end = (start + limit).min(5);
+            let items = (start + 1..=end).map(record).collect();
+            let page = if end < 5 {
+                Page::new(items, end.to_string())
+            } else {
+                Page::last(items)
+            };
+            Ok(page.complete_list_size(5))
+        }
+    }
+
+    fn provider() -> Provider<TestRepository> {
+        Provider::new(TestRepository).page_size(2)
+    }
+
+    // Serve `router` under /oai on an ephemeral port, returning the endpoint URL
+    async fn serve(router: Router) -> String {
+        let app = Router::new().nest("/oai", router);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{address}/oai")
+    }
+
+    #[tokio::test]
+    async fn test_client_identify() {
+        let url = serve(router(provider())).await;
+        let client = Client::new(&url).unwrap();
+
+        let identify = client.identify().await.unwrap().payload.unwrap();
+        assert_eq!(identify.repository_name, "Axum Test");
+    }
+
+    #[cfg(feature = "formats")]
+    #[tokio::test]
+    async fn test_client_harvest_follows_resumption_tokens() {
+        let url = serve(router(provider())).await;
+        let client = Client::new(&url).unwrap();
+
+        let mut records = client
+            .records(ListRecordsArgs::new("oai_dc"))
+            .await
+            .unwrap();
+        let mut titles = Vec::new();
+        while let Some(record) = records.next().await {
+            let dc = record.unwrap().parse_dc().unwrap();
+            titles.push(dc.title[0].value.clone());
+        }
+
+        assert_eq!(
+            titles,
+            vec!["Record 1", "Record 2", "Record 3", "Record 4", "Record 5"]
+        );
+    }
+
+    #[cfg(feature = "formats")]
+    #[tokio::test]
+    async fn test_client_get_record_error() {
+        let url = serve(router(provider())).await;
+        let client = Client::new(&url).unwrap();
+
+        let error = client
+            .get_record_as::<oai_pmh::formats::oai_dc::DublinCore>("oai:example.org:9")
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&error, oai_pmh::Error::Oai(e) if e.code == ErrorCode::IdDoesNotExist),
+            "{error:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_form() {
+        let url = serve(router(provider())).await;
+
+        let response = reqwest::Client::new()
+            .post(&url)
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body("verb=GetRecord&identifier=oai%3Aexample.org%3A3&metadataPrefix=oai_dc")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/xml; charset=utf-8"
+        );
+        let body = response.text().await.unwrap();
+        assert!(body.contains("<dc:title>Record 3</dc:title>"));
+    }
+
+    #[tokio::test]
+    async fn test_post_requires_form_content_type() {
+        let url = serve(router(provider())).await;
+        let post = |content_type: Option<&'static str>| {
+            let mut request = reqwest::Client::new().post(&url).body("verb=Identify");
+            if let Some(content_type) = content_type {
+                request = request.header("content-type", content_type);
+            }
+            request.send()
+        };
+
+        for content_type in [Some("text/plain"), Some("application/json"), None] {
+            let response = post(content_type).await.unwrap();
+            assert_eq!(response.status(), 415, "{content_type:?}");
+        }
+
+        let response = post(Some("application/x-www-form-urlencoded; charset=UTF-8"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert!(response.text().await.unwrap().contains("<Identify>"));
+    }
+
+    #[tokio::test]
+    async fn test_internal_error() {
+        let url = serve(router(provider())).await;
+
+        let response = reqwest::get(format!(
+            "{url}?verb=GetRecord&identifier=oai:example.org:broken&metadataPrefix=oai_dc"
+        ))
+        .await
+        .unwrap();
+        assert_eq!(response.status(), 500);
+    }
+
+    #[tokio::test]
+    async fn test_gzip() {
+        let url = serve(router(provider())).await;
+
+        let response = reqwest::Client::new()
+            .get(format!("{url}?verb=Identify"))
+            .header("accept-encoding", "gzip")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["content-encoding"], "gzip");
+
+        let compressed = response.bytes().await.unwrap();
+        let mut xml = String::new();
+        flate2::read::GzDecoder::new(&compressed[..])
+            .read_to_string(&mut xml)
+            .unwrap();
+        assert!(xml.contains("<repositoryName>Axum Test</repositoryName>"));
+
+        // Uncompressed unless asked for
+        let response = reqwest::get(format!("{url}?verb=Identify")).await.unwrap();
+        assert!(response.headers().get("content-encoding").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_overloaded() {
+        let endpoint = Endpoint::new(provider()).overloaded(|| Some(Duration::from_secs(120)));
+        let url = serve(endpoint.router()).await;
+
+        let response = reqwest::get(format!("{url}?verb=Identify")).await.unwrap();
+        assert_eq!(response.status(), 503);
+        assert_eq!(response.headers()["retry-after"], "120");
+    }
+}
@@ -0,0 +1,265 @@
+#![cfg(all(feature = "blocking", feature = "reqwest"))]
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, ServerGuard};
+    use oai_pmh::blocking::Client;
+    use oai_pmh::client::query::{
+        GetRecordArgs, ListIdentifiersArgs, ListMetadataFormatsArgs, ListRecordsArgs,
+    };
+    #[cfg(feature = "formats")]
+    use oai_pmh::formats::marcxml::MarcRecord;
+
+    fn setup_mock_server(
+        server: &mut ServerGuard,
+        fixture: &str,
+        query_matchers: Vec<Matcher>,
+    ) -> mockito::Mock {
+        let xml = std::fs::read_to_string(fixture).expect("Failed to load fixture");
+
+        server
+            .mock("GET", "/")
+            .match_query(Matcher::AllOf(query_matchers))
+            .with_status(200)
+            .with_header("content-type", "text/xml")
+            .with_body(xml)
+            .create()
+    }
+
+    #[test]
+    fn test_get_record() {
+        let identifier = "oai:archivesspace:/repositories/2/resources/2";
+        let metadata_prefix = "oai_ead";
+
+        let mut server = mockito::Server::new();
+
+        let mock = setup_mock_server(
+            &mut server,
+            "tests/fixtures/get_record.xml",
+            vec![
+                Matcher::UrlEncoded("verb".into(), "GetRecord".into()),
+                Matcher::UrlEncoded("identifier".into(), identifier.into()),
+                Matcher::UrlEncoded("metadataPrefix".into(), metadata_prefix.into()),
+            ],
+        );
+
+        let args = GetRecordArgs::new(identifier, metadata_prefix);
+        let client = Client::new(&server.url()).unwrap();
+        let response = client.get_record(args).unwrap();
+        let record = response.payload.unwrap().record;
+        assert_eq!(record.header.identifier, identifier);
+        assert!(record.metadata.contains("<ead"));
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_identify() {
+        let mut server = mockito::Server::new();
+
+        let mock = setup_mock_server(
+            &mut server,
+            "tests/fixtures/identify.xml",
+            vec![Matcher::UrlEncoded("verb".into(), "Identify".into())],
+        );
+
+        let client = Client::new(&server.url()).unwrap();
+        let response = client.identify().unwrap();
+        assert_eq!(
+            response.payload.unwrap().repository_name,
+            "ArchivesSpace OAI Provider"
+        );
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_list_identifiers_follows_resumption_token() {
+        let fixture = "tests/fixtures/list_identifiers.xml";
+        let xml = std::fs::read_to_string(fixture).expect("Failed to load fixture");
+        let token = "eyJtZXRhZGF0YV9wcmVmaXgiOiJvYWlfZWFkIiwiZnJvbSI6IjE5NzAtMDEtMDEgMDA6MDA6MDAgVVRDIiwidW50aWwiOiIyMDI1LTExLTI2IDIxOjU4OjE1IFVUQyIsInN0YXRlIjoicHJvZHVjaW5nX3JlY29yZHMiLCJsYXN0X2RlbGV0ZV9pZCI6MCwicmVtYWluaW5nX3R5cGVzIjp7IlJlc291cmNlIjoyfSwiaXNzdWVfdGltZSI6MTc2NDE5NDI5NjA2Mn0=";
+
+        let mut server = mockito::Server::new();
+
+        let first = setup_mock_server(
+            &mut server,
+            fixture,
+            vec![
+                Matcher::UrlEncoded("verb".into(), "ListIdentifiers".into()),
+                Matcher::UrlEncoded("metadataPrefix".into(), "oai_ead".into()),
+            ],
+        );
+
+        // Final page: same headers, empty resumption token
+        let last = server
+            .mock("GET", "/")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("verb".into(), "ListIdentifiers".into()),
+                Matcher::UrlEncoded("resumptionToken".into(), token.into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "text/xml")
+            .with_body(xml.replace(token, ""))
+            .create();
+
+        let client = Client::new(&server.url()).unwrap();
+        let responses: Vec<_> = client
+            .list_identifiers(ListIdentifiersArgs::new("oai_ead"))
+            .unwrap()
+            .collect::<oai_pmh::Result<_>>()
+            .unwrap();
+
+        assert_eq!(responses.len(), 2);
+        for response in responses {
+            let headers = response.payload.unwrap().header;
+            assert_eq!(
+                headers[0].identifier,
+                "oai:archivesspace:/repositories/2/resources/2"
+            );
+        }
+
+        first.assert();
+        last.assert();
+    }
+
+    #[test]
+    fn test_list_metadata_formats() {
+        let mut server = mockito::Server::new();
+
+        let mock = setup_mock_server(
+            &mut server,
+            "tests/fixtures/list_metadata_formats.xml",
+            vec![Matcher::UrlEncoded(
+                "verb".into(),
+                "ListMetadataFormats".into(),
+            )],
+        );
+
+        let client = Client::new(&server.url()).unwrap();
+        let response = client
+            .list_metadata_formats(None::<ListMetadataFormatsArgs>)
+            .unwrap();
+        let metadata_formats = response.payload.unwrap().metadata_format;
+        assert_eq!(metadata_formats[0].metadata_prefix, "oai_dc");
+        assert_eq!(metadata_formats[1].metadata_prefix, "oai_ead");
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_list_records() {
+        let mut server = mockito::Server::new();
+
+        let mock = setup_mock_server(
+            &mut server,
+            "tests/fixtures/list_records.xml",
+            vec![
+                Matcher::UrlEncoded("verb".into(), "ListRecords".into()),
+                Matcher::UrlEncoded("metadataPrefix".into(), "oai_dc".into()),
+            ],
+        );
+
+        let client = Client::new(&server.url()).unwrap();
+        let mut iter = client.list_records(ListRecordsArgs::new("oai_dc")).unwrap();
+
+        let response = iter.next().unwrap().unwrap();
+        let records = response.payload.unwrap().record;
+        assert_eq!(
+            records[0].header.identifier,
+            "oai:archivesspace:/repositories/2/archival_objects/1"
+        );
+        assert!(
+            records[0]
+                .metadata
+                .contains("Correspondence about art, 1974–2014")
+        );
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_list_sets() {
+        let mut server = mockito::Server::new();
+
+        let mock = setup_mock_server(
+            &mut server,
+            "tests/fixtures/list_sets.xml",
+            vec![Matcher::UrlEncoded("verb".into(), "ListSets".into())],
+        );
+
+        let client = Client::new(&server.url()).unwrap();
+        let mut iter = client.list_sets().unwrap();
+
+        let response = iter.next().unwrap().unwrap();
+        let sets = response.payload.unwrap().set;
+        assert_eq!(sets[0].set_spec, "class");
+
+        // No resumption token in the fixture, so the iterator is exhausted
+        assert!(iter.next().is_none());
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_records() {
+        let mut server = mockito::Server::new();
+
+        let mock = setup_mock_server(
+            &mut server,
+            "tests/fixtures/list_records_marc21.xml",
+            vec![
+                Matcher::UrlEncoded("verb".into(), "ListRecords".into()),
+                Matcher::UrlEncoded("metadataPrefix".into(), "marc21".into()),
+            ],
+        );
+
+        let client = Client::new(&server.url()).unwrap();
+        let identifiers: Vec<String> = client
+            .records(ListRecordsArgs::new("marc21"))
+            .unwrap()
+            .map(|record| record.unwrap().header.identifier)
+            .collect();
+
+        assert_eq!(
+            identifiers,
+            vec![
+                "oai:catalog.example.edu:991000123",
+                "oai:catalog.example.edu:991000456",
+                "oai:catalog.example.edu:991000789",
+            ]
+        );
+
+        mock.assert();
+    }
+
+    #[test]
+    #[cfg(feature = "formats")]
+    fn test_records_as() {
+        let mut server = mockito::Server::new();
+
+        let mock = setup_mock_server(
+            &mut server,
+            "tests/fixtures/list_records_marc21.xml",
+            vec![
+                Matcher::UrlEncoded("verb".into(), "ListRecords".into()),
+                Matcher::UrlEncoded("metadataPrefix".into(), "marc21".into()),
+            ],
+        );
+
+        let client = Client::new(&server.url()).unwrap();
+        let records: Vec<_> = client
+            .records_as::<MarcRecord>(ListRecordsArgs::new("marc21"))
+            .unwrap()
+            .map(|record| record.unwrap())
+            .collect();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            records[0].metadata.as_ref().unwrap().leader,
+            "01142cam  2200301 a 4500"
+        );
+        assert!(records[2].metadata.is_none());
+
+        mock.assert();
+    }
+}
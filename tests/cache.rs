@@ -0,0 +1,208 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use oai_pmh::client::cache::{MemoryCache, ResponseCache};
+    use oai_pmh::client::transport::{
+        BoxFuture, HttpRequest, HttpResponse, MemoryTransport, Transport,
+    };
+    use oai_pmh::{Client, ListRecordsArgs, Result, Verb};
+
+    const ENDPOINT: &str = "https://test.archivesspace.org/oai";
+
+    fn fixture(name: &str) -> String {
+        std::fs::read_to_string(format!("tests/fixtures/{name}")).expect("Failed to load fixture")
+    }
+
+    // Serves one body with an ETag, answering 304 when the client sends it back; each
+    // response has a later Last-Modified
+    struct Conditional {
+        body: String,
+        requests: Mutex<Vec<HttpRequest>>,
+    }
+
+    impl Transport for Conditional {
+        fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+            Box::pin(async move {
+                let revalidating = request
+                    .headers
+                    .iter()
+                    .any(|(k, v)| k == "If-None-Match" && v == "\"v1\"");
+                let mut requests = self.requests.lock().unwrap();
+                requests.push(request);
+                let last_modified = format!("Mon, 0{} Jan 2024 00:00:00 GMT", requests.len());
+
+                let response = if revalidating {
+                    HttpResponse::new(304, "")
+                } else {
+                    HttpResponse::new(200, self.body.clone())
+                        .with_header("Content-Type", "text/xml")
+                        .with_header("ETag", "\"v1\"")
+                };
+                Ok(response.with_header("Last-Modified", last_modified))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fresh_responses_are_served_from_cache() {
+        let transport = Arc::new(
+            MemoryTransport::new()
+                .with_response("verb=Identify", fixture("identify.xml"))
+                .with_response(
+                    "verb=ListMetadataFormats",
+                    fixture("list_metadata_formats.xml"),
+                ),
+        );
+        let cache = ResponseCache::new(MemoryCache::new(10));
+        let client = Client::with_transport(ENDPOINT, transport.clone())
+            .unwrap()
+            .with_cache(cache);
+
+        for _ in 0..3 {
+            let response = client.identify().await.unwrap();
+            assert_eq!(
+                response.payload.unwrap().repository_name,
+                "ArchivesSpace OAI Provider"
+            );
+            client.list_metadata_formats(None).await.unwrap();
+        }
+
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stale_responses_are_revalidated() {
+        let transport = Arc::new(Conditional {
+            body: fixture("identify.xml"),
+            requests: Mutex::new(Vec::new()),
+        });
+        let cache = ResponseCache::new(MemoryCache::new(10)).ttl(Verb::Identify, Duration::ZERO);
+        let client = Client::with_transport(ENDPOINT, transport.clone())
+            .unwrap()
+            .with_cache(cache);
+
+        client.identify().await.unwrap();
+        let response = client.identify().await.unwrap();
+        assert_eq!(response.payload.unwrap().protocol_version, "2.0");
+        client.identify().await.unwrap();
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(
+            !requests[0]
+                .headers
+                .iter()
+                .any(|(k, _)| k == "If-None-Match")
+        );
+        assert!(
+            requests[1]
+                .headers
+                .iter()
+                .any(|(k, v)| k == "If-None-Match" && v == "\"v1\"")
+        );
+        // The 304 refreshed Last-Modified along with the ETag
+        let if_modified_since = |request: &HttpRequest| {
+            request
+                .headers
+                .iter()
+                .find(|(k, _)| k == "If-Modified-Since")
+                .map(|(_, v)| v.clone())
+        };
+        assert_eq!(
+            if_modified_since(&requests[1]).as_deref(),
+            Some("Mon, 01 Jan 2024 00:00:00 GMT")
+        );
+        assert_eq!(
+            if_modified_since(&requests[2]).as_deref(),
+            Some("Mon, 02 Jan 2024 00:00:00 GMT")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_not_cached() {
+        let transport = Arc::new(MemoryTransport::new().with_response(
+            "verb=ListSets",
+            fixture("list_sets.xml").replace(
+                "<ListSets>",
+                r#"<error code="noSetHierarchy">No sets</error><ListSets>"#,
+            ),
+        ));
+        let client = Client::with_transport(ENDPOINT, transport.clone())
+            .unwrap()
+            .with_cache(ResponseCache::new(MemoryCache::new(10)));
+
+        for _ in 0..2 {
+            let page = client.list_sets().await.unwrap().next().await.unwrap();
+            assert!(page.unwrap().is_err());
+        }
+
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_incomplete_lists_are_not_cached() {
+        let xml = fixture("list_sets.xml").replace(
+            "</ListSets>",
+            "<resumptionToken>page2</resumptionToken></ListSets>",
+        );
+        let transport = Arc::new(
+            MemoryTransport::new()
+                .with_response("verb=ListSets", xml)
+                .with_response(
+                    "verb=ListSets&resumptionToken=page2",
+                    fixture("list_sets.xml"),
+                ),
+        );
+        let client = Client::with_transport(ENDPOINT, transport.clone())
+            .unwrap()
+            .with_cache(ResponseCache::new(MemoryCache::new(10)));
+
+        for _ in 0..2 {
+            let mut stream = client.list_sets().await.unwrap();
+            let mut pages = 0;
+            while let Some(response) = stream.next().await {
+                response.unwrap();
+                pages += 1;
+            }
+            assert_eq!(pages, 2);
+        }
+
+        // The first page's token would expire in the cache, so both harvests fetch it
+        let queries: Vec<String> = transport
+            .requests()
+            .iter()
+            .map(|r| r.query().to_string())
+            .collect();
+        assert_eq!(
+            queries,
+            vec![
+                "verb=ListSets",
+                "verb=ListSets&resumptionToken=page2",
+                "verb=ListSets",
+                "verb=ListSets&resumptionToken=page2",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_other_verbs_are_not_cached() {
+        let transport = Arc::new(MemoryTransport::new().with_response(
+            "verb=ListRecords&metadataPrefix=oai_dc",
+            fixture("list_records.xml"),
+        ));
+        let client = Client::with_transport(ENDPOINT, transport.clone())
+            .unwrap()
+            .with_cache(ResponseCache::new(MemoryCache::new(10)));
+
+        for _ in 0..2 {
+            client
+                .list_records(ListRecordsArgs::new("oai_dc"))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(transport.requests().len(), 2);
+    }
+}
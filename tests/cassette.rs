@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use oai_pmh::client::transport::{Cassette, MemoryTransport};
+    use oai_pmh::{Client, Error, ListIdentifiersArgs};
+
+    const ENDPOINT: &str = "https://test.archivesspace.org/oai";
+    const TOKEN: &str = "eyJtZXRhZGF0YV9wcmVmaXgiOiJvYWlfZWFkIiwiZnJvbSI6IjE5NzAtMDEtMDEgMDA6MDA6MDAgVVRDIiwidW50aWwiOiIyMDI1LTExLTI2IDIxOjU4OjE1IFVUQyIsInN0YXRlIjoicHJvZHVjaW5nX3JlY29yZHMiLCJsYXN0X2RlbGV0ZV9pZCI6MCwicmVtYWluaW5nX3R5cGVzIjp7IlJlc291cmNlIjoyfSwiaXNzdWVfdGltZSI6MTc2NDE5NDI5NjA2Mn0=";
+
+    fn cassette_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("oai-pmh-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    // Provider stand-in: two pages of ListIdentifiers linked by a resumption token
+    fn provider() -> MemoryTransport {
+        let xml = std::fs::read_to_string("tests/fixtures/list_identifiers.xml")
+            .expect("Failed to load fixture");
+
+        MemoryTransport::new()
+            .with_response("verb=ListIdentifiers&metadataPrefix=oai_ead", xml.clone())
+            .with_response(
+                &format!(
+                    "verb=ListIdentifiers&resumptionToken={}",
+                    TOKEN.replace('=', "%3D")
+                ),
+                xml.replace(TOKEN, ""),
+            )
+    }
+
+    async fn harvest(client: &Client) -> oai_pmh::Result<Vec<String>> {
+        let mut identifiers = Vec::new();
+        let mut stream = client
+            .list_identifiers(ListIdentifiersArgs::new("oai_ead"))
+            .await?;
+        while let Some(response) = stream.next().await {
+            for header in response?.payload.unwrap().header {
+                identifiers.push(header.identifier);
+            }
+        }
+        Ok(identifiers)
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay_resumption_chain() {
+        let dir = cassette_dir("chain");
+
+        let recorder =
+            Client::with_transport(ENDPOINT, Cassette::record(&dir, provider())).unwrap();
+        let recorded = harvest(&recorder).await.unwrap();
+        assert_eq!(recorded.len(), 2);
+
+        // One body and one metadata file per page
+        let mut files: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files.len(), 4);
+        assert!(files.iter().all(|f| f.starts_with("ListIdentifiers-")));
+
+        let player = Client::with_transport(ENDPOINT, Cassette::replay(&dir)).unwrap();
+        let replayed = harvest(&player).await.unwrap();
+        assert_eq!(recorded, replayed);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_fails_on_unrecorded_request() {
+        let dir = cassette_dir("unrecorded");
+
+        let recorder =
+            Client::with_transport(ENDPOINT, Cassette::record(&dir, provider())).unwrap();
+        harvest(&recorder).await.unwrap();
+
+        let player = Client::with_transport(ENDPOINT, Cassette::replay(&dir)).unwrap();
+        match player.identify().await {
+            Err(Error::Transport(msg)) => {
+                assert!(msg.contains("not recorded"));
+                assert!(msg.contains("GET verb=Identify"));
+            }
+            other => panic!("expected transport error, got {other:?}"),
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_preserves_status_and_headers() {
+        use oai_pmh::client::transport::{HttpRequest, HttpResponse, Transport};
+
+        let dir = cassette_dir("headers");
+        let request = HttpRequest::get(format!("{ENDPOINT}?verb=Identify"));
+        let response = HttpResponse::new(200, "<?xml version=\"1.0\"?><OAI-PMH/>")
+            .with_header("content-type", "text/xml; charset=utf-8")
+            .with_header("etag", "\"abc\"");
+
+        let recorder = Cassette::record(
+            &dir,
+            MemoryTransport::new().with_http_response("verb=Identify", response.clone()),
+        );
+        recorder.send(request.clone()).await.unwrap();
+
+        let replayed = Cassette::replay(&dir).send(request).await.unwrap();
+        assert_eq!(replayed, response);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
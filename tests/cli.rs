@@ -0,0 +1,284 @@
+#![cfg(feature = "cli")]
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::process::{Command, Output};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use oai_pmh::client::response::{Header, Identify, MetadataFormat, Record, Set};
+    use oai_pmh::provider::Provider;
+    use oai_pmh::provider::memory::MemoryRepository;
+    use serde_json::Value;
+
+    fn repository() -> MemoryRepository {
+        let identify = Identify {
+            repository_name: "Local".to_string(),
+            base_url: "https://example.org/oai".to_string(),
+            protocol_version: "2.0".to_string(),
+            admin_email: vec!["admin@example.org".to_string()],
+            earliest_datestamp: "2024-01-01".to_string(),
+            deleted_record: "persistent".to_string(),
+            granularity: "YYYY-MM-DD".to_string(),
+            compression: Vec::new(),
+            description: Vec::new(),
+        };
+        let oai_dc = MetadataFormat {
+            metadata_prefix: "oai_dc".to_string(),
+            metadata_namespace: "http://www.openarchives.org/OAI/2.0/oai_dc/".to_string(),
+            schema: "http://www.openarchives.org/OAI/2.0/oai_dc.xsd".to_string(),
+        };
+        // Five records, odd ones in the "odd" set; record 3 is deleted
+        let records = (1..=5).map(|n| Record {
+            header: Header {
+                identifier: format!("oai:example.org:{n}"),
+                datestamp: format!("2024-01-0{n}"),
+                status: (n == 3).then(|| "deleted".to_string()),
+                set_spec: if n % 2 == 1 {
+                    vec!["odd".to_string()]
+                } else {
+                    Vec::new()
+                },
+            },
+            metadata: if n == 3 {
+                String::new()
+            } else {
+                format!(
+                    r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Record {n}</dc:title></oai_dc:dc>"#
+                )
+            },
+            about: String::new(),
+        });
+        MemoryRepository::new(identify)
+            .format(oai_dc)
+            .set(Set {
+                set_spec: "odd".to_string(),
+                set_name: "Odd records".to_string(),
+                set_description: Vec::new(),
+            })
+            .records("oai_dc", records)
+    }
+
+    // Serve the repository over HTTP in pages of two, dropping the connection of request
+    // number `fail` (counting from 1) unanswered; returns the endpoint URL
+    fn serve(fail: Option<usize>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/oai", listener.local_addr().unwrap());
+        let provider = Provider::new(repository()).page_size(2);
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+
+                if Some(requests.fetch_add(1, Ordering::SeqCst) + 1) == fail {
+                    continue;
+                }
+                let target = request_line.split(' ').nth(1).unwrap_or_default();
+                let query = target.split_once('?').map_or("", |(_, q)| q);
+                let body = provider.handle_query(query).unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/xml; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        endpoint
+    }
+
+    fn oai_pmh(args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_oai-pmh"))
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    fn json_lines(output: &Output) -> Vec<Value> {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("oai-pmh-cli-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_records_jsonl() {
+        let endpoint = serve(None);
+
+        let output = oai_pmh(&["records", &endpoint, "--output", "jsonl"]);
+        assert!(output.status.success());
+        let records = json_lines(&output);
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[0]["identifier"], "oai:example.org:1");
+        assert!(
+            records[0]["metadata"]
+                .as_str()
+                .unwrap()
+                .contains("<dc:title>Record 1</dc:title>")
+        );
+        assert_eq!(records[2]["deleted"], true);
+        assert!(records[2]["metadata"].is_null());
+
+        let output = oai_pmh(&["identifiers", &endpoint, "--set", "odd", "-o", "jsonl"]);
+        let identifiers: Vec<_> = json_lines(&output)
+            .iter()
+            .map(|header| header["identifier"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            identifiers,
+            [
+                "oai:example.org:1",
+                "oai:example.org:3",
+                "oai:example.org:5"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_xml_output() {
+        let endpoint = serve(None);
+
+        let output = oai_pmh(&["get", &endpoint, "oai:example.org:2"]);
+        assert!(output.status.success());
+        let xml = String::from_utf8(output.stdout).unwrap();
+        assert!(xml.contains("<GetRecord>"));
+        assert!(xml.contains(r#"identifier="oai:example.org:2""#));
+        assert!(xml.contains("<dc:title>Record 2</dc:title>"));
+
+        // One document per page
+        let output = oai_pmh(&["records", &endpoint, "--from", "2024-01-02"]);
+        let xml = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(xml.matches("<ListRecords>").count(), 2);
+        assert_eq!(xml.matches("<record>").count(), 4);
+    }
+
+    #[test]
+    fn test_files_output() {
+        let endpoint = serve(None);
+        let dir = temp_path("files");
+
+        let output = oai_pmh(&[
+            "records",
+            &endpoint,
+            "--output",
+            "files",
+            "--dir",
+            dir.to_str().unwrap(),
+        ]);
+        assert!(output.status.success());
+        let mut files: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        // The deleted record has no file
+        assert_eq!(
+            files,
+            [
+                "oai%3Aexample.org%3A1.xml",
+                "oai%3Aexample.org%3A2.xml",
+                "oai%3Aexample.org%3A4.xml",
+                "oai%3Aexample.org%3A5.xml"
+            ]
+        );
+        let file = fs::read_to_string(dir.join("oai%3Aexample.org%3A4.xml")).unwrap();
+        assert!(file.starts_with("<?xml"));
+        assert!(file.contains("<dc:title>Record 4</dc:title>"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        let endpoint = serve(None);
+
+        let output = oai_pmh(&["records", &endpoint, "--prefix", "marc21"]);
+        assert_eq!(output.status.code(), Some(3));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("cannotDisseminateFormat"));
+
+        // An empty selective harvest is not a failure
+        let output = oai_pmh(&["records", &endpoint, "--from", "2030-01-01"]);
+        assert_eq!(output.status.code(), Some(0));
+        assert!(output.stdout.is_empty());
+
+        let output = oai_pmh(&["identify", "not a url"]);
+        assert_eq!(output.status.code(), Some(2));
+
+        let output = oai_pmh(&["identify"]);
+        assert_eq!(output.status.code(), Some(2));
+
+        let output = oai_pmh(&["identify", &serve(Some(1))]);
+        assert_eq!(output.status.code(), Some(4));
+    }
+
+    #[test]
+    fn test_checkpoint_resume() {
+        let endpoint = serve(Some(2));
+        let checkpoint = temp_path("checkpoint.json");
+        let args = [
+            "records",
+            &endpoint,
+            "-o",
+            "jsonl",
+            "--checkpoint",
+            checkpoint.to_str().unwrap(),
+        ];
+
+        // The second page fails, leaving the checkpoint after the first
+        let output = oai_pmh(&args);
+        assert_eq!(output.status.code(), Some(4));
+        assert_eq!(json_lines(&output).len(), 2);
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&checkpoint).unwrap()).unwrap();
+        assert_eq!(saved["harvested"], 2);
+
+        // A different harvest refuses the checkpoint
+        let output = oai_pmh(&[
+            "identifiers",
+            &endpoint,
+            "--checkpoint",
+            checkpoint.to_str().unwrap(),
+        ]);
+        assert_eq!(output.status.code(), Some(2));
+
+        // A corrupt checkpoint is refused without touching it
+        let saved = fs::read_to_string(&checkpoint).unwrap();
+        fs::write(&checkpoint, "{not json").unwrap();
+        let output = oai_pmh(&args);
+        assert_eq!(output.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("is unreadable"));
+        fs::write(&checkpoint, saved).unwrap();
+
+        let output = oai_pmh(&args);
+        assert!(output.status.success());
+        let identifiers: Vec<_> = json_lines(&output)
+            .iter()
+            .map(|record| record["identifier"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            identifiers,
+            [
+                "oai:example.org:3",
+                "oai:example.org:4",
+                "oai:example.org:5"
+            ]
+        );
+        assert!(!checkpoint.exists());
+    }
+}
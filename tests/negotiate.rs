@@ -0,0 +1,71 @@
+#![cfg(feature = "formats")]
+
+#[cfg(test)]
+mod tests {
+    use oai_pmh::client::response::ErrorCode;
+    use oai_pmh::client::transport::MemoryTransport;
+    use oai_pmh::formats::{datacite, marcxml, mods, oai_dc};
+    use oai_pmh::{Client, Error, ListMetadataFormatsArgs};
+
+    const ENDPOINT: &str = "https://test.archivesspace.org/oai";
+    const IDENTIFIER: &str = "oai:archivesspace:/repositories/2/resources/2";
+
+    fn fixture(name: &str) -> String {
+        std::fs::read_to_string(format!("tests/fixtures/{name}")).expect("Failed to load fixture")
+    }
+
+    fn client() -> Client {
+        let transport = MemoryTransport::new()
+            .with_response(
+                "verb=ListMetadataFormats",
+                fixture("list_metadata_formats.xml"),
+            )
+            .with_response(
+                &format!("verb=ListMetadataFormats&identifier={IDENTIFIER}"),
+                fixture("list_metadata_formats.xml").replace("oai_marc", "marcxml"),
+            )
+            .with_response(
+                "verb=ListMetadataFormats&identifier=missing",
+                fixture("err_not_found.xml"),
+            );
+        Client::with_transport(ENDPOINT, transport).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_negotiates_repository_format() {
+        let client = client();
+
+        let format = client
+            .negotiate_format(&[datacite::KERNEL_4_NAMESPACE, mods::NAMESPACE], None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(format.metadata_prefix, "oai_mods");
+
+        let format = client
+            .negotiate_format(&[datacite::KERNEL_4_NAMESPACE], None)
+            .await
+            .unwrap();
+        assert!(format.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_negotiates_item_format() {
+        let client = client();
+
+        let args = ListMetadataFormatsArgs::new(IDENTIFIER);
+        let format = client
+            .negotiate_format(&[marcxml::NAMESPACE, oai_dc::NAMESPACE], Some(args))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(format.metadata_prefix, "marcxml");
+
+        let args = ListMetadataFormatsArgs::new("missing");
+        let err = client
+            .negotiate_format(&[oai_dc::NAMESPACE], Some(args))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Oai(e) if e.code == ErrorCode::IdDoesNotExist));
+    }
+}
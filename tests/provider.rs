@@ -0,0 +1,561 @@
+#![cfg(feature = "provider")]
+
+#[cfg(test)]
+mod tests {
+    use oai_pmh::client::response::{
+        ErrorCode, GetRecordResponse, Header, Identify, IdentifyResponse, ListIdentifiersResponse,
+        ListMetadataFormatsResponse, ListRecordsResponse, ListSetsResponse, MetadataFormat, Record,
+        Set,
+    };
+    use oai_pmh::provider::datestamp::{DateRange, Datestamp};
+    use oai_pmh::provider::token::{ResumptionTokenCodec, TokenCodec, TokenState};
+    use oai_pmh::provider::{Page, Provider, ProviderError, Repository};
+    use oai_pmh::{ListRecordsArgs, Verb};
+    use std::time::Duration;
+
+    // Five records stamped 2024-01-01 to 2024-01-05; odd ones are in set "odd",
+    // and record 4 is deleted
+    struct TestRepository;
+
+    fn record(n: usize) -> Record {
+        let deleted = n == 4;
+        Record {
+            header: Header {
+                identifier: format!("oai:example.org:{n}"),
+                datestamp: format!("2024-01-0{n}"),
+                status: deleted.then(|| "deleted".to_string()),
+                set_spec: if n % 2 == 1 {
+                    vec!["odd".to_string()]
+                } else {
+                    Vec::new()
+                },
+            },
+            metadata: if deleted {
+                String::new()
+            } else {
+                format!(
+                    r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Record {n} &amp; more</dc:title></oai_dc:dc>"#
+                )
+            },
+            about: String::new(),
+        }
+    }
+
+    impl Repository for TestRepository {
+        fn identify(&self) -> Identify {
+            Identify {
+                repository_name: "Test & Co".to_string(),
+                base_url: "https://example.org/oai".to_string(),
+                protocol_version: "2.0".to_string(),
+                admin_email: vec!["admin@example.org".to_string()],
+                earliest_datestamp: "2024-01-01".to_string(),
+                deleted_record: "persistent".to_string(),
+                granularity: "YYYY-MM-DD".to_string(),
+                compression: Vec::new(),
+                description: Vec::new(),
+            }
+        }
+
+        fn metadata_formats(
+            &self,
+            identifier: Option<&str>,
+        ) -> Result<Vec<MetadataFormat>, ProviderError> {
+            if let Some(identifier) = identifier {
+                self.get_record(identifier, "oai_dc")?;
+            }
+            Ok(vec![MetadataFormat {
+                metadata_prefix: "oai_dc".to_string(),
+                metadata_namespace: "http://www.openarchives.org/OAI/2.0/oai_dc/".to_string(),
+                schema: "http://www.openarchives.org/OAI/2.0/oai_dc.xsd".to_string(),
+            }])
+        }
+
+        fn sets(&self) -> Result<Vec<Set>, ProviderError> {
+            Ok(vec![Set {
+                set_spec: "odd".to_string(),
+                set_name: "Odd records".to_string(),
+                set_description: Vec::new(),
+            }])
+        }
+
+        fn get_record(&self, identifier: &str, _: &str) -> Result<Record, ProviderError> {
+            if identifier == "oai:example.org:broken" {
+                return Err(ProviderError::internal(std::io::Error::other(
+                    "storage unavailable",
+                )));
+            }
+            (1..=5)
+                .map(record)
+                .find(|r| r.header.identifier == identifier)
+                .ok_or_else(|| ErrorCode::IdDoesNotExist.into())
+        }
+
+        fn list_records(
+            &self,
+            args: &ListRecordsArgs,
+            cursor: Option<&str>,
+            limit: usize,
+        ) -> Result<Page<Record>, ProviderError> {
+            let range = DateRange::parse(args.from_datestamp(), args.until_datestamp())
+                .ok_or(ErrorCode::BadArgument)?;
+            let matches: Vec<Record> = (1..=5)
+                .map(record)
+                .filter(|r| range.contains(Datestamp::parse(&r.header.datestamp).unwrap().0))
+                .filter(|r| {
+                    args.set_spec()
+                        .is_none_or(|s| r.header.set_spec.iter().any(|x| x == s))
+                })
+                .collect();
+
+            let start: usize = cursor
+                .map_or(Ok(0), str::parse)
+                .map_err(|_| ErrorCode::BadResumptionToken)?;
+            let end = (start + limit).min(matches.len());
+            let items = matches[start..end].to_vec();
+            let page = if end < matches.len() {
+                Page::new(items, end.to_string())
+            } else {
+                Page::last(items)
+            };
+            Ok(page.complete_list_size(matches.len()))
+        }
+    }
+
+    fn provider() -> Provider<TestRepository> {
+        Provider::new(TestRepository).page_size(2)
+    }
+
+    #[test]
+    fn test_internal_errors_are_returned_to_the_transport() {
+        let provider = provider();
+
+        let error = provider
+            .handle_query("verb=GetRecord&identifier=oai:example.org:broken&metadataPrefix=oai_dc")
+            .unwrap_err();
+        assert!(error.is_internal());
+        assert_eq!(error.code(), None);
+        assert_eq!(error.to_string(), "internal error: storage unavailable");
+        assert!(std::error::Error::source(&error).is_some());
+
+        // Checking an item's formats fails the same way
+        let error = provider
+            .handle_query("verb=ListMetadataFormats&identifier=oai:example.org:broken")
+            .unwrap_err();
+        assert!(error.is_internal());
+    }
+
+    fn error_code(xml: &str) -> ErrorCode {
+        IdentifyResponse::new(xml).unwrap().error.unwrap().code
+    }
+
+    #[test]
+    fn test_identify() {
+        let xml = provider().handle_query("verb=Identify").unwrap();
+        let response = IdentifyResponse::new(&xml).unwrap();
+
+        assert!(!response.is_err());
+        assert_eq!(response.request, "https://example.org/oai");
+        let identify = response.payload.unwrap();
+        assert_eq!(identify.repository_name, "Test & Co");
+        assert_eq!(identify.granularity, "YYYY-MM-DD");
+        assert!(Datestamp::parse(&response.response_date).is_some());
+    }
+
+    #[test]
+    fn test_get_record() {
+        let xml = provider()
+            .handle_query("verb=GetRecord&identifier=oai:example.org:1&metadataPrefix=oai_dc")
+            .unwrap();
+        assert!(xml.contains(
+            r#"<request verb="GetRecord" identifier="oai:example.org:1" metadataPrefix="oai_dc">"#
+        ));
+
+        let record = GetRecordResponse::new(&xml).unwrap().into_record().unwrap();
+        assert_eq!(record.header.identifier, "oai:example.org:1");
+        assert_eq!(record.header.set_spec, vec!["odd"]);
+        #[cfg(feature = "formats")]
+        assert_eq!(record.parse_dc().unwrap().title[0].value, "Record 1 & more");
+    }
+
+    #[test]
+    fn test_get_record_errors() {
+        let provider = provider();
+        let xml = provider
+            .handle_query("verb=GetRecord&identifier=nope&metadataPrefix=oai_dc")
+            .unwrap();
+        assert_eq!(error_code(&xml), ErrorCode::IdDoesNotExist);
+        // Valid arguments are echoed for errors other than badVerb and badArgument
+        assert!(xml.contains(r#"<request verb="GetRecord" identifier="nope""#));
+
+        let xml = provider
+            .handle_query("verb=GetRecord&identifier=oai:example.org:1&metadataPrefix=marc21")
+            .unwrap();
+        assert_eq!(error_code(&xml), ErrorCode::CannotDisseminateFormat);
+
+        let xml = provider
+            .handle_query("verb=GetRecord&identifier=oai:example.org:1")
+            .unwrap();
+        assert_eq!(error_code(&xml), ErrorCode::BadArgument);
+        assert!(xml.contains("<request>https://example.org/oai</request>"));
+    }
+
+    #[test]
+    fn test_bad_verb() {
+        let provider = provider();
+        for query in ["", "verb=Nope", "verb=Identify&verb=Identify"] {
+            let xml = provider.handle_query(query).unwrap();
+            assert_eq!(error_code(&xml), ErrorCode::BadVerb, "{query}");
+            assert!(xml.contains("<request>https://example.org/oai</request>"));
+        }
+    }
+
+    #[test]
+    fn test_bad_arguments() {
+        let provider = provider();
+        for query in [
+            "verb=Identify&metadataPrefix=oai_dc",
+            "verb=ListRecords",
+            "verb=ListRecords&metadataPrefix=oai_dc&metadataPrefix=oai_dc",
+            "verb=ListRecords&metadataPrefix=oai_dc&from=yesterday",
+            "verb=ListRecords&metadataPrefix=oai_dc&from=2024-01-01T00:00:00Z",
+            "verb=ListRecords&metadataPrefix=oai_dc&from=2024-01-05&until=2024-01-01",
+            "verb=ListRecords&metadataPrefix=oai_dc&resumptionToken=x",
+        ] {
+            let xml = provider.handle_query(query).unwrap();
+            assert_eq!(error_code(&xml), ErrorCode::BadArgument, "{query}");
+        }
+    }
+
+    #[test]
+    fn test_list_metadata_formats() {
+        let provider = provider();
+        let xml = provider.handle_query("verb=ListMetadataFormats").unwrap();
+        let formats = ListMetadataFormatsResponse::new(&xml)
+            .unwrap()
+            .payload
+            .unwrap()
+            .metadata_format;
+        assert_eq!(formats[0].metadata_prefix, "oai_dc");
+
+        let xml = provider
+            .handle_query("verb=ListMetadataFormats&identifier=nope")
+            .unwrap();
+        assert_eq!(error_code(&xml), ErrorCode::IdDoesNotExist);
+    }
+
+    #[test]
+    fn test_list_sets() {
+        let xml = provider().handle_query("verb=ListSets").unwrap();
+        let sets = ListSetsResponse::new(&xml).unwrap().payload.unwrap().set;
+        assert_eq!(sets[0].set_spec, "odd");
+        assert_eq!(sets[0].set_name, "Odd records");
+    }
+
+    #[test]
+    fn test_list_records_pages() {
+        let provider = provider();
+        let mut query = "verb=ListRecords&metadataPrefix=oai_dc".to_string();
+        let mut identifiers = Vec::new();
+        let mut cursors = Vec::new();
+
+        loop {
+            let xml = provider.handle_query(&query).unwrap();
+            let list = ListRecordsResponse::new(&xml).unwrap().payload.unwrap();
+            for record in &list.record {
+                assert_eq!(record.is_deleted(), record.metadata.is_empty());
+                identifiers.push(record.header.identifier.clone());
+            }
+
+            let token = list.resumption_token.unwrap();
+            assert_eq!(token.complete_list_size, Some(5));
+            cursors.push(token.cursor.unwrap());
+            if token.token.is_empty() {
+                break;
+            }
+            query = url::form_urlencoded::Serializer::new(String::new())
+                .append_pair("verb", "ListRecords")
+                .append_pair("resumptionToken", &token.token)
+                .finish();
+        }
+
+        assert_eq!(identifiers.len(), 5);
+        assert_eq!(identifiers[4], "oai:example.org:5");
+        assert_eq!(cursors, vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn test_list_identifiers_selective() {
+        let provider = provider();
+        let xml = provider.handle_query(
+            "verb=ListIdentifiers&metadataPrefix=oai_dc&set=odd&from=2024-01-02&until=2024-01-05",
+        ).unwrap();
+        let list = ListIdentifiersResponse::new(&xml).unwrap().payload.unwrap();
+        let identifiers: Vec<_> = list.header.iter().map(|h| h.identifier.as_str()).collect();
+
+        // A complete list in one page has no resumptionToken
+        assert_eq!(identifiers, vec!["oai:example.org:3", "oai:example.org:5"]);
+        assert!(list.resumption_token.is_none());
+
+        let xml = provider
+            .handle_query("verb=ListIdentifiers&metadataPrefix=oai_dc&from=2025-01-01")
+            .unwrap();
+        assert_eq!(error_code(&xml), ErrorCode::NoRecordsMatch);
+    }
+
+    #[test]
+    fn test_bad_resumption_token() {
+        let provider = provider();
+        let xml = provider
+            .handle_query("verb=ListRecords&resumptionToken=garbage")
+            .unwrap();
+        assert_eq!(error_code(&xml), ErrorCode::BadResumptionToken);
+
+        // A token issued for ListRecords is not valid for ListIdentifiers
+        let xml = provider
+            .handle_query("verb=ListRecords&metadataPrefix=oai_dc")
+            .unwrap();
+        let token = ListRecordsResponse::new(&xml)
+            .unwrap()
+            .payload
+            .unwrap()
+            .resumption_token
+            .unwrap()
+            .token;
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("verb", "ListIdentifiers")
+            .append_pair("resumptionToken", &token)
+            .finish();
+        assert_eq!(
+            error_code(&provider.handle_query(&query).unwrap()),
+            ErrorCode::BadResumptionToken
+        );
+    }
+
+    fn resume_query(verb: &str, token: &str) -> String {
+        url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("verb", verb)
+            .append_pair("resumptionToken", token)
+            .finish()
+    }
+
+    #[test]
+    fn test_signed_expiring_tokens() {
+        let signed = provider().token_codec(
+            TokenCodec::new()
+                .secret("s3cret")
+                .ttl(Duration::from_secs(600)),
+        );
+
+        let xml = signed
+            .handle_query("verb=ListIdentifiers&metadataPrefix=oai_dc")
+            .unwrap();
+        let token = ListIdentifiersResponse::new(&xml)
+            .unwrap()
+            .payload
+            .unwrap()
+            .resumption_token
+            .unwrap();
+        let (expires, _) = Datestamp::parse(token.expiration_date.as_deref().unwrap()).unwrap();
+        assert!(expires > Datestamp::now());
+
+        let xml = signed
+            .handle_query(&resume_query("ListIdentifiers", &token.token))
+            .unwrap();
+        let list = ListIdentifiersResponse::new(&xml).unwrap().payload.unwrap();
+        assert_eq!(list.header[0].identifier, "oai:example.org:3");
+        assert_eq!(list.resumption_token.unwrap().cursor, Some(2));
+
+        // Tokens are signed, so editing one invalidates it
+        let tampered = format!("A{}", token.token);
+        let xml = signed
+            .handle_query(&resume_query("ListIdentifiers", &tampered))
+            .unwrap();
+        assert_eq!(error_code(&xml), ErrorCode::BadResumptionToken);
+        assert!(xml.contains("resumptionToken="));
+
+        // A token from an unsigned provider is not accepted either
+        let xml = provider()
+            .handle_query("verb=ListIdentifiers&metadataPrefix=oai_dc")
+            .unwrap();
+        let unsigned = ListIdentifiersResponse::new(&xml)
+            .unwrap()
+            .payload
+            .unwrap()
+            .resumption_token
+            .unwrap()
+            .token;
+        let xml = signed
+            .handle_query(&resume_query("ListIdentifiers", &unsigned))
+            .unwrap();
+        assert_eq!(error_code(&xml), ErrorCode::BadResumptionToken);
+    }
+
+    #[test]
+    fn test_forged_tokens_are_validated() {
+        let provider = provider();
+        let forge = |args: ListRecordsArgs| {
+            let state = TokenState::new(Verb::ListRecords, args, "2", 2);
+            resume_query("ListRecords", &TokenCodec::new().encode(&state))
+        };
+
+        let xml = provider
+            .handle_query(&forge(ListRecordsArgs::new("oai_dc").set("odd")))
+            .unwrap();
+        let list = ListRecordsResponse::new(&xml).unwrap().payload.unwrap();
+        assert_eq!(list.record[0].header.identifier, "oai:example.org:5");
+
+        for args in [
+            ListRecordsArgs::new("marc21"),
+            ListRecordsArgs::new("oai_dc").from("yesterday"),
+            ListRecordsArgs::new("oai_dc").from("2024-01-01T00:00:00Z"),
+            ListRecordsArgs::new("oai_dc")
+                .from("2024-01-03")
+                .until("2024-01-01"),
+        ] {
+            let xml = provider.handle_query(&forge(args.clone())).unwrap();
+            assert_eq!(error_code(&xml), ErrorCode::BadResumptionToken, "{args:?}");
+            assert!(xml.contains("invalid arguments"));
+        }
+    }
+
+    // Titles as a "title" format, refusing records 1 and 2
+    #[cfg(feature = "formats")]
+    fn converting() -> Provider<TestRepository> {
+        let title = MetadataFormat {
+            metadata_prefix: "title".to_string(),
+            metadata_namespace: "https://example.org/title".to_string(),
+            schema: "https://example.org/title.xsd".to_string(),
+        };
+        provider().converter(title, "oai_dc", |record: &Record| {
+            let title = record.parse_dc().ok()?.title[0].value.clone();
+            let refused = title.starts_with("Record 1") || title.starts_with("Record 2");
+            (!refused).then(|| {
+                format!(
+                    r#"<title xmlns="https://example.org/title">{}</title>"#,
+                    title.replace('&', "&amp;")
+                )
+            })
+        })
+    }
+
+    #[test]
+    #[cfg(feature = "formats")]
+    fn test_converted_formats() {
+        let provider = converting();
+        let prefixes = |query: &str| -> Vec<String> {
+            let xml = provider.handle_query(query).unwrap();
+            ListMetadataFormatsResponse::new(&xml)
+                .unwrap()
+                .payload
+                .unwrap()
+                .metadata_format
+                .into_iter()
+                .map(|f| f.metadata_prefix)
+                .collect()
+        };
+        assert_eq!(prefixes("verb=ListMetadataFormats"), ["oai_dc", "title"]);
+        assert_eq!(
+            prefixes("verb=ListMetadataFormats&identifier=oai:example.org:3"),
+            ["oai_dc", "title"]
+        );
+        assert_eq!(
+            prefixes("verb=ListMetadataFormats&identifier=oai:example.org:1"),
+            ["oai_dc"]
+        );
+
+        let xml = provider
+            .handle_query("verb=GetRecord&identifier=oai:example.org:3&metadataPrefix=title")
+            .unwrap();
+        let record = GetRecordResponse::new(&xml).unwrap().into_record().unwrap();
+        assert!(
+            record
+                .metadata
+                .ends_with(r#"xmlns="https://example.org/title">Record 3 &amp; more</title>"#)
+        );
+        assert_eq!(record.header.set_spec, ["odd"]);
+
+        let xml = provider
+            .handle_query("verb=GetRecord&identifier=oai:example.org:1&metadataPrefix=title")
+            .unwrap();
+        assert_eq!(error_code(&xml), ErrorCode::CannotDisseminateFormat);
+    }
+
+    #[test]
+    #[cfg(feature = "formats")]
+    fn test_converted_lists() {
+        let provider = converting();
+
+        // The first source page is refused entirely, so the first page is the second
+        let xml = provider
+            .handle_query("verb=ListRecords&metadataPrefix=title")
+            .unwrap();
+        let list = ListRecordsResponse::new(&xml).unwrap().payload.unwrap();
+        let identifiers: Vec<_> = list
+            .record
+            .iter()
+            .map(|r| r.header.identifier.as_str())
+            .collect();
+        assert_eq!(identifiers, ["oai:example.org:3", "oai:example.org:4"]);
+        assert!(list.record[1].is_deleted());
+        let token = list.resumption_token.unwrap();
+        assert_eq!(token.complete_list_size, None);
+
+        let xml = provider
+            .handle_query(&resume_query("ListRecords", &token.token))
+            .unwrap();
+        let list = ListRecordsResponse::new(&xml).unwrap().payload.unwrap();
+        assert_eq!(list.record[0].header.identifier, "oai:example.org:5");
+        assert_eq!(list.resumption_token.unwrap().cursor, Some(2));
+
+        let xml = provider
+            .handle_query("verb=ListIdentifiers&metadataPrefix=title&set=odd")
+            .unwrap();
+        let list = ListIdentifiersResponse::new(&xml).unwrap().payload.unwrap();
+        let identifiers: Vec<_> = list.header.iter().map(|h| h.identifier.as_str()).collect();
+        assert_eq!(identifiers, ["oai:example.org:3"]);
+        assert!(!list.resumption_token.unwrap().token.is_empty());
+
+        let xml = provider
+            .handle_query("verb=ListRecords&metadataPrefix=title&until=2024-01-02")
+            .unwrap();
+        assert_eq!(error_code(&xml), ErrorCode::NoRecordsMatch);
+    }
+
+    #[test]
+    #[cfg(feature = "formats")]
+    fn test_converted_lists_read_a_bounded_number_of_pages() {
+        let title = MetadataFormat {
+            metadata_prefix: "title".to_string(),
+            metadata_namespace: "https://example.org/title".to_string(),
+            schema: "https://example.org/title.xsd".to_string(),
+        };
+        // One record a page, with the first three pages refused
+        let provider = Provider::new(TestRepository).page_size(1).converter(
+            title,
+            "oai_dc",
+            |record: &Record| {
+                let title = record.parse_dc().ok()?.title[0].value.clone();
+                (!["Record 1", "Record 2", "Record 3"]
+                    .iter()
+                    .any(|refused| title.starts_with(refused)))
+                .then(|| format!(r#"<title xmlns="https://example.org/title">{title}</title>"#))
+            },
+        );
+
+        // Three refused pages end the first response, empty but resumable
+        let xml = provider
+            .handle_query("verb=ListRecords&metadataPrefix=title")
+            .unwrap();
+        let list = ListRecordsResponse::new(&xml).unwrap().payload.unwrap();
+        assert!(list.record.is_empty());
+        let token = list.resumption_token.unwrap();
+        assert_eq!(token.cursor, Some(0));
+
+        let xml = provider
+            .handle_query(&resume_query("ListRecords", &token.token))
+            .unwrap();
+        let list = ListRecordsResponse::new(&xml).unwrap().payload.unwrap();
+        assert_eq!(list.record[0].header.identifier, "oai:example.org:4");
+        assert!(!list.resumption_token.unwrap().token.is_empty());
+    }
+}
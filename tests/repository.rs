@@ -0,0 +1,319 @@
+#![cfg(all(feature = "formats", feature = "provider"))]
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use oai_pmh::client::response::{ErrorCode, Header, Identify, MetadataFormat, Record, Set};
+    use oai_pmh::client::sets::SetTree;
+    use oai_pmh::client::transport::ProviderTransport;
+    use oai_pmh::provider::directory::DirectoryRepository;
+    use oai_pmh::provider::memory::MemoryRepository;
+    use oai_pmh::provider::{Provider, Repository};
+    use oai_pmh::{Client, Error, ListMetadataFormatsArgs, ListRecordsArgs};
+
+    fn identify() -> Identify {
+        Identify {
+            repository_name: "Local".to_string(),
+            base_url: "https://example.org/oai".to_string(),
+            protocol_version: "2.0".to_string(),
+            admin_email: vec!["admin@example.org".to_string()],
+            earliest_datestamp: "2024-01-01".to_string(),
+            deleted_record: "persistent".to_string(),
+            granularity: "YYYY-MM-DD".to_string(),
+            compression: Vec::new(),
+            description: Vec::new(),
+        }
+    }
+
+    fn oai_dc() -> MetadataFormat {
+        MetadataFormat {
+            metadata_prefix: "oai_dc".to_string(),
+            metadata_namespace: "http://www.openarchives.org/OAI/2.0/oai_dc/".to_string(),
+            schema: "http://www.openarchives.org/OAI/2.0/oai_dc.xsd".to_string(),
+        }
+    }
+
+    fn marc21() -> MetadataFormat {
+        MetadataFormat {
+            metadata_prefix: "marc21".to_string(),
+            metadata_namespace: "http://www.loc.gov/MARC21/slim".to_string(),
+            schema: "http://www.loc.gov/standards/marcxml/schema/MARC21slim.xsd".to_string(),
+        }
+    }
+
+    fn dc(title: &str) -> String {
+        format!(
+            r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>{title}</dc:title></oai_dc:dc>"#
+        )
+    }
+
+    fn marc(title: &str) -> String {
+        format!(
+            r#"<record xmlns="http://www.loc.gov/MARC21/slim"><leader>00000nam a2200000 a 4500</leader><datafield tag="245" ind1="0" ind2="0"><subfield code="a">{title}</subfield></datafield></record>"#
+        )
+    }
+
+    fn record(n: usize, metadata: String) -> Record {
+        let deleted = n == 3;
+        Record {
+            header: Header {
+                identifier: format!("oai:example.org:{n}"),
+                datestamp: format!("2024-01-0{n}"),
+                status: deleted.then(|| "deleted".to_string()),
+                set_spec: if n % 2 == 1 {
+                    vec!["odd".to_string()]
+                } else {
+                    Vec::new()
+                },
+            },
+            metadata: if deleted { String::new() } else { metadata },
+            about: String::new(),
+        }
+    }
+
+    // Five records in oai_dc, the first two also in marc21; record 3 is deleted
+    fn memory() -> MemoryRepository {
+        MemoryRepository::new(identify())
+            .format(oai_dc())
+            .format(marc21())
+            .set(Set {
+                set_spec: "odd".to_string(),
+                set_name: "Odd records".to_string(),
+                set_description: Vec::new(),
+            })
+            .records(
+                "oai_dc",
+                (1..=5).map(|n| record(n, dc(&format!("Record {n}")))),
+            )
+            .records(
+                "marc21",
+                (1..=2).map(|n| record(n, marc(&format!("Record {n}")))),
+            )
+    }
+
+    fn client<R: Repository + Send + Sync + 'static>(repository: R) -> Client {
+        let transport = ProviderTransport::new(Provider::new(repository).page_size(2));
+        Client::with_transport("https://example.org/oai", transport).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_memory_harvest() {
+        let client = client(memory());
+
+        let mut records = client
+            .records(ListRecordsArgs::new("oai_dc"))
+            .await
+            .unwrap();
+        let mut harvested = Vec::new();
+        while let Some(record) = records.next().await {
+            let record = record.unwrap();
+            let title = if record.is_deleted() {
+                None
+            } else {
+                Some(record.parse_dc().unwrap().title[0].value.clone())
+            };
+            harvested.push((record.header.identifier, title));
+        }
+
+        assert_eq!(harvested.len(), 5);
+        assert_eq!(harvested[0].1.as_deref(), Some("Record 1"));
+        assert_eq!(harvested[2], ("oai:example.org:3".to_string(), None));
+    }
+
+    #[tokio::test]
+    async fn test_memory_formats_and_sets() {
+        let client = client(memory());
+
+        let mut records = client
+            .records(ListRecordsArgs::new("marc21"))
+            .await
+            .unwrap();
+        let mut titles = Vec::new();
+        while let Some(record) = records.next().await {
+            let marc = record.unwrap().parse_marc().unwrap();
+            titles.push(marc.data_fields[0].subfields[0].value.clone());
+        }
+        assert_eq!(titles, ["Record 1", "Record 2"]);
+
+        let response = client
+            .list_metadata_formats(Some(ListMetadataFormatsArgs::new("oai:example.org:4")))
+            .await
+            .unwrap();
+        let formats = response.payload.unwrap().metadata_format;
+        assert_eq!(formats, [oai_dc()]);
+
+        let mut records = client
+            .records(ListRecordsArgs::new("oai_dc").set("odd"))
+            .await
+            .unwrap();
+        let mut identifiers = Vec::new();
+        while let Some(record) = records.next().await {
+            identifiers.push(record.unwrap().header.identifier);
+        }
+        assert_eq!(
+            identifiers,
+            [
+                "oai:example.org:1",
+                "oai:example.org:3",
+                "oai:example.org:5"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_cannot_disseminate() {
+        let client = client(memory());
+
+        let error = client
+            .get_record_as::<oai_pmh::formats::marcxml::MarcRecord>("oai:example.org:4")
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&error, Error::Oai(e) if e.code == ErrorCode::CannotDisseminateFormat),
+            "{error:?}"
+        );
+    }
+
+    // Write files below a fresh temporary directory
+    fn tree(name: &str, files: &[(&str, String)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("oai-pmh-test-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (path, xml) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(
+                path,
+                format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{xml}"),
+            )
+            .unwrap();
+        }
+        root
+    }
+
+    #[tokio::test]
+    async fn test_directory_harvest() {
+        let root = tree(
+            "directory",
+            &[
+                ("readme.oai_dc.xml", dc("Readme")),
+                ("theses/smith.oai_dc.xml", dc("Smith")),
+                ("theses/smith.marc21.xml", marc("Smith")),
+                ("theses/2024/jones.oai_dc.xml", dc("Jones")),
+            ],
+        );
+        let repository = DirectoryRepository::open(&root, identify())
+            .unwrap()
+            .identifier_prefix("oai:example.org:")
+            .format(oai_dc())
+            .format(marc21());
+        let client = client(repository);
+
+        let mut records = client
+            .records(ListRecordsArgs::new("oai_dc"))
+            .await
+            .unwrap();
+        let mut harvested = Vec::new();
+        while let Some(record) = records.next().await {
+            let record = record.unwrap();
+            let title = record.parse_dc().unwrap().title[0].value.clone();
+            harvested.push((record.header.identifier, record.header.set_spec, title));
+        }
+        assert_eq!(
+            harvested,
+            [
+                (
+                    "oai:example.org:readme".to_string(),
+                    vec![],
+                    "Readme".to_string()
+                ),
+                (
+                    "oai:example.org:theses/2024/jones".to_string(),
+                    vec!["theses:2024".to_string()],
+                    "Jones".to_string()
+                ),
+                (
+                    "oai:example.org:theses/smith".to_string(),
+                    vec!["theses".to_string()],
+                    "Smith".to_string()
+                ),
+            ]
+        );
+
+        let mut sets = client.list_sets().await.unwrap();
+        let page = sets.next().await.unwrap().unwrap();
+        let specs: Vec<String> = page
+            .payload
+            .unwrap()
+            .set
+            .into_iter()
+            .map(|s| s.set_spec)
+            .collect();
+        assert_eq!(specs, ["theses", "theses:2024"]);
+
+        let record = client
+            .get_record_as::<oai_pmh::formats::marcxml::MarcRecord>("oai:example.org:theses/smith")
+            .await
+            .unwrap();
+        assert_eq!(record.header.identifier, "oai:example.org:theses/smith");
+    }
+
+    #[tokio::test]
+    async fn test_set_hierarchy() {
+        // A repository without sets gives an empty tree
+        let empty = client(MemoryRepository::new(identify()).format(oai_dc()));
+        let sets = SetTree::from_stream(empty.list_sets().await.unwrap())
+            .await
+            .unwrap();
+        assert!(sets.is_empty());
+
+        let root = tree(
+            "hierarchy",
+            &[
+                ("theses/smith.oai_dc.xml", dc("Smith")),
+                ("theses/2024/jones.oai_dc.xml", dc("Jones")),
+                ("theses/2024/spring/lee.oai_dc.xml", dc("Lee")),
+                ("articles/brown.oai_dc.xml", dc("Brown")),
+            ],
+        );
+        let repository = DirectoryRepository::open(&root, identify())
+            .unwrap()
+            .identifier_prefix("oai:example.org:")
+            .format(oai_dc());
+        let client = client(repository);
+
+        // Records in subsets are also in the parent set
+        let mut records = client
+            .records(ListRecordsArgs::new("oai_dc").set("theses"))
+            .await
+            .unwrap();
+        let mut identifiers = Vec::new();
+        while let Some(record) = records.next().await {
+            identifiers.push(record.unwrap().header.identifier);
+        }
+        assert_eq!(
+            identifiers,
+            [
+                "oai:example.org:theses/2024/jones",
+                "oai:example.org:theses/2024/spring/lee",
+                "oai:example.org:theses/smith"
+            ]
+        );
+
+        let sets = SetTree::from_stream(client.list_sets().await.unwrap())
+            .await
+            .unwrap();
+        let specs = |sets: Vec<&Set>| -> Vec<String> {
+            sets.into_iter().map(|s| s.set_spec.clone()).collect()
+        };
+        assert_eq!(specs(sets.roots().collect()), ["articles", "theses"]);
+        assert_eq!(specs(sets.children("theses").collect()), ["theses:2024"]);
+        assert_eq!(
+            specs(sets.ancestors("theses:2024:spring")),
+            ["theses", "theses:2024"]
+        );
+        assert_eq!(sets.depth("theses:2024:spring"), 2);
+        assert_eq!(sets.get("theses:2024").unwrap().set_name, "2024");
+    }
+}
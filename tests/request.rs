@@ -1,3 +1,5 @@
+#![cfg(feature = "reqwest")]
+
 #[cfg(test)]
 mod tests {
     use mockito::{Matcher, ServerGuard};
@@ -194,4 +196,70 @@ mod tests {
 
         mock.assert();
     }
+
+    #[tokio::test]
+    #[cfg(feature = "formats")]
+    async fn test_records_yields_individual_records() {
+        let metadata_prefix = "marc21";
+
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = setup_mock_server(
+            &mut server,
+            "tests/fixtures/list_records_marc21.xml",
+            vec![
+                Matcher::UrlEncoded("verb".into(), "ListRecords".into()),
+                Matcher::UrlEncoded("metadataPrefix".into(), metadata_prefix.into()),
+            ],
+        );
+
+        let args = ListRecordsArgs::new(metadata_prefix);
+        let client = Client::new(&server.url()).unwrap();
+
+        let mut titles = Vec::new();
+        let mut deleted = Vec::new();
+        let mut stream = client.records(args).await.unwrap();
+        while let Some(record) = stream.next().await {
+            let record = record.unwrap();
+            if record.header.status.as_deref() == Some("deleted") {
+                deleted.push(record.header.identifier);
+                continue;
+            }
+
+            let marc = record.parse_marc().unwrap();
+            titles.push(marc.subfield("245", 'a').unwrap().to_string());
+        }
+
+        assert_eq!(titles, vec!["Arithmetic /", "The waves /"]);
+        assert_eq!(deleted, vec!["oai:catalog.example.edu:991000789"]);
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_records_surfaces_oai_errors() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = setup_mock_server(
+            &mut server,
+            "tests/fixtures/err_bad_prefix.xml",
+            vec![Matcher::UrlEncoded("verb".into(), "ListRecords".into())],
+        );
+
+        let client = Client::new(&server.url()).unwrap();
+        let mut stream = client.records(ListRecordsArgs::new("bogus")).await.unwrap();
+
+        match stream.next().await {
+            Some(Err(oai_pmh::Error::Oai(error))) => {
+                assert_eq!(
+                    error.code,
+                    oai_pmh::client::response::ErrorCode::CannotDisseminateFormat
+                );
+            }
+            other => panic!("expected OAI-PMH error, got {other:?}"),
+        }
+        assert!(stream.next().await.is_none());
+
+        mock.assert();
+    }
 }
@@ -0,0 +1,129 @@
+#![cfg(feature = "sqlite")]
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use oai_pmh::client::response::{Header, Identify, MetadataFormat, Record, Set};
+    use oai_pmh::client::transport::ProviderTransport;
+    use oai_pmh::provider::Provider;
+    use oai_pmh::provider::datestamp::Datestamp;
+    use oai_pmh::provider::sqlite::SqliteRepository;
+    use oai_pmh::provider::token::TokenCodec;
+    use oai_pmh::{Client, ListIdentifiersArgs, ListRecordsArgs};
+
+    // Seven Dublin Core records, even ones in set "even"; record 6 is deleted
+    fn repository() -> SqliteRepository {
+        let identify = Identify {
+            repository_name: "Aggregation".to_string(),
+            base_url: "https://example.org/oai".to_string(),
+            protocol_version: "2.0".to_string(),
+            admin_email: vec!["admin@example.org".to_string()],
+            earliest_datestamp: "2024-01-01".to_string(),
+            deleted_record: "persistent".to_string(),
+            granularity: "YYYY-MM-DD".to_string(),
+            compression: Vec::new(),
+            description: Vec::new(),
+        };
+        let repository = SqliteRepository::open_in_memory(identify).unwrap();
+        repository
+            .add_format(&MetadataFormat {
+                metadata_prefix: "oai_dc".to_string(),
+                metadata_namespace: "http://www.openarchives.org/OAI/2.0/oai_dc/".to_string(),
+                schema: "http://www.openarchives.org/OAI/2.0/oai_dc.xsd".to_string(),
+            })
+            .unwrap();
+        repository
+            .add_set(&Set {
+                set_spec: "even".to_string(),
+                set_name: "Even records".to_string(),
+                set_description: Vec::new(),
+            })
+            .unwrap();
+
+        let records: Vec<Record> = (1..=7)
+            .map(|n| Record {
+                header: Header {
+                    identifier: format!("oai:example.org:{n}"),
+                    datestamp: format!("2024-01-0{n}"),
+                    status: None,
+                    set_spec: if n % 2 == 0 {
+                        vec!["even".to_string()]
+                    } else {
+                        Vec::new()
+                    },
+                },
+                metadata: format!(
+                    r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Record {n}</dc:title></oai_dc:dc>"#
+                ),
+                about: String::new(),
+            })
+            .collect();
+        repository.put_records("oai_dc", &records).unwrap();
+        repository
+            .delete_record(
+                "oai:example.org:6",
+                Datestamp::parse("2024-01-08").unwrap().0,
+            )
+            .unwrap();
+        repository
+    }
+
+    fn client() -> Client {
+        let codec = TokenCodec::new()
+            .secret("secret")
+            .ttl(Duration::from_secs(600));
+        let provider = Provider::new(repository()).page_size(3).token_codec(codec);
+        Client::with_transport("https://example.org/oai", ProviderTransport::new(provider)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_harvest_with_signed_tokens() {
+        let client = client();
+
+        let mut records = client
+            .records(ListRecordsArgs::new("oai_dc"))
+            .await
+            .unwrap();
+        let mut harvested = Vec::new();
+        while let Some(record) = records.next().await {
+            let record = record.unwrap();
+            harvested.push((record.header.identifier.clone(), record.is_deleted()));
+        }
+
+        // The deletion is stamped after the other records
+        let identifiers: Vec<&str> = harvested.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(
+            identifiers,
+            [
+                "oai:example.org:1",
+                "oai:example.org:2",
+                "oai:example.org:3",
+                "oai:example.org:4",
+                "oai:example.org:5",
+                "oai:example.org:7",
+                "oai:example.org:6",
+            ]
+        );
+        assert!(harvested[6].1);
+    }
+
+    #[tokio::test]
+    async fn test_selective_identifiers() {
+        let client = client();
+
+        let args = ListIdentifiersArgs::new("oai_dc")
+            .set("even")
+            .from("2024-01-03");
+        let mut pages = client.list_identifiers(args).await.unwrap();
+        let mut headers = Vec::new();
+        while let Some(page) = pages.next().await {
+            headers.extend(page.unwrap().payload.unwrap().header);
+        }
+
+        let identifiers: Vec<&str> = headers.iter().map(|h| h.identifier.as_str()).collect();
+        assert_eq!(identifiers, ["oai:example.org:4", "oai:example.org:6"]);
+        assert_eq!(headers[1].status.as_deref(), Some("deleted"));
+        assert_eq!(headers[1].set_spec, ["even"]);
+    }
+}
@@ -0,0 +1,108 @@
+#![cfg(all(feature = "formats", feature = "provider"))]
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use oai_pmh::client::response::ErrorCode;
+    use oai_pmh::client::static_repository::StaticRepository;
+    use oai_pmh::client::transport::ProviderTransport;
+    use oai_pmh::formats::marcxml::MarcRecord;
+    use oai_pmh::provider::Provider;
+    use oai_pmh::provider::gateway::StaticGateway;
+    use oai_pmh::{Client, Error, ListRecordsArgs};
+
+    const FIXTURE: &str = "tests/fixtures/static_repository.xml";
+
+    fn provider() -> Provider<StaticGateway> {
+        let gateway = StaticGateway::open(FIXTURE)
+            .unwrap()
+            .source("http://an.oai.org/ma/mini.xml")
+            .gateway_admin("gateway@example.org");
+        Provider::new(gateway).page_size(2)
+    }
+
+    fn client() -> Client {
+        let transport = ProviderTransport::new(provider());
+        Client::with_transport(
+            "http://gateway.institution.org/oai/an.oai.org/ma/mini.xml",
+            transport,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let repository = StaticRepository::parse(&fs::read_to_string(FIXTURE).unwrap()).unwrap();
+
+        assert_eq!(repository.identify.granularity, "YYYY-MM-DD");
+        assert!(
+            repository.identify.description[0]
+                .contains("<repositoryIdentifier>an.oai.org</repositoryIdentifier>")
+        );
+        let prefixes: Vec<&str> = repository
+            .metadata_formats
+            .iter()
+            .map(|f| f.metadata_prefix.as_str())
+            .collect();
+        assert_eq!(prefixes, ["oai_dc", "marc21"]);
+        assert_eq!(repository.records["oai_dc"].len(), 3);
+
+        // The marc prefix is declared on ListRecords, outside the fragment
+        let marc = repository.records["marc21"][0].parse_marc().unwrap();
+        assert_eq!(
+            marc.data_fields[0].subfields[0].value,
+            "Using Structural Metadata to Localize Experience of Digital Content"
+        );
+    }
+
+    #[test]
+    fn test_gateway_description() {
+        let xml = provider().handle_query("verb=Identify").unwrap();
+
+        assert!(xml.contains("<repositoryName>Demo repository</repositoryName>"));
+        assert!(xml.contains(
+            r#"<gateway xmlns="http://www.openarchives.org/OAI/2.0/gateway/"><source>http://an.oai.org/ma/mini.xml</source>"#
+        ));
+        assert!(xml.contains("<gatewayAdmin>gateway@example.org</gatewayAdmin>"));
+    }
+
+    #[tokio::test]
+    async fn test_gateway_harvest() {
+        let client = client();
+
+        let mut records = client
+            .records(ListRecordsArgs::new("oai_dc").from("2002-09-20"))
+            .await
+            .unwrap();
+        let mut titles = Vec::new();
+        while let Some(record) = records.next().await {
+            titles.push(record.unwrap().parse_dc().unwrap().title[0].value.clone());
+        }
+        assert_eq!(titles, ["Grassroots Harvesting", "Static Repositories"]);
+
+        let record = client
+            .get_record_as::<MarcRecord>("oai:an.oai.org:ma/mini.xml:1")
+            .await
+            .unwrap();
+        assert_eq!(record.header.datestamp, "2002-09-19");
+    }
+
+    #[tokio::test]
+    async fn test_gateway_errors() {
+        let client = client();
+
+        let error = client
+            .get_record_as::<MarcRecord>("oai:an.oai.org:ma/mini.xml:2")
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&error, Error::Oai(e) if e.code == ErrorCode::CannotDisseminateFormat),
+            "{error:?}"
+        );
+
+        let mut sets = client.list_sets().await.unwrap();
+        let page = sets.next().await.unwrap().unwrap();
+        assert_eq!(page.error.unwrap().code, ErrorCode::NoSetHierarchy);
+    }
+}
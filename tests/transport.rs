@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod tests {
+    use oai_pmh::client::transport::{
+        BoxFuture, HttpRequest, HttpResponse, MemoryTransport, Transport,
+    };
+    use oai_pmh::{Client, Error, GetRecordArgs, ListIdentifiersArgs, Result};
+
+    const ENDPOINT: &str = "https://test.archivesspace.org/oai";
+    const TOKEN: &str = "eyJtZXRhZGF0YV9wcmVmaXgiOiJvYWlfZWFkIiwiZnJvbSI6IjE5NzAtMDEtMDEgMDA6MDA6MDAgVVRDIiwidW50aWwiOiIyMDI1LTExLTI2IDIxOjU4OjE1IFVUQyIsInN0YXRlIjoicHJvZHVjaW5nX3JlY29yZHMiLCJsYXN0X2RlbGV0ZV9pZCI6MCwicmVtYWluaW5nX3R5cGVzIjp7IlJlc291cmNlIjoyfSwiaXNzdWVfdGltZSI6MTc2NDE5NDI5NjA2Mn0=";
+
+    fn fixture(name: &str) -> String {
+        std::fs::read_to_string(format!("tests/fixtures/{name}")).expect("Failed to load fixture")
+    }
+
+    #[tokio::test]
+    async fn test_get_record_from_memory() {
+        let transport = MemoryTransport::new().with_response(
+            "verb=GetRecord&identifier=oai:archivesspace:/repositories/2/resources/2&metadataPrefix=oai_ead",
+            fixture("get_record.xml"),
+        );
+
+        let client = Client::with_transport(ENDPOINT, transport).unwrap();
+        let args = GetRecordArgs::new("oai:archivesspace:/repositories/2/resources/2", "oai_ead");
+        let response = client.get_record(args).await.unwrap();
+
+        let record = response.payload.unwrap().record;
+        assert_eq!(record.header.datestamp, "2025-11-11T14:28:08Z");
+        assert!(record.metadata.contains("<ead"));
+    }
+
+    #[tokio::test]
+    async fn test_list_identifiers_follows_resumption_token() {
+        let xml = fixture("list_identifiers.xml");
+        let transport = MemoryTransport::new()
+            .with_response("verb=ListIdentifiers&metadataPrefix=oai_ead", xml.clone())
+            .with_response(
+                &format!(
+                    "verb=ListIdentifiers&resumptionToken={}",
+                    TOKEN.replace('=', "%3D")
+                ),
+                xml.replace(TOKEN, ""),
+            );
+
+        let client = Client::with_transport(ENDPOINT, transport).unwrap();
+        let mut stream = client
+            .list_identifiers(ListIdentifiersArgs::new("oai_ead"))
+            .await
+            .unwrap();
+
+        let mut pages = 0;
+        while let Some(response) = stream.next().await {
+            let headers = response.unwrap().payload.unwrap().header;
+            assert_eq!(
+                headers[0].identifier,
+                "oai:archivesspace:/repositories/2/resources/2"
+            );
+            pages += 1;
+        }
+        assert_eq!(pages, 2);
+    }
+
+    #[tokio::test]
+    async fn test_resume_list_identifiers() {
+        let xml = fixture("list_identifiers.xml");
+        let transport = MemoryTransport::new().with_response(
+            &format!(
+                "verb=ListIdentifiers&resumptionToken={}",
+                TOKEN.replace('=', "%3D")
+            ),
+            xml.replace(TOKEN, ""),
+        );
+
+        let client = Client::with_transport(ENDPOINT, transport).unwrap();
+        let mut stream = client.resume_list_identifiers(TOKEN).await.unwrap();
+
+        let page = stream.next().await.unwrap().unwrap();
+        assert!(!page.payload.unwrap().header.is_empty());
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_request_is_transport_error() {
+        let client = Client::with_transport(ENDPOINT, MemoryTransport::new()).unwrap();
+
+        let err = client.identify().await.unwrap_err();
+        assert!(matches!(err, Error::Transport(_)));
+    }
+
+    #[tokio::test]
+    async fn test_custom_transport() {
+        // Echoes the request headers back as a non-XML body
+        struct Echo;
+
+        impl Transport for Echo {
+            fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+                Box::pin(async move {
+                    let body = request
+                        .headers
+                        .iter()
+                        .map(|(k, v)| format!("{k}: {v}"))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    Ok(HttpResponse::new(200, body).with_header("Content-Type", "text/plain"))
+                })
+            }
+        }
+
+        let client = Client::with_transport(ENDPOINT, Echo).unwrap();
+        match client.identify().await {
+            Err(Error::UnexpectedResponse { content_type, body }) => {
+                assert_eq!(content_type.as_deref(), Some("text/plain"));
+                assert!(body.contains("Accept: text/xml"));
+                assert!(body.contains("User-Agent: oai-pmh-rs/"));
+            }
+            other => panic!("expected unexpected response, got {other:?}"),
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_identify_from_memory() {
+        let transport =
+            MemoryTransport::new().with_response("verb=Identify", fixture("identify.xml"));
+
+        let client = oai_pmh::blocking::Client::with_transport(ENDPOINT, transport).unwrap();
+        let response = client.identify().unwrap();
+        assert_eq!(response.payload.unwrap().protocol_version, "2.0");
+    }
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "formats")]
+
+#[cfg(test)]
+mod tests {
+    use oai_pmh::client::transport::MemoryTransport;
+    use oai_pmh::formats::ead::Ead;
+    use oai_pmh::formats::marcxml::MarcRecord;
+    use oai_pmh::formats::oai_dc::DublinCore;
+    use oai_pmh::formats::{self, MetadataFormat};
+    use oai_pmh::{Client, Error, ListRecordsArgs, Result};
+    use serde::Deserialize;
+
+    const ENDPOINT: &str = "https://test.archivesspace.org/oai";
+    const IDENTIFIER: &str = "oai:archivesspace:/repositories/2/resources/2";
+
+    fn fixture(name: &str) -> String {
+        std::fs::read_to_string(format!("tests/fixtures/{name}")).expect("Failed to load fixture")
+    }
+
+    fn get_record(prefix: &str, fixture_name: &str) -> Client {
+        let transport = MemoryTransport::new().with_response(
+            &format!("verb=GetRecord&identifier={IDENTIFIER}&metadataPrefix={prefix}"),
+            fixture(fixture_name),
+        );
+        Client::with_transport(ENDPOINT, transport).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_record_as() {
+        let client = get_record("oai_ead", "get_record.xml");
+
+        let record = client.get_record_as::<Ead>(IDENTIFIER).await.unwrap();
+        assert_eq!(record.header.identifier, IDENTIFIER);
+        assert_eq!(
+            record.metadata.unwrap().archdesc.did.unit_id[0].value,
+            "arc.A0059"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_record_as_reports_oai_error() {
+        let client = get_record("oai_ead", "err_not_found.xml");
+
+        let err = client.get_record_as::<Ead>(IDENTIFIER).await.unwrap_err();
+        assert!(matches!(err, Error::Oai(_)));
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_carries_record_identifier() {
+        // An EAD record served where Dublin Core was requested
+        let client = get_record("oai_dc", "get_record.xml");
+
+        let err = client
+            .get_record_as::<DublinCore>(IDENTIFIER)
+            .await
+            .unwrap_err();
+        match err {
+            Error::Record { identifier, source } => {
+                assert_eq!(identifier, IDENTIFIER);
+                assert!(matches!(*source, Error::Metadata(_)));
+            }
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_as() {
+        let transport = MemoryTransport::new().with_response(
+            "verb=ListRecords&metadataPrefix=marc21",
+            fixture("list_records_marc21.xml"),
+        );
+        let client = Client::with_transport(ENDPOINT, transport).unwrap();
+
+        let mut stream = client
+            .records_as::<MarcRecord>(ListRecordsArgs::new(MarcRecord::PREFIX))
+            .await
+            .unwrap();
+
+        let mut records = Vec::new();
+        while let Some(record) = stream.next().await {
+            records.push(record.unwrap());
+        }
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            records[0].header.identifier,
+            "oai:catalog.example.edu:991000123"
+        );
+        assert!(records[0].metadata.as_ref().unwrap().field("245").is_some());
+        assert!(records[1].metadata.is_some());
+
+        // Deleted records have no metadata to parse
+        assert_eq!(records[2].header.status.as_deref(), Some("deleted"));
+        assert!(records[2].metadata.is_none());
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct SimpleDc {
+        #[serde(rename = "title")]
+        titles: Vec<String>,
+    }
+
+    impl MetadataFormat for SimpleDc {
+        const PREFIX: &'static str = "oai_dc";
+        const NAMESPACE: &'static str = "http://www.openarchives.org/OAI/2.0/oai_dc/";
+
+        fn from_metadata(xml: &str) -> Result<Self> {
+            formats::deserialize(xml)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_as_serde_type() {
+        let xml = fixture("list_records.xml");
+        let transport =
+            MemoryTransport::new().with_response("verb=ListRecords&metadataPrefix=oai_dc", xml);
+        let client = Client::with_transport(ENDPOINT, transport).unwrap();
+
+        let mut stream = client
+            .records_as::<SimpleDc>(ListRecordsArgs::new(SimpleDc::PREFIX))
+            .await
+            .unwrap();
+
+        let record = stream.next().await.unwrap().unwrap();
+        assert!(!record.metadata.unwrap().titles.is_empty());
+    }
+}
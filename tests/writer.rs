@@ -0,0 +1,200 @@
+#![cfg(feature = "provider")]
+
+#[cfg(test)]
+mod tests {
+    use oai_pmh::client::response::{
+        ErrorCode, GetRecordResponse, Header, Identify, IdentifyResponse, ListIdentifiersResponse,
+        ListMetadataFormatsResponse, ListRecordsResponse, ListSetsResponse, MetadataFormat, Record,
+        ResponseError, ResumptionToken, Set,
+    };
+    use oai_pmh::provider::datestamp::Datestamp;
+    use oai_pmh::provider::writer::ResponseWriter;
+
+    const DC: &str = r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title xml:lang="en">Maps &amp; charts</dc:title><dc:creator>Mercator, Gerardus</dc:creator></oai_dc:dc>"#;
+
+    fn writer(verb: &str) -> ResponseWriter {
+        let (date, _) = Datestamp::parse("2024-06-01T12:00:00Z").unwrap();
+        ResponseWriter::new("https://example.org/oai")
+            .response_date(date)
+            .request([("verb", verb), ("metadataPrefix", "oai_dc")])
+    }
+
+    fn header(n: usize, deleted: bool) -> Header {
+        Header {
+            identifier: format!("oai:example.org:{n}"),
+            datestamp: format!("2024-01-0{n}T00:00:00Z"),
+            status: deleted.then(|| "deleted".to_string()),
+            set_spec: vec!["maps".to_string(), "maps:europe".to_string()],
+        }
+    }
+
+    fn record(n: usize, deleted: bool) -> Record {
+        Record {
+            header: header(n, deleted),
+            metadata: if deleted {
+                String::new()
+            } else {
+                DC.to_string()
+            },
+            about: String::new(),
+        }
+    }
+
+    fn token() -> ResumptionToken {
+        ResumptionToken {
+            token: "page=2&set=maps".to_string(),
+            expiration_date: Some("2024-06-02T12:00:00Z".to_string()),
+            complete_list_size: Some(3),
+            cursor: Some(0),
+        }
+    }
+
+    #[test]
+    fn test_identify_round_trip() {
+        let identify = Identify {
+            repository_name: "Maps <&> Charts".to_string(),
+            base_url: "https://example.org/oai".to_string(),
+            protocol_version: "2.0".to_string(),
+            admin_email: vec!["a@example.org".to_string(), "b@example.org".to_string()],
+            earliest_datestamp: "2000-01-01T00:00:00Z".to_string(),
+            deleted_record: "transient".to_string(),
+            granularity: "YYYY-MM-DDThh:mm:ssZ".to_string(),
+            compression: vec!["gzip".to_string()],
+            // Descriptions are not parsed by the client
+            description: Vec::new(),
+        };
+
+        let xml = ResponseWriter::new("https://example.org/oai")
+            .request([("verb", "Identify")])
+            .identify(&identify);
+        let response = IdentifyResponse::new(&xml).unwrap();
+
+        assert!(!response.is_err());
+        assert_eq!(response.request, "https://example.org/oai");
+        assert_eq!(response.payload.unwrap(), identify);
+        assert!(Datestamp::parse(&response.response_date).is_some());
+    }
+
+    #[test]
+    fn test_list_metadata_formats_round_trip() {
+        let formats = vec![
+            MetadataFormat {
+                metadata_prefix: "oai_dc".to_string(),
+                metadata_namespace: "http://www.openarchives.org/OAI/2.0/oai_dc/".to_string(),
+                schema: "http://www.openarchives.org/OAI/2.0/oai_dc.xsd".to_string(),
+            },
+            MetadataFormat {
+                metadata_prefix: "marc21".to_string(),
+                metadata_namespace: "http://www.loc.gov/MARC21/slim".to_string(),
+                schema: "http://www.loc.gov/standards/marcxml/schema/MARC21slim.xsd".to_string(),
+            },
+        ];
+
+        let xml = writer("ListMetadataFormats").list_metadata_formats(&formats);
+        let response = ListMetadataFormatsResponse::new(&xml).unwrap();
+        assert_eq!(response.payload.unwrap().metadata_format, formats);
+    }
+
+    #[test]
+    fn test_list_sets_round_trip() {
+        let sets = vec![
+            Set {
+                set_spec: "maps".to_string(),
+                set_name: "Maps".to_string(),
+                set_description: Vec::new(),
+            },
+            Set {
+                set_spec: "maps:europe".to_string(),
+                set_name: "Maps of Europe".to_string(),
+                set_description: Vec::new(),
+            },
+        ];
+
+        let xml = writer("ListSets").list_sets(&sets, Some(&token()));
+        let list = ListSetsResponse::new(&xml).unwrap().payload.unwrap();
+        assert_eq!(list.set, sets);
+        assert_eq!(list.resumption_token, Some(token()));
+    }
+
+    #[test]
+    fn test_get_record_round_trip() {
+        let record = record(1, false);
+        let xml = writer("GetRecord").get_record(&record);
+
+        // Metadata is embedded verbatim
+        assert!(xml.contains(&format!("<metadata>{DC}</metadata>")));
+
+        let parsed = GetRecordResponse::new(&xml).unwrap().into_record().unwrap();
+        assert_eq!(parsed.header, record.header);
+        #[cfg(feature = "formats")]
+        assert_eq!(parsed.parse_dc().unwrap(), record.parse_dc().unwrap());
+    }
+
+    #[test]
+    fn test_list_identifiers_round_trip() {
+        let headers = vec![header(1, false), header(2, true)];
+        let xml = writer("ListIdentifiers").list_identifiers(&headers, Some(&token()));
+
+        let list = ListIdentifiersResponse::new(&xml).unwrap().payload.unwrap();
+        assert_eq!(list.header, headers);
+        assert_eq!(list.resumption_token, Some(token()));
+    }
+
+    #[test]
+    fn test_list_records_round_trip() {
+        let records = vec![record(1, false), record(2, true), record(3, false)];
+        let xml = writer("ListRecords").list_records(&records, None);
+
+        let list = ListRecordsResponse::new(&xml).unwrap().payload.unwrap();
+        assert_eq!(list.record.len(), 3);
+        assert!(list.resumption_token.is_none());
+        for (parsed, record) in list.record.iter().zip(&records) {
+            assert_eq!(parsed.header, record.header);
+            assert_eq!(parsed.is_deleted(), record.is_deleted());
+            #[cfg(feature = "formats")]
+            if !record.is_deleted() {
+                assert_eq!(parsed.parse_dc().unwrap(), record.parse_dc().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixture_round_trip() {
+        let xml = std::fs::read_to_string("tests/fixtures/list_records_marc21.xml").unwrap();
+        let original = ListRecordsResponse::new(&xml).unwrap().payload.unwrap();
+
+        let written = writer("ListRecords")
+            .list_records(&original.record, original.resumption_token.as_ref());
+        let parsed = ListRecordsResponse::new(&written).unwrap().payload.unwrap();
+
+        assert_eq!(parsed.record.len(), original.record.len());
+        assert_eq!(parsed.resumption_token, original.resumption_token);
+        for (parsed, original) in parsed.record.iter().zip(&original.record) {
+            assert_eq!(parsed.header, original.header);
+            #[cfg(feature = "formats")]
+            if !original.is_deleted() {
+                assert_eq!(parsed.parse_marc().unwrap(), original.parse_marc().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_error_round_trip() {
+        let xml = ResponseWriter::new("https://example.org/oai")
+            .request([("verb", "ListRecords"), ("metadataPrefix", "oai_dc")])
+            .error(
+                ErrorCode::NoRecordsMatch,
+                "Nothing between <from> & <until>",
+            );
+
+        let response = ListRecordsResponse::new(&xml).unwrap();
+        assert!(response.payload.is_none());
+        assert_eq!(
+            response.error,
+            Some(ResponseError {
+                code: ErrorCode::NoRecordsMatch,
+                message: "Nothing between <from> & <until>".to_string(),
+            })
+        );
+    }
+}